@@ -0,0 +1,135 @@
+//! Well-known values of a network derived from its genesis files
+
+use pallas_traverse::wellknown::{GenesisValues, MAINNET_NETWORK_ID, TESTNET_NETWORK_ID};
+
+use crate::{byron, shelley};
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum Error {
+    #[error("shelley genesis lacks the {0}")]
+    MissingField(&'static str),
+
+    #[error("byron slot duration of {0}ms is not a positive whole number of seconds")]
+    InvalidSlotDuration(u64),
+}
+
+/// Builds the values of a custom network from its Byron and Shelley genesis
+/// files, given the epoch at which the network hard-forked into Shelley (zero
+/// for networks that start in Shelley or later).
+///
+/// Block hashes can't be derived from the genesis files, so the known hashes
+/// are left empty. Slot lengths are kept in seconds, so Byron slot durations
+/// that aren't a whole number of seconds are rejected.
+pub fn genesis_values(
+    byron: &byron::GenesisFile,
+    shelley: &shelley::GenesisFile,
+    shelley_hardfork_epoch: u64,
+) -> Result<GenesisValues, Error> {
+    let slot_duration = byron.block_version_data.slot_duration;
+
+    if slot_duration == 0 || !slot_duration.is_multiple_of(1000) {
+        return Err(Error::InvalidSlotDuration(slot_duration));
+    }
+
+    let byron_slot_length = (slot_duration / 1000) as u32;
+    // a Byron epoch lasts ten times the security parameter, in slots
+    let byron_epoch_slots = byron.protocol_consts.k as u64 * 10;
+
+    let shelley_slot_length = shelley
+        .slot_length
+        .ok_or(Error::MissingField("slot length"))?;
+    let shelley_epoch_length = shelley
+        .epoch_length
+        .ok_or(Error::MissingField("epoch length"))?;
+    let magic = shelley
+        .network_magic
+        .ok_or(Error::MissingField("network magic"))?;
+
+    let shelley_known_slot = shelley_hardfork_epoch * byron_epoch_slots;
+
+    let network_id = match shelley.network_id.as_deref() {
+        Some("Mainnet") => MAINNET_NETWORK_ID,
+        _ => TESTNET_NETWORK_ID,
+    };
+
+    Ok(GenesisValues {
+        magic: magic as u64,
+        network_id,
+        byron_epoch_length: byron_epoch_slots as u32 * byron_slot_length,
+        byron_slot_length,
+        byron_known_slot: 0,
+        byron_known_hash: String::new(),
+        byron_known_time: byron.start_time,
+        shelley_epoch_length: shelley_epoch_length * shelley_slot_length,
+        shelley_slot_length,
+        shelley_known_slot,
+        shelley_known_hash: String::new(),
+        shelley_known_time: byron.start_time + shelley_known_slot * byron_slot_length as u64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn test_data(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../test_data")
+            .join(name)
+    }
+
+    fn genesis_files(network: &str) -> (byron::GenesisFile, shelley::GenesisFile) {
+        let byron = byron::from_file(&test_data(&format!("{network}-byron-genesis.json")));
+        let shelley = shelley::from_file(&test_data(&format!("{network}-shelley-genesis.json")));
+
+        (byron.unwrap(), shelley.unwrap())
+    }
+
+    #[test]
+    fn genesis_files_match_hardcoded_values() {
+        for (network, shelley_hardfork_epoch, expected) in [
+            ("mainnet", 208, GenesisValues::mainnet()),
+            ("preview", 0, GenesisValues::preview()),
+        ] {
+            let (byron, shelley) = genesis_files(network);
+            let genesis = genesis_values(&byron, &shelley, shelley_hardfork_epoch).unwrap();
+
+            assert_eq!(genesis.magic, expected.magic);
+            assert_eq!(genesis.network_id, expected.network_id);
+            assert_eq!(genesis.byron_epoch_length, expected.byron_epoch_length);
+            assert_eq!(genesis.byron_slot_length, expected.byron_slot_length);
+            assert_eq!(genesis.byron_known_time, expected.byron_known_time);
+            assert_eq!(genesis.shelley_epoch_length, expected.shelley_epoch_length);
+            assert_eq!(genesis.shelley_slot_length, expected.shelley_slot_length);
+            assert_eq!(genesis.shelley_known_slot, expected.shelley_known_slot);
+            assert_eq!(genesis.shelley_known_time, expected.shelley_known_time);
+        }
+    }
+
+    #[test]
+    fn rejects_sub_second_byron_slots() {
+        let (mut byron, shelley) = genesis_files("preview");
+
+        for duration in [0, 500, 1500] {
+            byron.block_version_data.slot_duration = duration;
+
+            assert_eq!(
+                genesis_values(&byron, &shelley, 0).unwrap_err(),
+                Error::InvalidSlotDuration(duration)
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_incomplete_shelley_genesis() {
+        let (byron, mut shelley) = genesis_files("preview");
+        shelley.network_magic = None;
+
+        assert_eq!(
+            genesis_values(&byron, &shelley, 0).unwrap_err(),
+            Error::MissingField("network magic")
+        );
+    }
+}
@@ -1,6 +1,9 @@
-//! Genesis data structs and utilities
+//! Genesis, node config and topology structs and utilities
 
 pub mod alonzo;
 pub mod byron;
 pub mod conway;
+pub mod genesis;
+pub mod node;
 pub mod shelley;
+pub mod topology;
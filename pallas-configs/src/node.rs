@@ -0,0 +1,275 @@
+use pallas_crypto::hash::{Hash, Hasher};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+use crate::{alonzo, byron, conway, shelley};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("cannot read {0}: {1}")]
+    CannotRead(PathBuf, std::io::Error),
+
+    #[error("{era} genesis hash mismatch, config declares {declared} but file hashes to {actual}")]
+    GenesisHashMismatch {
+        era: &'static str,
+        declared: String,
+        actual: Hash<32>,
+    },
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum RequiresNetworkMagic {
+    RequiresNoMagic,
+    RequiresMagic,
+}
+
+/// Configuration of the node, as read from `config.json`. Logging and tracing
+/// options are ignored.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct ConfigFile {
+    pub protocol: Option<String>,
+    pub requires_network_magic: Option<RequiresNetworkMagic>,
+
+    pub byron_genesis_file: PathBuf,
+    pub byron_genesis_hash: Option<String>,
+    pub shelley_genesis_file: PathBuf,
+    pub shelley_genesis_hash: Option<String>,
+    pub alonzo_genesis_file: PathBuf,
+    pub alonzo_genesis_hash: Option<String>,
+    pub conway_genesis_file: Option<PathBuf>,
+    pub conway_genesis_hash: Option<String>,
+
+    #[serde(rename = "LastKnownBlockVersion-Major")]
+    pub last_known_block_version_major: Option<u64>,
+    #[serde(rename = "LastKnownBlockVersion-Minor")]
+    pub last_known_block_version_minor: Option<u64>,
+    #[serde(rename = "LastKnownBlockVersion-Alt")]
+    pub last_known_block_version_alt: Option<u64>,
+    pub max_known_major_protocol_version: Option<u64>,
+
+    pub test_shelley_hard_fork_at_epoch: Option<u64>,
+    pub test_allegra_hard_fork_at_epoch: Option<u64>,
+    pub test_mary_hard_fork_at_epoch: Option<u64>,
+    pub test_alonzo_hard_fork_at_epoch: Option<u64>,
+    pub test_babbage_hard_fork_at_epoch: Option<u64>,
+    pub test_conway_hard_fork_at_epoch: Option<u64>,
+
+    pub experimental_hard_forks_enabled: Option<bool>,
+    pub experimental_protocols_enabled: Option<bool>,
+}
+
+pub fn from_file(path: &std::path::Path) -> Result<ConfigFile, std::io::Error> {
+    let file = std::fs::File::open(path)?;
+    let reader = std::io::BufReader::new(file);
+    let parsed: ConfigFile = serde_json::from_reader(reader)?;
+
+    Ok(parsed)
+}
+
+/// Writes the canonical JSON form of a value: sorted keys and no whitespace
+fn write_canonical(value: &serde_json::Value, out: &mut Vec<u8>) {
+    match value {
+        serde_json::Value::Array(items) => {
+            out.push(b'[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(b',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(b']');
+        }
+        serde_json::Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by_key(|(key, _)| *key);
+
+            out.push(b'{');
+            for (i, (key, item)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(b',');
+                }
+                write_canonical(&serde_json::Value::String(key.clone()), out);
+                out.push(b':');
+                write_canonical(item, out);
+            }
+            out.push(b'}');
+        }
+        scalar => out.extend(serde_json::to_vec(scalar).unwrap_or_default()),
+    }
+}
+
+/// Hash of the Byron genesis file, taken over its canonical JSON form
+pub fn byron_genesis_hash(path: &Path) -> Result<Hash<32>, std::io::Error> {
+    let file = std::fs::File::open(path)?;
+    let value: serde_json::Value = serde_json::from_reader(std::io::BufReader::new(file))?;
+
+    let mut canonical = Vec::new();
+    write_canonical(&value, &mut canonical);
+
+    Ok(Hasher::<256>::hash(&canonical))
+}
+
+/// Hash of a Shelley-based genesis file, taken over its raw bytes
+pub fn genesis_hash(path: &Path) -> Result<Hash<32>, std::io::Error> {
+    let bytes = std::fs::read(path)?;
+
+    Ok(Hasher::<256>::hash(&bytes))
+}
+
+fn verify_hash(
+    era: &'static str,
+    declared: &Option<String>,
+    actual: Hash<32>,
+) -> Result<(), Error> {
+    match declared {
+        Some(declared) if !declared.eq_ignore_ascii_case(&actual.to_string()) => {
+            Err(Error::GenesisHashMismatch {
+                era,
+                declared: declared.clone(),
+                actual,
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Node config along with the genesis files it points to
+#[derive(Clone)]
+pub struct NetworkConfig {
+    pub config: ConfigFile,
+    pub byron: byron::GenesisFile,
+    pub shelley: shelley::GenesisFile,
+    pub alonzo: alonzo::GenesisFile,
+    pub conway: Option<conway::GenesisFile>,
+}
+
+impl NetworkConfig {
+    /// Loads the node config at the given path and the genesis files it
+    /// references, relative to the directory of the config. Each genesis file
+    /// is checked against the hash declared in the config, if any.
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let read_err = |path: &Path| {
+            let path = path.to_path_buf();
+            move |e| Error::CannotRead(path, e)
+        };
+
+        let config = from_file(path).map_err(read_err(path))?;
+        let dir = path.parent().unwrap_or(Path::new(""));
+
+        let byron_path = dir.join(&config.byron_genesis_file);
+        let hash = byron_genesis_hash(&byron_path).map_err(read_err(&byron_path))?;
+        verify_hash("byron", &config.byron_genesis_hash, hash)?;
+        let byron = byron::from_file(&byron_path).map_err(read_err(&byron_path))?;
+
+        let shelley_path = dir.join(&config.shelley_genesis_file);
+        let hash = genesis_hash(&shelley_path).map_err(read_err(&shelley_path))?;
+        verify_hash("shelley", &config.shelley_genesis_hash, hash)?;
+        let shelley = shelley::from_file(&shelley_path).map_err(read_err(&shelley_path))?;
+
+        let alonzo_path = dir.join(&config.alonzo_genesis_file);
+        let hash = genesis_hash(&alonzo_path).map_err(read_err(&alonzo_path))?;
+        verify_hash("alonzo", &config.alonzo_genesis_hash, hash)?;
+        let alonzo = alonzo::from_file(&alonzo_path).map_err(read_err(&alonzo_path))?;
+
+        let conway = match &config.conway_genesis_file {
+            Some(file) => {
+                let conway_path = dir.join(file);
+                let hash = genesis_hash(&conway_path).map_err(read_err(&conway_path))?;
+                verify_hash("conway", &config.conway_genesis_hash, hash)?;
+                Some(conway::from_file(&conway_path).map_err(read_err(&conway_path))?)
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            config,
+            byron,
+            shelley,
+            alonzo,
+            conway,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_data(name: &str) -> PathBuf {
+        PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap())
+            .join("..")
+            .join("test_data")
+            .join(name)
+    }
+
+    #[test]
+    fn test_mainnet_network_loads() {
+        let network = NetworkConfig::load(&test_data("mainnet-config.json")).unwrap();
+
+        assert_eq!(network.config.protocol.as_deref(), Some("Cardano"));
+        assert_eq!(
+            network.config.requires_network_magic,
+            Some(RequiresNetworkMagic::RequiresNoMagic)
+        );
+        assert_eq!(network.config.last_known_block_version_major, Some(3));
+        assert_eq!(network.byron.protocol_consts.protocol_magic, 764824073);
+        assert_eq!(network.shelley.network_magic, Some(764824073));
+        assert!(network.conway.is_some());
+    }
+
+    #[test]
+    fn test_hard_fork_overrides() {
+        let config: ConfigFile = serde_json::from_str(
+            r#"{
+                "ByronGenesisFile": "byron-genesis.json",
+                "ShelleyGenesisFile": "shelley-genesis.json",
+                "AlonzoGenesisFile": "alonzo-genesis.json",
+                "TestShelleyHardForkAtEpoch": 0,
+                "TestAllegraHardForkAtEpoch": 0,
+                "TestMaryHardForkAtEpoch": 0,
+                "TestAlonzoHardForkAtEpoch": 0,
+                "TestBabbageHardForkAtEpoch": 0,
+                "TestConwayHardForkAtEpoch": 3,
+                "ExperimentalHardForksEnabled": true
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.test_shelley_hard_fork_at_epoch, Some(0));
+        assert_eq!(config.test_conway_hard_fork_at_epoch, Some(3));
+        assert_eq!(config.experimental_hard_forks_enabled, Some(true));
+        assert!(config.conway_genesis_file.is_none());
+    }
+
+    #[test]
+    fn test_genesis_hash_mismatch() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let config = std::fs::read_to_string(test_data("mainnet-config.json"))
+            .unwrap()
+            .replace(
+                "1a3be38bcbb7911969283716ad7aa550250226b76a61fc51cc9a9a35d9276d81",
+                &"00".repeat(32),
+            )
+            .replace(
+                "\"mainnet-",
+                &format!("\"{}/mainnet-", test_data("").to_str().unwrap()),
+            );
+
+        let path = dir.path().join("config.json");
+        std::fs::write(&path, config).unwrap();
+
+        match NetworkConfig::load(&path) {
+            Err(Error::GenesisHashMismatch { era, actual, .. }) => {
+                assert_eq!(era, "shelley");
+                assert_eq!(
+                    actual.to_string(),
+                    "1a3be38bcbb7911969283716ad7aa550250226b76a61fc51cc9a9a35d9276d81"
+                );
+            }
+            Err(other) => panic!("unexpected error: {other}"),
+            Ok(_) => panic!("hash mismatch not detected"),
+        }
+    }
+}
@@ -143,6 +143,41 @@ pub enum Relay {
     MultiHostName(MultiHostName),
 }
 
+/// A relay as written in the genesis file, a single-entry map keyed by kind.
+#[derive(Deserialize)]
+enum KeyedRelay {
+    #[serde(rename = "single host address")]
+    SingleHostAddr(SingleHostAddr),
+    #[serde(rename = "single host name")]
+    SingleHostName(SingleHostName),
+    #[serde(rename = "multi host name")]
+    MultiHostName(MultiHostName),
+}
+
+// The shape of a relay alone is ambiguous (a single host name without a port
+// looks like a multi host name), so the kind is taken from the map key.
+fn deserialize_relays<'de, D>(deserializer: D) -> Result<Vec<HashMap<String, Relay>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let relays = Vec::<KeyedRelay>::deserialize(deserializer)?;
+
+    let relays = relays
+        .into_iter()
+        .map(|relay| {
+            let (kind, relay) = match relay {
+                KeyedRelay::SingleHostAddr(x) => ("single host address", Relay::SingleHostAddr(x)),
+                KeyedRelay::SingleHostName(x) => ("single host name", Relay::SingleHostName(x)),
+                KeyedRelay::MultiHostName(x) => ("multi host name", Relay::MultiHostName(x)),
+            };
+
+            HashMap::from([(kind.to_string(), relay)])
+        })
+        .collect();
+
+    Ok(relays)
+}
+
 #[derive(Debug, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub enum Credential {
@@ -167,6 +202,7 @@ pub struct Pool {
     pub owners: Vec<String>,
     pub pledge: u64,
     pub public_key: String, // pool ID
+    #[serde(deserialize_with = "deserialize_relays")]
     pub relays: Vec<HashMap<String, Relay>>,
     pub reward_account: RewardAccount,
     pub vrf: String,
@@ -209,22 +245,50 @@ pub fn from_file(path: &std::path::Path) -> Result<GenesisFile, std::io::Error>
 
 pub type GenesisUtxo = (Hash<32>, pallas_addresses::Address, u64);
 
-pub fn shelley_utxos(config: &GenesisFile) -> Vec<GenesisUtxo> {
-    match &config.initial_funds {
-        None => Vec::new(),
-        Some(funds) => funds
-            .iter()
-            .map(|(addr, amount)| {
-                let addr = pallas_addresses::Address::from_hex(addr).unwrap();
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum InitialFundsError {
+    #[error("invalid initial funds address {0}")]
+    InvalidAddress(String),
 
-                let txid = pallas_crypto::hash::Hasher::<256>::hash(&addr.to_vec());
+    #[error("unsupported initial funds address {0}, only shelley addresses can hold them")]
+    UnsupportedAddress(String),
+}
 
-                (txid, addr, *amount)
-            })
-            .collect(),
+/// Hash of the pseudo tx that holds the initial funds of an address, as in
+/// the ledger's `initialFundsPseudoTxIn`: the Blake2b-256 of the CBOR of the
+/// payment key or script hash. Byron and stake addresses are rejected.
+pub fn initial_funds_tx_hash(
+    address: &pallas_addresses::Address,
+) -> Result<Hash<32>, InitialFundsError> {
+    match address {
+        pallas_addresses::Address::Shelley(x) => {
+            let hash = match x.payment() {
+                pallas_addresses::ShelleyPaymentPart::Key(x) => x,
+                pallas_addresses::ShelleyPaymentPart::Script(x) => x,
+            };
+
+            Ok(pallas_crypto::hash::Hasher::<256>::hash_cbor(hash))
+        }
+        _ => Err(InitialFundsError::UnsupportedAddress(address.to_hex())),
     }
 }
 
+pub fn shelley_utxos(config: &GenesisFile) -> Result<Vec<GenesisUtxo>, InitialFundsError> {
+    config
+        .initial_funds
+        .iter()
+        .flatten()
+        .map(|(addr, amount)| {
+            let addr = pallas_addresses::Address::from_hex(addr)
+                .map_err(|_| InitialFundsError::InvalidAddress(addr.clone()))?;
+
+            let txid = initial_funds_tx_hash(&addr)?;
+
+            Ok((txid, addr, *amount))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -238,14 +302,45 @@ mod tests {
         from_file(&path).unwrap()
     }
 
+    #[test]
+    fn relay_kind_comes_from_key() {
+        let relays = serde_json::json!([
+            { "single host name": { "dnsName": "relay.example" } },
+            { "multi host name": { "dnsName": "pool.example" } },
+            { "single host address": { "port": 3001, "IPv4": "10.0.0.1" } },
+        ]);
+
+        let relays = deserialize_relays(relays).unwrap();
+
+        assert!(matches!(
+            &relays[0]["single host name"],
+            Relay::SingleHostName(SingleHostName { port: None, dns_name }) if dns_name == "relay.example"
+        ));
+        assert!(matches!(
+            &relays[1]["multi host name"],
+            Relay::MultiHostName(MultiHostName { dns_name }) if dns_name == "pool.example"
+        ));
+        assert!(matches!(
+            &relays[2]["single host address"],
+            Relay::SingleHostAddr(SingleHostAddr {
+                port: Some(3001),
+                ..
+            })
+        ));
+
+        let unknown = serde_json::json!([{ "some relay": { "dnsName": "x" } }]);
+        assert!(deserialize_relays(unknown).is_err());
+    }
+
     #[test]
     fn calc_address_txid() {
         let config = load_test_data_config("golden");
-        let utxos = shelley_utxos(&config);
+        let utxos = shelley_utxos(&config).unwrap();
         let utxo = utxos.first().unwrap();
+        // as given by `cardano-cli genesis initial-txin`
         assert_eq!(
             utxo.0.to_string(),
-            "f9ec23569778d1c5f7f43e0e98464335f02fb98b57683faa1c6b18c82921d2da"
+            "04387d049935c19e1161f69ec57fbb8366cd221798bed24347ed602815fae05d"
         );
         assert_eq!(
             utxo.1.to_bech32().unwrap(),
@@ -254,6 +349,26 @@ mod tests {
         assert_eq!(utxo.2, 12157196);
     }
 
+    #[test]
+    fn byron_address_has_no_initial_funds_txid() {
+        let addr = pallas_addresses::Address::from_bech32(
+            "addr_test1qrsm4h32h9r95f8at64ykuugxqu3wvu0s5ay3vg6tlyevjh4e2flkegka00r69gt8c4vkxgf2vnnph3nsvhlkg5ukgxslee3tf",
+        )
+        .unwrap();
+        assert!(initial_funds_tx_hash(&addr).is_ok());
+
+        let addr = pallas_addresses::ByronAddress::from_base58(
+            "Ae2tdPwUPEZKQuZh2UndEoTKEakMYHGNjJVYmNZgJk2qqgHouxDsA5oT83n",
+        )
+        .unwrap();
+        let addr = pallas_addresses::Address::Byron(addr);
+
+        assert_eq!(
+            initial_funds_tx_hash(&addr),
+            Err(InitialFundsError::UnsupportedAddress(addr.to_hex()))
+        );
+    }
+
     #[test]
     fn test_preview_json_loads() {
         load_test_data_config("preview");
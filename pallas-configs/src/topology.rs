@@ -0,0 +1,90 @@
+use serde::Deserialize;
+use std::path::PathBuf;
+
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct AccessPoint {
+    pub address: String,
+    pub port: u16,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalRoots {
+    pub access_points: Vec<AccessPoint>,
+    pub advertise: bool,
+    #[serde(default)]
+    pub trustable: bool,
+    pub valency: Option<u32>,
+    pub hot_valency: Option<u32>,
+    pub warm_valency: Option<u32>,
+    pub diffusion_mode: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicRoots {
+    pub access_points: Vec<AccessPoint>,
+    pub advertise: bool,
+}
+
+/// P2P topology of the node, as read from `topology.json`
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TopologyFile {
+    /// `None` when bootstrap peers are disabled
+    pub bootstrap_peers: Option<Vec<AccessPoint>>,
+    #[serde(default)]
+    pub local_roots: Vec<LocalRoots>,
+    #[serde(default)]
+    pub public_roots: Vec<PublicRoots>,
+    /// Slot after which peers are taken from the ledger, negative values
+    /// disabling ledger peers
+    pub use_ledger_after_slot: Option<i64>,
+    pub peer_snapshot_file: Option<PathBuf>,
+}
+
+pub fn from_file(path: &std::path::Path) -> Result<TopologyFile, std::io::Error> {
+    let file = std::fs::File::open(path)?;
+    let reader = std::io::BufReader::new(file);
+    let parsed: TopologyFile = serde_json::from_reader(reader)?;
+
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mainnet_json_loads() {
+        let path = std::path::PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap())
+            .join("..")
+            .join("test_data")
+            .join("mainnet-topology.json");
+
+        let topology = from_file(&path).unwrap();
+
+        let bootstrap = topology.bootstrap_peers.unwrap();
+        assert_eq!(bootstrap.len(), 3);
+        assert_eq!(
+            bootstrap[0],
+            AccessPoint {
+                address: "backbone.cardano.iog.io".into(),
+                port: 3001
+            }
+        );
+        assert_eq!(topology.local_roots[0].valency, Some(1));
+        assert!(topology.public_roots[0].access_points.is_empty());
+        assert_eq!(topology.use_ledger_after_slot, Some(128908821));
+    }
+
+    #[test]
+    fn test_disabled_bootstrap_peers() {
+        let topology: TopologyFile =
+            serde_json::from_str(r#"{"bootstrapPeers": null, "useLedgerAfterSlot": -1}"#).unwrap();
+
+        assert!(topology.bootstrap_peers.is_none());
+        assert!(topology.local_roots.is_empty());
+        assert_eq!(topology.use_ledger_after_slot, Some(-1));
+    }
+}
@@ -0,0 +1,578 @@
+//! Key Evolving Signatures (KES)
+//!
+//! Cardano uses the `Sum6KES` scheme of `cardano-base`: the binary sum
+//! composition of MMM (Malkin, Micciancio and Miner), applied 6 times on top
+//! of Ed25519. A key is valid for `2^6 = 64` periods, and each level of the
+//! tree binds its two sub-keys together with a Blake2b-256 hash.
+//!
+//! A [`Signature`] carries, along with the Ed25519 signature of the leaf, the
+//! public keys of both branches at every level of the tree so that it can be
+//! verified against the root [`PublicKey`] only.
+//!
+//! The [`SecretKey`] only keeps the keys needed for the current period and
+//! the seeds of the branches still to come. Evolving the key to the next
+//! period erases the material of the previous ones, so that a compromised key
+//! can not be used to forge signatures of past periods.
+
+use crate::{
+    hash::Hasher,
+    key::ed25519::{self, Signature as Ed25519Signature},
+    memsec::Scrubbed as _,
+};
+use cryptoxide::hashing::blake2b::Blake2b;
+use rand_core::{CryptoRng, RngCore};
+use std::{any::type_name, convert::TryFrom, fmt, str::FromStr};
+use thiserror::Error;
+
+/// The depth of the Sum6KES tree
+const DEPTH: u32 = 6;
+
+/// The size of the seeds the keys are generated from
+pub const SEED_SIZE: usize = 32;
+
+/// size of the secret key of a tree of the given `depth`
+const fn secret_key_size(depth: u32) -> usize {
+    ed25519::SecretKey::SIZE + depth as usize * (SEED_SIZE + 2 * PublicKey::SIZE)
+}
+
+/// Sum6KES Secret Key, at a given period.
+///
+/// The layout of the key is the one of `cardano-base`: the secret key of the
+/// current sub-tree, followed by the seed of the right sub-tree and by the
+/// public keys of the left and right sub-trees, at every level of the tree.
+#[derive(Clone)]
+pub struct SecretKey {
+    bytes: [u8; Self::SIZE],
+    period: u32,
+}
+
+/// Sum6KES Public Key. It is the root of the tree of keys and can be used to
+/// verify a [`Signature`] of any period.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PublicKey([u8; Self::SIZE]);
+
+/// Sum6KES Signature, as found in the headers of the Praos blocks.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Signature([u8; Self::SIZE]);
+
+/// Error type used when retrieving a [`PublicKey`] via the [`TryFrom`]
+/// trait.
+#[derive(Debug, Error)]
+pub enum TryFromPublicKeyError {
+    #[error("Invalid size, expecting {}", PublicKey::SIZE)]
+    InvalidSize,
+}
+
+/// Error type used when retrieving a [`Signature`] via the [`TryFrom`]
+/// trait.
+#[derive(Debug, Error)]
+pub enum TryFromSignatureError {
+    #[error("Invalid size, expecting {}", Signature::SIZE)]
+    InvalidSize,
+}
+
+/// Error type returned when evolving a [`SecretKey`] past its last period
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum EvolutionError {
+    #[error("KES key already reached its last period ({})", PublicKey::TOTAL_PERIODS - 1)]
+    KeyExhausted,
+}
+
+/// Error type returned when verifying a [`Signature`]
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum VerificationError {
+    #[error(
+        "KES period {0} is out of range, expecting less than {}",
+        PublicKey::TOTAL_PERIODS
+    )]
+    PeriodOutOfRange(u32),
+
+    #[error("Sub-keys of the signature do not hash to the public key")]
+    InvalidPublicKeyHash,
+
+    #[error("Ed25519 signature does not verify")]
+    InvalidSignature,
+}
+
+macro_rules! impl_size_zero {
+    ($Type:ty, $Size:expr) => {
+        impl $Type {
+            /// This is the size of the type in bytes.
+            pub const SIZE: usize = $Size;
+
+            /// create a zero object. This is not a _"valid"_ one. It is
+            /// used to initialize a ready to use data structure in this module.
+            #[inline]
+            fn zero() -> Self {
+                Self([0; Self::SIZE])
+            }
+        }
+    };
+}
+
+impl_size_zero!(PublicKey, 32);
+
+impl SecretKey {
+    /// This is the size of the key in bytes, the period excluded.
+    pub const SIZE: usize = secret_key_size(DEPTH);
+
+    /// generate a new [`SecretKey`] with the given random number generator
+    pub fn new<Rng>(mut rng: Rng) -> Self
+    where
+        Rng: RngCore + CryptoRng,
+    {
+        let mut seed = [0; SEED_SIZE];
+        rng.fill_bytes(&mut seed);
+        let key = Self::from_seed(&seed);
+        seed.scrub();
+        key
+    }
+
+    /// generate the [`SecretKey`] of the first period from the given seed,
+    /// the same way as `cardano-base`
+    pub fn from_seed(seed: &[u8; SEED_SIZE]) -> Self {
+        let mut key = Self {
+            bytes: [0; Self::SIZE],
+            period: 0,
+        };
+        keygen(DEPTH, seed, &mut key.bytes);
+        key
+    }
+
+    /// get the [`PublicKey`] associated to this key
+    ///
+    /// The [`PublicKey`] is the same for all the periods of the key.
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey(public_key(DEPTH, &self.bytes))
+    }
+
+    /// the period the key is currently at
+    pub fn period(&self) -> u32 {
+        self.period
+    }
+
+    /// create a [`Signature`] for the given message with this [`SecretKey`],
+    /// for the current period of the key.
+    pub fn sign<T>(&self, msg: T) -> Signature
+    where
+        T: AsRef<[u8]>,
+    {
+        let mut signature = Signature::zero();
+        sign(DEPTH, &self.bytes, msg.as_ref(), &mut signature.0);
+        signature
+    }
+
+    /// evolve the key to the next period, erasing the secret material of the
+    /// current period.
+    pub fn evolve(&mut self) -> Result<(), EvolutionError> {
+        if self.period + 1 >= PublicKey::TOTAL_PERIODS {
+            return Err(EvolutionError::KeyExhausted);
+        }
+        update(DEPTH, &mut self.bytes, self.period);
+        self.period += 1;
+        Ok(())
+    }
+
+    /// convert the [`SecretKey`] into its byte composition (without the
+    /// period) as found in the KES signing keys of `cardano-cli`
+    ///
+    /// # Safety
+    ///
+    /// This function is not safe because:
+    ///
+    /// * using it removes all the security measure we put in place
+    ///   to protect your private key: opaque [`Debug`] impl, zeroisation on [`Drop`], ...
+    /// * you will need to be careful not to leak the bytes
+    #[inline]
+    pub unsafe fn leak_into_bytes(mut key: Self) -> [u8; Self::SIZE] {
+        let bytes = key.bytes;
+        key.bytes.scrub();
+        bytes
+    }
+
+    /// retrieve a [`SecretKey`] at the given `period` from its byte
+    /// composition
+    ///
+    /// # Safety
+    ///
+    /// The bytes are not checked, signing with a key whose bytes do not
+    /// match the period will generate [`Signature`] that do not verify.
+    pub unsafe fn from_bytes_unchecked(bytes: [u8; Self::SIZE], period: u32) -> Self {
+        Self { bytes, period }
+    }
+}
+impl_size_zero!(
+    Signature,
+    Ed25519Signature::SIZE + 2 * DEPTH as usize * PublicKey::SIZE
+);
+
+impl PublicKey {
+    /// The number of periods a key can evolve through
+    pub const TOTAL_PERIODS: u32 = 1 << DEPTH;
+
+    /// verify the [`Signature`] of the `message` for the given `period`
+    /// against the [`PublicKey`] `self`.
+    pub fn verify<T>(
+        &self,
+        period: u32,
+        message: T,
+        signature: &Signature,
+    ) -> Result<(), VerificationError>
+    where
+        T: AsRef<[u8]>,
+    {
+        if period >= Self::TOTAL_PERIODS {
+            return Err(VerificationError::PeriodOutOfRange(period));
+        }
+        verify_sum(DEPTH, &self.0, period, message.as_ref(), &signature.0)
+    }
+}
+
+/// split a seed into the seeds of the left and right branches
+fn expand_seed(seed: &[u8]) -> ([u8; SEED_SIZE], [u8; SEED_SIZE]) {
+    let left = Blake2b::<256>::new().update(&[1]).update(seed).finalize();
+    let right = Blake2b::<256>::new().update(&[2]).update(seed).finalize();
+    (left, right)
+}
+
+/// generate the secret key of a tree of the given `depth` into `key`
+fn keygen(depth: u32, seed: &[u8], key: &mut [u8]) {
+    if depth == 0 {
+        key.copy_from_slice(seed);
+        return;
+    }
+
+    let (mut left_seed, mut right_seed) = expand_seed(seed);
+    let child_size = secret_key_size(depth - 1);
+    let (child, rest) = key.split_at_mut(child_size);
+
+    let mut right = vec![0; child_size];
+    keygen(depth - 1, &right_seed, &mut right);
+    rest[SEED_SIZE + PublicKey::SIZE..].copy_from_slice(&public_key(depth - 1, &right));
+    right.scrub();
+
+    keygen(depth - 1, &left_seed, child);
+    rest[SEED_SIZE..SEED_SIZE + PublicKey::SIZE].copy_from_slice(&public_key(depth - 1, child));
+    rest[..SEED_SIZE].copy_from_slice(&right_seed);
+
+    left_seed.scrub();
+    right_seed.scrub();
+}
+
+/// public key of the secret key of a tree of the given `depth`
+fn public_key(depth: u32, key: &[u8]) -> [u8; PublicKey::SIZE] {
+    if depth == 0 {
+        let mut seed = [0; ed25519::SecretKey::SIZE];
+        seed.copy_from_slice(key);
+        let key = ed25519::SecretKey::from(seed);
+        seed.scrub();
+        return key.public_key().into();
+    }
+
+    let keys = &key[key.len() - 2 * PublicKey::SIZE..];
+    hash_pair(&keys[..PublicKey::SIZE], &keys[PublicKey::SIZE..])
+}
+
+/// sign with the secret key of a tree of the given `depth`, the signature
+/// being laid out as expected by [`verify_sum`]. The key of the current
+/// sub-tree is always the one stored, whatever the period.
+fn sign(depth: u32, key: &[u8], message: &[u8], signature: &mut [u8]) {
+    if depth == 0 {
+        let mut seed = [0; ed25519::SecretKey::SIZE];
+        seed.copy_from_slice(key);
+        let key = ed25519::SecretKey::from(seed);
+        seed.scrub();
+        signature.copy_from_slice(key.sign(message).as_ref());
+        return;
+    }
+
+    let child_size = secret_key_size(depth - 1);
+    let (sigma, keys) = signature.split_at_mut(signature.len() - 2 * PublicKey::SIZE);
+    keys.copy_from_slice(&key[child_size + SEED_SIZE..]);
+    sign(depth - 1, &key[..child_size], message, sigma);
+}
+
+/// move the secret key of a tree of the given `depth` from `period` to the
+/// next one. When the left branch is exhausted, the key of the right branch
+/// is generated from its seed, which is then erased.
+fn update(depth: u32, key: &mut [u8], period: u32) {
+    let half = 1 << (depth - 1);
+    let child_size = secret_key_size(depth - 1);
+    let (child, rest) = key.split_at_mut(child_size);
+
+    if period + 1 == half {
+        child.scrub();
+        keygen(depth - 1, &rest[..SEED_SIZE], child);
+        rest[..SEED_SIZE].scrub();
+    } else {
+        update(depth - 1, child, period % half);
+    }
+}
+
+/// hash of the public keys of the two branches of a sum composition
+fn hash_pair(left: &[u8], right: &[u8]) -> [u8; PublicKey::SIZE] {
+    let mut hasher = Hasher::<256>::new();
+    hasher.input(left);
+    hasher.input(right);
+    *hasher.finalize()
+}
+
+/// verify a signature of the sum composition of the given `depth`. The
+/// signature is laid out as the signature of the sub-tree followed by the
+/// public keys of the left and right branches.
+fn verify_sum(
+    depth: u32,
+    public_key: &[u8],
+    period: u32,
+    message: &[u8],
+    signature: &[u8],
+) -> Result<(), VerificationError> {
+    if depth == 0 {
+        let public_key = ed25519::PublicKey::try_from(public_key)
+            .map_err(|_| VerificationError::InvalidSignature)?;
+        let signature = Ed25519Signature::try_from(signature)
+            .map_err(|_| VerificationError::InvalidSignature)?;
+        return if public_key.verify(message, &signature) {
+            Ok(())
+        } else {
+            Err(VerificationError::InvalidSignature)
+        };
+    }
+
+    let (sigma, keys) = signature.split_at(signature.len() - 2 * PublicKey::SIZE);
+    let (left, right) = keys.split_at(PublicKey::SIZE);
+
+    if hash_pair(left, right)[..] != *public_key {
+        return Err(VerificationError::InvalidPublicKeyHash);
+    }
+
+    let half = 1 << (depth - 1);
+    if period < half {
+        verify_sum(depth - 1, left, period, message, sigma)
+    } else {
+        verify_sum(depth - 1, right, period - half, message, sigma)
+    }
+}
+
+/* Drop ******************************************************************** */
+
+impl Drop for SecretKey {
+    fn drop(&mut self) {
+        self.bytes.scrub();
+        self.period.scrub();
+    }
+}
+
+/* Format ****************************************************************** */
+
+impl fmt::Display for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&hex::encode(self.0))
+    }
+}
+
+impl fmt::Display for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&hex::encode(self.0))
+    }
+}
+
+impl fmt::Debug for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PublicKey")
+            .field(&hex::encode(self.0))
+            .finish()
+    }
+}
+
+impl fmt::Debug for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Signature")
+            .field(&hex::encode(self.0))
+            .finish()
+    }
+}
+
+/// conveniently provide a proper implementation to debug for the
+/// SecretKey types when only *testing* the library
+#[cfg(test)]
+impl fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(&format!(
+            "SecretKey<{typename}>",
+            typename = type_name::<Self>()
+        ))
+        .field("bytes", &hex::encode(self.bytes))
+        .field("period", &self.period)
+        .finish()
+    }
+}
+
+/// conveniently provide an incomplete implementation of Debug for the
+/// SecretKey.
+#[cfg(not(test))]
+impl fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(&format!(
+            "SecretKey<{typename}>",
+            typename = type_name::<Self>()
+        ))
+        .field("period", &self.period)
+        .finish_non_exhaustive()
+    }
+}
+
+/* Conversion ************************************************************** */
+
+impl AsRef<[u8]> for PublicKey {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_ref()
+    }
+}
+
+impl AsRef<[u8]> for Signature {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_ref()
+    }
+}
+
+impl From<[u8; Self::SIZE]> for PublicKey {
+    fn from(bytes: [u8; Self::SIZE]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<[u8; Self::SIZE]> for Signature {
+    fn from(bytes: [u8; Self::SIZE]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for PublicKey {
+    type Error = TryFromPublicKeyError;
+    fn try_from(value: &'a [u8]) -> Result<Self, Self::Error> {
+        if value.len() != Self::SIZE {
+            Err(Self::Error::InvalidSize)
+        } else {
+            let mut s = Self::zero();
+            s.0.copy_from_slice(value);
+            Ok(s)
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for Signature {
+    type Error = TryFromSignatureError;
+    fn try_from(value: &'a [u8]) -> Result<Self, Self::Error> {
+        if value.len() != Self::SIZE {
+            Err(Self::Error::InvalidSize)
+        } else {
+            let mut s = Self::zero();
+            s.0.copy_from_slice(value);
+            Ok(s)
+        }
+    }
+}
+
+impl FromStr for PublicKey {
+    type Err = hex::FromHexError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut r = Self::zero();
+        hex::decode_to_slice(s, &mut r.0)?;
+        Ok(r)
+    }
+}
+
+impl FromStr for Signature {
+    type Err = hex::FromHexError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut r = Self::zero();
+        hex::decode_to_slice(s, &mut r.0)?;
+        Ok(r)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // body of the header of babbage1.block, signed by cardano-node at KES
+    // period 11 of its hot key
+    const HOT_KEY: &str = "840ba03379518ab70200edd70a984cd5fcf4ef8f0994c95cc394d3e7243de3f1";
+    const HEADER_BODY: &str = "8a19ae991a000fb73c582068e0b30103cba96819b7f7abbe80f4aae6fb35270c3c77c4a8f82d9ab21a920e582063fc404a8f5635aa133818457a406f62d946dd35362ef78429da4953b5125ab15820a9a971c7cc826c53a9a1690ae9c6a46123d85ac90641c859da408cbc7ce6b3eb825840e5b74253ccd2a78e4e5653499151fb1c01e0c009d3b6e80feb4d15c6ef10abe9683b652ca968a1ae124be7b59eb1f4ebfd882671da49101635b1995e1e42a2ef58500ec054de37747089cd4fd9864b2fe17cdb2567adf9ee89a44c29e81fad88d663bd76d6abd65848ba0df146df1f95a6eb16e7d794332323e359442a9f2830d5b5b052e00bcd147c7e5bed987a70580f021903385820219f8b90967156d6ee02e3507ccb413b5a8d87343706cbd9e8fb1e1285e4b3c0845820840ba03379518ab70200edd70a984cd5fcf4ef8f0994c95cc394d3e7243de3f100005840a352185eb0e2783206e23d0aa25b8d799a228266baf65de832cd2b64d666fbdd5649461d3cd30f721bc04e94bb9f97c31335ee39893247eaf9912cfa68878504820700";
+    const BODY_SIGNATURE: &str = "954157d0a93992a0cda655574074541b92a5d12cba0a94dc8dc7c97de407dc6bbb463810a6fec0ae47c7d4d3cddcb51485616e924448e9850369efdf9f248d0ff052309c26385a7bff045e39d4afdb90cc114b06215ee4c2ba026b9109aecf87e0547eeab8973a3f5903403fea20416357881a2226733dafd3700154992ad454f75383438acab170e4fca6691d40ed986df32b006d9408cb70a1265c52673cb7c361b646b8a1e47b92b015dd82743ce75680caa0b3337dbec280b91d4fcf31e97630d09977f264eb4c4794647734532a36538896e640982f52649f808a26e71782232617c944e8ab2bf62e59772123b68d136b3a06c0d3263fbfc51f4225a18d788b4f45b62741472e72d107be72e63265cf83f389b4ced1b5246e5f4b1c2aeb35d7f60b1e417940b917f0ad9cc7962f28dcd243cac00b7a4c2d233a1e3dc0749d5430d777d41425c9cac7f7232f620f0ed8e5f400b7c2a1508e5e61adcd2d5bf76d9db9576cd1d87ba1912567b493c7e20756e970a731a05ee20ef2db22f8b7959323025072ecdb163adb96878bea9aee92b67556ae38ee764e37ce65d74c5c4255ec86defaf48cd31d9ab7b868606f050c816119d7c00dbbf66c248cbfc4a2";
+
+    #[test]
+    fn verify_header_signature() {
+        let public_key: PublicKey = HOT_KEY.parse().unwrap();
+        let message = hex::decode(HEADER_BODY).unwrap();
+        let signature: Signature = BODY_SIGNATURE.parse().unwrap();
+
+        assert_eq!(public_key.verify(11, &message, &signature), Ok(()));
+        assert_eq!(
+            public_key.verify(10, &message, &signature),
+            Err(VerificationError::InvalidSignature)
+        );
+        assert_eq!(
+            public_key.verify(40, &message, &signature),
+            Err(VerificationError::InvalidPublicKeyHash)
+        );
+        assert_eq!(
+            public_key.verify(11, &message[1..], &signature),
+            Err(VerificationError::InvalidSignature)
+        );
+        assert_eq!(
+            public_key.verify(64, &message, &signature),
+            Err(VerificationError::PeriodOutOfRange(64))
+        );
+    }
+
+    #[test]
+    fn sizes_match_cardano_base() {
+        assert_eq!(SecretKey::SIZE, 608);
+        assert_eq!(PublicKey::SIZE, 32);
+        assert_eq!(Signature::SIZE, 448);
+        assert_eq!(PublicKey::TOTAL_PERIODS, 64);
+    }
+
+    #[test]
+    fn evolve_through_all_periods() {
+        let mut secret_key = SecretKey::from_seed(&[7; SEED_SIZE]);
+        let public_key = secret_key.public_key();
+
+        for period in 0..PublicKey::TOTAL_PERIODS {
+            assert_eq!(secret_key.period(), period);
+            assert_eq!(secret_key.public_key(), public_key);
+
+            let signature = secret_key.sign(period.to_be_bytes());
+            assert_eq!(
+                public_key.verify(period, period.to_be_bytes(), &signature),
+                Ok(())
+            );
+            if period > 0 {
+                assert!(public_key
+                    .verify(period - 1, period.to_be_bytes(), &signature)
+                    .is_err());
+            }
+
+            let evolved = secret_key.evolve();
+            if period + 1 < PublicKey::TOTAL_PERIODS {
+                assert_eq!(evolved, Ok(()));
+            } else {
+                assert_eq!(evolved, Err(EvolutionError::KeyExhausted));
+            }
+        }
+    }
+
+    #[test]
+    fn evolved_key_erases_past_seeds() {
+        let mut secret_key = SecretKey::from_seed(&[7; SEED_SIZE]);
+        let top_seed = secret_key_size(DEPTH - 1)..secret_key_size(DEPTH - 1) + SEED_SIZE;
+        assert_ne!(secret_key.bytes[top_seed.clone()], [0; SEED_SIZE]);
+
+        for _ in 0..PublicKey::TOTAL_PERIODS / 2 {
+            secret_key.evolve().unwrap();
+        }
+        assert_eq!(secret_key.bytes[top_seed], [0; SEED_SIZE]);
+    }
+}
@@ -1 +1,3 @@
 pub mod ed25519;
+pub mod kes;
+pub mod vrf;
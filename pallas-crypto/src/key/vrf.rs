@@ -0,0 +1,790 @@
+//! ECVRF-ED25519-SHA512-Elligator2 Verifiable Random Function
+//!
+//! This is the VRF construction of the IETF draft-irtf-cfrg-vrf-03, as used
+//! by the Praos consensus protocol. It matches the implementation found in
+//! the libsodium fork maintained by IOG (and used by `cardano-base`).
+//!
+//! A [`SecretKey`] proves an input (`alpha`) with a [`Proof`], which is
+//! checked against the associated [`PublicKey`]. The pseudo-random
+//! [`Output`] is recovered from a valid [`Proof`].
+
+use crate::memsec::Scrubbed as _;
+use cryptoxide::{
+    curve25519::{Fe, Ge, GePartial, Scalar},
+    hashing::sha2::Sha512,
+};
+use rand_core::{CryptoRng, RngCore};
+use std::{any::type_name, convert::TryFrom, fmt, str::FromStr};
+use thiserror::Error;
+
+/// The ciphersuite identifier of ECVRF-ED25519-SHA512-Elligator2
+const SUITE: u8 = 0x04;
+
+/// The Montgomery curve25519 `A` coefficient (486662)
+const CURVE25519_A: Fe = Fe::from_bytes(&[
+    0x06, 0x6d, 0x07, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0,
+]);
+
+/// VRF Secret Key. This is the 32 bytes seed of the key, the 64 bytes
+/// signing keys of `cardano-cli` are this seed followed by the [`PublicKey`].
+#[derive(Clone)]
+pub struct SecretKey([u8; Self::SIZE]);
+
+/// VRF Public Key. Can be used to verify a [`Proof`]. A [`PublicKey`] is
+/// associated to a [`SecretKey`]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PublicKey([u8; Self::SIZE]);
+
+/// VRF Proof, as found in the headers of the Praos blocks.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Proof([u8; Self::SIZE]);
+
+/// VRF Output (also called `beta`), the pseudo-random value associated to a
+/// valid [`Proof`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Output([u8; Self::SIZE]);
+
+/// Error type used when retrieving a [`PublicKey`] via the [`TryFrom`]
+/// trait.
+#[derive(Debug, Error)]
+pub enum TryFromPublicKeyError {
+    #[error("Invalid size, expecting {}", PublicKey::SIZE)]
+    InvalidSize,
+}
+
+/// Error type used when retrieving a [`Proof`] via the [`TryFrom`]
+/// trait.
+#[derive(Debug, Error)]
+pub enum TryFromProofError {
+    #[error("Invalid size, expecting {}", Proof::SIZE)]
+    InvalidSize,
+}
+
+/// Error type used when retrieving an [`Output`] via the [`TryFrom`]
+/// trait.
+#[derive(Debug, Error)]
+pub enum TryFromOutputError {
+    #[error("Invalid size, expecting {}", Output::SIZE)]
+    InvalidSize,
+}
+
+/// Error type returned when creating a [`Proof`]
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ProveError {
+    #[error("VRF input does not hash to a point of the curve")]
+    HashToCurve,
+}
+
+/// Error type returned when verifying a [`Proof`]
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum VerificationError {
+    #[error("Invalid VRF public key")]
+    InvalidPublicKey,
+
+    #[error("Malformed VRF proof")]
+    MalformedProof,
+
+    #[error("VRF proof does not verify")]
+    InvalidProof,
+}
+
+macro_rules! impl_size_zero {
+    ($Type:ty, $Size:expr) => {
+        impl $Type {
+            /// This is the size of the type in bytes.
+            pub const SIZE: usize = $Size;
+
+            /// create a zero object. This is not a _"valid"_ one. It is
+            /// used to initialize a ready to use data structure in this module.
+            #[inline]
+            fn zero() -> Self {
+                Self([0; Self::SIZE])
+            }
+        }
+    };
+}
+
+impl_size_zero!(SecretKey, 32);
+impl_size_zero!(PublicKey, 32);
+impl_size_zero!(Proof, 80);
+impl_size_zero!(Output, 64);
+
+impl SecretKey {
+    /// generate a new [`SecretKey`] with the given random number generator
+    pub fn new<Rng>(mut rng: Rng) -> Self
+    where
+        Rng: RngCore + CryptoRng,
+    {
+        let mut s = Self::zero();
+        rng.fill_bytes(&mut s.0);
+        s
+    }
+
+    /// get the [`PublicKey`] associated to this key
+    pub fn public_key(&self) -> PublicKey {
+        let mut x = self.expand();
+        let public_key = Ge::scalarmult_base(&Scalar::from_bytes(&x[0..32].try_into().unwrap()));
+        x.scrub();
+
+        PublicKey(public_key.to_bytes())
+    }
+
+    /// create a [`Proof`] for the given input `alpha` with this
+    /// [`SecretKey`].
+    ///
+    /// The [`Proof`] can then be verified against the associated
+    /// [`PublicKey`] and the original input.
+    pub fn prove<T>(&self, alpha: T) -> Result<Proof, ProveError>
+    where
+        T: AsRef<[u8]>,
+    {
+        let mut az = self.expand();
+        let mut x = [0; 32];
+        x.copy_from_slice(&az[0..32]);
+
+        let public_key = Ge::scalarmult_base(&Scalar::from_bytes(&x)).to_bytes();
+        let h = hash_to_curve(&public_key, alpha.as_ref())
+            .and_then(|h_string| Some((h_string, ExtendedPoint::from_bytes(&h_string)?)));
+
+        let (h_string, h) = match h {
+            Some(h) => h,
+            None => {
+                az.scrub();
+                x.scrub();
+                return Err(ProveError::HashToCurve);
+            }
+        };
+        let gamma = h.scalarmult(&x).to_bytes();
+
+        // the nonce is derived from the second half of the expanded key, as
+        // with Ed25519 signatures
+        let mut ctx = Sha512::new();
+        ctx.update_mut(&az[32..64]);
+        ctx.update_mut(&h_string);
+        let mut nonce = ctx.finalize();
+        let mut k = Scalar::reduce_from_wide_bytes(&nonce).to_bytes();
+
+        let k_b = Ge::scalarmult_base(&Scalar::from_bytes(&k)).to_bytes();
+        let k_h = h.scalarmult(&k).to_bytes();
+
+        let mut c = [0; 32];
+        c[0..16].copy_from_slice(&hash_points(&h_string, &gamma, &k_b, &k_h));
+        let s = mul_add(&c, &x, &k);
+
+        az.scrub();
+        x.scrub();
+        nonce.scrub();
+        k.scrub();
+
+        let mut proof = Proof::zero();
+        proof.0[0..32].copy_from_slice(&gamma);
+        proof.0[32..48].copy_from_slice(&c[0..16]);
+        proof.0[48..80].copy_from_slice(&s.to_bytes());
+        Ok(proof)
+    }
+
+    /// the clamped secret scalar followed by the nonce seed
+    fn expand(&self) -> [u8; 64] {
+        let mut az = cryptoxide::hashing::sha512(&self.0);
+        az[0] &= 0b1111_1000;
+        az[31] &= 0b0111_1111;
+        az[31] |= 0b0100_0000;
+        az
+    }
+
+    /// convert the [`SecretKey`] into its compressed byte composition
+    ///
+    /// # Safety
+    ///
+    /// This function is not safe because:
+    ///
+    /// * using it removes all the security measure we put in place
+    ///   to protect your private key: opaque [`Debug`] impl, zeroisation on [`Drop`], ...
+    /// * you will need to be careful not to leak the bytes
+    #[inline]
+    pub unsafe fn leak_into_bytes(Self(bytes): Self) -> [u8; Self::SIZE] {
+        bytes
+    }
+}
+
+impl PublicKey {
+    /// verify the [`Proof`] against the input `alpha` and the [`PublicKey`]
+    /// `self`. On success, the [`Output`] of the proof is returned.
+    pub fn verify<T>(&self, alpha: T, proof: &Proof) -> Result<Output, VerificationError>
+    where
+        T: AsRef<[u8]>,
+    {
+        if !is_canonical(&self.0) || has_small_order(&self.0) {
+            return Err(VerificationError::InvalidPublicKey);
+        }
+        // `Ge::from_bytes` recovers the negation of the encoded point
+        let neg_y = Ge::from_bytes(&self.0).ok_or(VerificationError::InvalidPublicKey)?;
+
+        let (gamma, c, s) = proof.decode()?;
+        let h_string =
+            hash_to_curve(&self.0, alpha.as_ref()).ok_or(VerificationError::InvalidProof)?;
+        let h = decode_point(&h_string).ok_or(VerificationError::InvalidProof)?;
+
+        let c_scalar = Scalar::from_bytes(&c);
+
+        // U = s*B - c*Y
+        let u = GePartial::double_scalarmult_vartime(&c_scalar, neg_y, &s).to_bytes();
+
+        // V = s*H - c*Gamma
+        let s_h = scalarmult(&s, h).ok_or(VerificationError::InvalidProof)?;
+        let c_gamma = scalarmult(&c_scalar, gamma).ok_or(VerificationError::InvalidProof)?;
+        let v = (&s_h - &c_gamma.to_cached()).to_full().to_bytes();
+
+        let c_prime = hash_points(&h_string, &proof.0[0..32], &u, &v);
+
+        if c_prime[..] == c[0..16] {
+            proof.to_output()
+        } else {
+            Err(VerificationError::InvalidProof)
+        }
+    }
+}
+
+impl Proof {
+    /// split the proof into `Gamma`, `c` and `s`
+    fn decode(&self) -> Result<(Ge, [u8; 32], Scalar), VerificationError> {
+        let mut gamma = [0; 32];
+        gamma.copy_from_slice(&self.0[0..32]);
+        let gamma = decode_point(&gamma).ok_or(VerificationError::MalformedProof)?;
+
+        let mut c = [0; 32];
+        c[0..16].copy_from_slice(&self.0[32..48]);
+
+        let mut s = [0; 32];
+        s.copy_from_slice(&self.0[48..80]);
+        let s = Scalar::from_bytes_canonical(&s).ok_or(VerificationError::MalformedProof)?;
+
+        Ok((gamma, c, s))
+    }
+
+    /// compute the VRF [`Output`] (`beta`) of this [`Proof`].
+    ///
+    /// This does not check the proof, use [`PublicKey::verify`] for that.
+    pub fn to_output(&self) -> Result<Output, VerificationError> {
+        let (gamma, _, _) = self.decode()?;
+        let cofactor_gamma = gamma.double().double().double();
+
+        let mut ctx = Sha512::new();
+        ctx.update_mut(&[SUITE, 0x03]);
+        ctx.update_mut(&cofactor_gamma.to_bytes());
+
+        Ok(Output(ctx.finalize()))
+    }
+}
+
+/// `a * b + c mod l`, with `a` and `c` below `2^253` and `b` below `2^255`
+fn mul_add(a: &[u8; 32], b: &[u8; 32], c: &[u8; 32]) -> Scalar {
+    let mut limbs = [0u64; 64];
+    for i in 0..32 {
+        limbs[i] += c[i] as u64;
+        for j in 0..32 {
+            limbs[i + j] += a[i] as u64 * b[j] as u64;
+        }
+    }
+
+    let mut wide = [0u8; 64];
+    let mut carry = 0;
+    for (byte, limb) in wide.iter_mut().zip(limbs.iter()) {
+        let v = limb + carry;
+        *byte = v as u8;
+        carry = v >> 8;
+    }
+    let scalar = Scalar::reduce_from_wide_bytes(&wide);
+
+    limbs.iter_mut().for_each(|limb| limb.scrub());
+    wide.scrub();
+    scalar
+}
+
+/// Point in extended homogeneous coordinates, for the constant time scalar
+/// multiplications of the prover (`Ge` only offers a variable time one).
+#[derive(Clone)]
+struct ExtendedPoint {
+    x: Fe,
+    y: Fe,
+    z: Fe,
+    t: Fe,
+}
+
+impl ExtendedPoint {
+    const IDENTITY: Self = Self {
+        x: Fe::ZERO,
+        y: Fe::ONE,
+        z: Fe::ONE,
+        t: Fe::ZERO,
+    };
+
+    /// decode a compressed point (RFC8032 5.1.3)
+    fn from_bytes(bytes: &[u8; 32]) -> Option<Self> {
+        let y = Fe::from_bytes(bytes);
+        let y2 = y.square();
+        let u = &y2 - &Fe::ONE;
+        let v = &(&y2 * &Fe::D) + &Fe::ONE;
+        let v3 = &v.square() * &v;
+        let v7 = &v3.square() * &v;
+        let mut x = &(&(&v7 * &u).pow25523() * &v3) * &u;
+
+        let vxx = &x.square() * &v;
+        if (&vxx - &u).is_nonzero() {
+            if (&vxx + &u).is_nonzero() {
+                return None;
+            }
+            x = &x * &Fe::SQRTM1;
+        }
+        if x.is_negative() != ((bytes[31] >> 7) == 1) {
+            x = -&x;
+        }
+
+        let t = &x * &y;
+        Some(Self {
+            x,
+            y,
+            z: Fe::ONE,
+            t,
+        })
+    }
+
+    fn to_bytes(&self) -> [u8; 32] {
+        let recip = self.z.invert();
+        let x = &self.x * &recip;
+        let mut bytes = (&self.y * &recip).to_bytes();
+        bytes[31] ^= (x.is_negative() as u8) << 7;
+        bytes
+    }
+
+    /// complete addition of twisted Edwards points (add-2008-hwcd-3)
+    fn add(&self, other: &Self) -> Self {
+        let a = &(&self.y - &self.x) * &(&other.y - &other.x);
+        let b = &(&self.y + &self.x) * &(&other.y + &other.x);
+        let c = &(&self.t * &Fe::D2) * &other.t;
+        let zz = &self.z * &other.z;
+        let d = &zz + &zz;
+        let e = &b - &a;
+        let f = &d - &c;
+        let g = &d + &c;
+        let h = &b + &a;
+        Self {
+            x: &e * &f,
+            y: &g * &h,
+            z: &f * &g,
+            t: &e * &h,
+        }
+    }
+
+    /// select `b` if `choice` is 1 and `a` if it is 0, in constant time
+    fn select(a: &Self, b: &Self, choice: u8) -> Self {
+        fn select_fe(a: &Fe, b: &Fe, mask: u8) -> Fe {
+            let (a, b) = (a.to_bytes(), b.to_bytes());
+            let mut out = [0; 32];
+            for i in 0..32 {
+                out[i] = a[i] ^ (mask & (a[i] ^ b[i]));
+            }
+            Fe::from_bytes(&out)
+        }
+
+        let mask = 0u8.wrapping_sub(choice);
+        Self {
+            x: select_fe(&a.x, &b.x, mask),
+            y: select_fe(&a.y, &b.y, mask),
+            z: select_fe(&a.z, &b.z, mask),
+            t: select_fe(&a.t, &b.t, mask),
+        }
+    }
+
+    /// constant time double-and-add-always, `scalar` in little endian
+    fn scalarmult(&self, scalar: &[u8; 32]) -> Self {
+        let mut r = Self::IDENTITY;
+        for i in (0..256).rev() {
+            r = r.add(&r);
+            let sum = r.add(self);
+            r = Self::select(&r, &sum, (scalar[i / 8] >> (i % 8)) & 1);
+        }
+        r
+    }
+}
+
+/// decode a compressed point, unlike `Ge::from_bytes` the returned point is
+/// not negated.
+fn decode_point(bytes: &[u8; 32]) -> Option<Ge> {
+    let mut flipped = *bytes;
+    flipped[31] ^= 0x80;
+    Ge::from_bytes(&flipped)
+}
+
+/// variable time scalar multiplication of an arbitrary point
+fn scalarmult(scalar: &Scalar, point: Ge) -> Option<Ge> {
+    let partial = GePartial::double_scalarmult_vartime(scalar, point, &Scalar::ZERO);
+    decode_point(&partial.to_bytes())
+}
+
+/// check the encoded `y` coordinate is reduced modulo `2^255 - 19`
+fn is_canonical(bytes: &[u8; 32]) -> bool {
+    let mut y = *bytes;
+    y[31] &= 0x7f;
+    Fe::from_bytes(&y).to_bytes() == y
+}
+
+/// check whether the encoded point is in the small subgroup
+fn has_small_order(bytes: &[u8; 32]) -> bool {
+    match decode_point(bytes) {
+        Some(point) => {
+            let cofactor_point = point.double().double().double();
+            let mut identity = [0; 32];
+            identity[0] = 1;
+            cofactor_point.to_bytes() == identity
+        }
+        None => true,
+    }
+}
+
+/// the Legendre symbol of `e`: `e^((p - 1) / 2)`
+fn chi(e: &Fe) -> Fe {
+    let t = e.pow25523().square_repeatdly(2);
+    &t * &e.square()
+}
+
+/// Elligator2 map from a uniform string to a point in the prime subgroup
+fn from_uniform(r: &[u8; 32]) -> Option<[u8; 32]> {
+    let mut s = *r;
+    let x_sign = s[31] & 0x80;
+    s[31] &= 0x7f;
+
+    // x = -A / (1 + 2 r^2)
+    let r = Fe::from_bytes(&s);
+    let rr2 = &(&r.square() + &r.square()) + &Fe::ONE;
+    let mut x = -&(&CURVE25519_A * &rr2.invert());
+
+    // e = chi(x^3 + A x^2 + x)
+    let x2 = x.square();
+    let x3 = &x * &x2;
+    let e = chi(&(&(&x3 + &x) + &(&x2 * &CURVE25519_A)));
+
+    // x = -x - A when e == -1
+    let e_is_minus_1 = e.to_bytes()[1] & 1 == 1;
+    if e_is_minus_1 {
+        x = &(-&x) - &CURVE25519_A;
+    }
+
+    // y = (x - 1) / (x + 1) on the birationally equivalent edwards curve
+    let y = &(&x - &Fe::ONE) * &(&x + &Fe::ONE).invert();
+    let mut s = y.to_bytes();
+    s[31] |= x_sign;
+
+    // multiply by the cofactor
+    let point = decode_point(&s)?;
+    Some(point.double().double().double().to_bytes())
+}
+
+/// hash the public key and the input to a point of the curve
+fn hash_to_curve(public_key: &[u8; 32], alpha: &[u8]) -> Option<[u8; 32]> {
+    let mut ctx = Sha512::new();
+    ctx.update_mut(&[SUITE, 0x01]);
+    ctx.update_mut(public_key);
+    ctx.update_mut(alpha);
+    let digest = ctx.finalize();
+
+    let mut r = [0; 32];
+    r.copy_from_slice(&digest[0..32]);
+    r[31] &= 0x7f;
+
+    from_uniform(&r)
+}
+
+/// the challenge of the proof, from the encoded points of the protocol
+fn hash_points(h: &[u8], gamma: &[u8], u: &[u8], v: &[u8]) -> [u8; 16] {
+    let mut ctx = Sha512::new();
+    ctx.update_mut(&[SUITE, 0x02]);
+    ctx.update_mut(h);
+    ctx.update_mut(gamma);
+    ctx.update_mut(u);
+    ctx.update_mut(v);
+    let digest = ctx.finalize();
+
+    let mut c = [0; 16];
+    c.copy_from_slice(&digest[0..16]);
+    c
+}
+
+/* Drop ******************************************************************** */
+
+impl Drop for SecretKey {
+    fn drop(&mut self) {
+        self.0.scrub()
+    }
+}
+
+/* Format ****************************************************************** */
+
+impl fmt::Display for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&hex::encode(self.0))
+    }
+}
+
+impl fmt::Display for Proof {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&hex::encode(self.0))
+    }
+}
+
+impl fmt::Display for Output {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&hex::encode(self.0))
+    }
+}
+
+impl fmt::Debug for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PublicKey")
+            .field(&hex::encode(self.0))
+            .finish()
+    }
+}
+
+impl fmt::Debug for Proof {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Proof").field(&hex::encode(self.0)).finish()
+    }
+}
+
+impl fmt::Debug for Output {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Output").field(&hex::encode(self.0)).finish()
+    }
+}
+
+/// conveniently provide a proper implementation to debug for the
+/// SecretKey types when only *testing* the library
+#[cfg(test)]
+impl fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple(&format!(
+            "SecretKey<{typename}>",
+            typename = type_name::<Self>()
+        ))
+        .field(&hex::encode(self.0))
+        .finish()
+    }
+}
+
+/// conveniently provide an incomplete implementation of Debug for the
+/// SecretKey.
+#[cfg(not(test))]
+impl fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(&format!(
+            "SecretKey<{typename}>",
+            typename = type_name::<Self>()
+        ))
+        .finish_non_exhaustive()
+    }
+}
+
+/* Conversion ************************************************************** */
+
+impl AsRef<[u8]> for PublicKey {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_ref()
+    }
+}
+
+impl AsRef<[u8]> for Proof {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_ref()
+    }
+}
+
+impl AsRef<[u8]> for Output {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_ref()
+    }
+}
+
+impl From<[u8; Self::SIZE]> for SecretKey {
+    fn from(bytes: [u8; Self::SIZE]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<[u8; Self::SIZE]> for PublicKey {
+    fn from(bytes: [u8; Self::SIZE]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<[u8; Self::SIZE]> for Proof {
+    fn from(bytes: [u8; Self::SIZE]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<[u8; Self::SIZE]> for Output {
+    fn from(bytes: [u8; Self::SIZE]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for PublicKey {
+    type Error = TryFromPublicKeyError;
+    fn try_from(value: &'a [u8]) -> Result<Self, Self::Error> {
+        if value.len() != Self::SIZE {
+            Err(Self::Error::InvalidSize)
+        } else {
+            let mut s = Self::zero();
+            s.0.copy_from_slice(value);
+            Ok(s)
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for Proof {
+    type Error = TryFromProofError;
+    fn try_from(value: &'a [u8]) -> Result<Self, Self::Error> {
+        if value.len() != Self::SIZE {
+            Err(Self::Error::InvalidSize)
+        } else {
+            let mut s = Self::zero();
+            s.0.copy_from_slice(value);
+            Ok(s)
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for Output {
+    type Error = TryFromOutputError;
+    fn try_from(value: &'a [u8]) -> Result<Self, Self::Error> {
+        if value.len() != Self::SIZE {
+            Err(Self::Error::InvalidSize)
+        } else {
+            let mut s = Self::zero();
+            s.0.copy_from_slice(value);
+            Ok(s)
+        }
+    }
+}
+
+impl FromStr for PublicKey {
+    type Err = hex::FromHexError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut r = Self::zero();
+        hex::decode_to_slice(s, &mut r.0)?;
+        Ok(r)
+    }
+}
+
+impl FromStr for Proof {
+    type Err = hex::FromHexError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut r = Self::zero();
+        hex::decode_to_slice(s, &mut r.0)?;
+        Ok(r)
+    }
+}
+
+impl FromStr for Output {
+    type Err = hex::FromHexError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut r = Self::zero();
+        hex::decode_to_slice(s, &mut r.0)?;
+        Ok(r)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck::{Arbitrary, Gen};
+    use quickcheck_macros::quickcheck;
+
+    impl Arbitrary for SecretKey {
+        fn arbitrary(g: &mut Gen) -> Self {
+            let mut s = Self::zero();
+            s.0.iter_mut().for_each(|byte| {
+                *byte = u8::arbitrary(g);
+            });
+            s
+        }
+    }
+
+    // test vectors 10, 11 and 12 of draft-irtf-cfrg-vrf-03
+    // (ECVRF-ED25519-SHA512-Elligator2), as found in the test vectors of
+    // cardano-base
+    const VECTORS: [(&str, &str, &str, &str, &str); 3] = [
+        (
+            "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60",
+            "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+            "",
+            "b6b4699f87d56126c9117a7da55bd0085246f4c56dbc95d20172612e9d38e8d7ca65e573a126ed88d4e30a46f80a666854d675cf3ba81de0de043c3774f061560f55edc256a787afe701677c0f602900",
+            "5b49b554d05c0cd5a5325376b3387de59d924fd1e13ded44648ab33c21349a603f25b84ec5ed887995b33da5e3bfcb87cd2f64521c4c62cf825cffabbe5d31cc",
+        ),
+        (
+            "4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb",
+            "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c",
+            "72",
+            "ae5b66bdf04b4c010bfe32b2fc126ead2107b697634f6f7337b9bff8785ee111200095ece87dde4dbe87343f6df3b107d91798c8a7eb1245d3bb9c5aafb093358c13e6ae1111a55717e895fd15f99f07",
+            "94f4487e1b2fec954309ef1289ecb2e15043a2461ecc7b2ae7d4470607ef82eb1cfa97d84991fe4a7bfdfd715606bc27e2967a6c557cfb5875879b671740b7d8",
+        ),
+        (
+            "c5aa8df43f9f837bedb7442f31dcb7b166d38535076f094b85ce3a2e0b4458f7",
+            "fc51cd8e6218a1a38da47ed00230f0580816ed13ba3303ac5deb911548908025",
+            "af82",
+            "dfa2cba34b611cc8c833a6ea83b8eb1bb5e2ef2dd1b0c481bc42ff36ae7847f6ab52b976cfd5def172fa412defde270c8b8bdfbaae1c7ece17d9833b1bcf31064fff78ef493f820055b561ece45e1009",
+            "2031837f582cd17a9af9e0c7ef5a6540e3453ed894b62c293686ca3c1e319dde9d0aa489a4b59a9594fc2328bc3deff3c8a0929a369a72b1180a596e016b5ded",
+        ),
+    ];
+
+    #[test]
+    fn test_vectors() {
+        for (sk, pk, alpha, proof, output) in VECTORS {
+            let mut seed = [0; SecretKey::SIZE];
+            hex::decode_to_slice(sk, &mut seed).unwrap();
+            let sk = SecretKey::from(seed);
+            let pk: PublicKey = pk.parse().unwrap();
+            let alpha = hex::decode(alpha).unwrap();
+            let proof: Proof = proof.parse().unwrap();
+            let output: Output = output.parse().unwrap();
+
+            assert_eq!(sk.public_key(), pk);
+            assert_eq!(sk.prove(&alpha), Ok(proof));
+            assert_eq!(pk.verify(&alpha, &proof), Ok(output));
+            assert_eq!(proof.to_output(), Ok(output));
+        }
+    }
+
+    #[test]
+    fn reject_wrong_input() {
+        let (_, pk, _, proof, _) = VECTORS[0];
+        let pk: PublicKey = pk.parse().unwrap();
+        let proof: Proof = proof.parse().unwrap();
+
+        assert_eq!(
+            pk.verify([0x72], &proof),
+            Err(VerificationError::InvalidProof)
+        );
+    }
+
+    #[test]
+    fn reject_small_order_key() {
+        let mut identity = [0; PublicKey::SIZE];
+        identity[0] = 1;
+        let proof: Proof = VECTORS[0].3.parse().unwrap();
+
+        assert_eq!(
+            PublicKey::from(identity).verify([], &proof),
+            Err(VerificationError::InvalidPublicKey)
+        );
+    }
+
+    #[quickcheck]
+    fn prove_verify_works(secret_key: SecretKey, alpha: Vec<u8>) -> bool {
+        let proof = secret_key.prove(&alpha).unwrap();
+
+        secret_key.public_key().verify(&alpha, &proof) == proof.to_output()
+    }
+}
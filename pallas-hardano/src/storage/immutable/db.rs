@@ -0,0 +1,689 @@
+use std::{
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+use pallas_codec::minicbor;
+use pallas_traverse::{wellknown::GenesisValues, MultiEraHeader};
+
+use super::{
+    build_stack_of_chunk_names, chunk, primary, secondary, Block, ChunkName, Error, Point,
+};
+
+pub type FallibleHeader = Result<Header, chunk::Error>;
+
+/// A block header as stored in the ImmutableDB, read without reading the rest
+/// of the block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+    pub slot: u64,
+    pub hash: [u8; 32],
+    /// Era tag of the block that contains the header, as found in the
+    /// hard-fork combinator envelope of the block (0 for epoch boundary blocks)
+    pub era_tag: u8,
+    pub cbor: Vec<u8>,
+}
+
+impl Header {
+    pub fn point(&self) -> Point {
+        Point::Specific(self.slot, self.hash.to_vec())
+    }
+
+    pub fn decode(&self) -> Result<MultiEraHeader<'_>, pallas_traverse::Error> {
+        match self.era_tag {
+            0 | 1 => MultiEraHeader::decode(0, Some(self.era_tag), &self.cbor),
+            x => MultiEraHeader::decode(x - 1, None, &self.cbor),
+        }
+    }
+}
+
+/// The secondary index of a chunk, along with the slot of each of its entries
+struct ChunkIndex {
+    name: ChunkName,
+    entries: Vec<secondary::Entry>,
+    slots: Vec<u64>,
+    chunk_size: u64,
+}
+
+// Relative slot 0 of every chunk is reserved for an epoch boundary block, in
+// which case the secondary index holds the epoch instead of the slot.
+fn entry_slot(entry: &secondary::Entry, is_ebb: bool, genesis: &GenesisValues) -> u64 {
+    let value = u64::from_be_bytes(entry.block_or_ebb);
+
+    match is_ebb {
+        true => genesis.relative_slot_to_absolute(value, 0),
+        false => value,
+    }
+}
+
+fn has_ebb(dir: &Path, name: &str) -> Result<bool, chunk::Error> {
+    let primary = dir.join(name).with_extension("primary");
+    let primary = File::open(primary)
+        .map_err(|e| chunk::Error::SecondaryIndexError(secondary::Error::CannotOpenFile(e)))?;
+    let first = primary::Reader::open(primary)
+        .map_err(secondary::Error::PrimaryIndexError)
+        .map_err(chunk::Error::SecondaryIndexError)?
+        .next()
+        .transpose()
+        .map_err(secondary::Error::PrimaryIndexError)
+        .map_err(chunk::Error::SecondaryIndexError)?;
+
+    Ok(matches!(first, Some(primary::Entry::Occupied(0, _))))
+}
+
+/// Slot of the first block of a chunk, reading only the beginning of its
+/// indexes
+fn first_slot(
+    dir: &Path,
+    name: &str,
+    genesis: &GenesisValues,
+) -> Result<Option<u64>, chunk::Error> {
+    let first = secondary::read_entries(dir, name)
+        .map_err(chunk::Error::SecondaryIndexError)?
+        .next()
+        .transpose()
+        .map_err(chunk::Error::SecondaryIndexError)?;
+
+    match first {
+        Some(entry) => Ok(Some(entry_slot(&entry, has_ebb(dir, name)?, genesis))),
+        None => Ok(None),
+    }
+}
+
+impl ChunkIndex {
+    fn load(dir: &Path, name: &str, genesis: &GenesisValues) -> Result<Self, chunk::Error> {
+        let entries = secondary::read_entries(dir, name)
+            .map_err(chunk::Error::SecondaryIndexError)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(chunk::Error::SecondaryIndexError)?;
+
+        let has_ebb = !entries.is_empty() && has_ebb(dir, name)?;
+
+        let slots = entries
+            .iter()
+            .enumerate()
+            .map(|(i, x)| entry_slot(x, has_ebb && i == 0, genesis))
+            .collect();
+
+        let chunk_size = dir
+            .join(name)
+            .with_extension("chunk")
+            .metadata()
+            .map_err(chunk::Error::CannotOpenChunkFile)?
+            .len();
+
+        Ok(Self {
+            name: name.to_owned(),
+            entries,
+            slots,
+            chunk_size,
+        })
+    }
+
+    fn block_size(&self, pos: usize) -> u64 {
+        let end = match self.entries.get(pos + 1) {
+            Some(next) => next.block_offset,
+            None => self.chunk_size,
+        };
+
+        end - self.entries[pos].block_offset
+    }
+
+    fn open_chunk(&self, dir: &Path) -> Result<File, chunk::Error> {
+        let path = dir.join(&self.name).with_extension("chunk");
+        File::open(path).map_err(chunk::Error::CannotOpenChunkFile)
+    }
+}
+
+fn read_at(file: &mut File, offset: u64, size: u64) -> Result<Vec<u8>, chunk::Error> {
+    file.seek(SeekFrom::Start(offset))
+        .map_err(chunk::Error::CannotReadBlock)?;
+
+    let mut buf = vec![0u8; size as usize];
+    file.read_exact(&mut buf)
+        .map_err(chunk::Error::CannotReadBlock)?;
+
+    Ok(buf)
+}
+
+fn read_block(index: &ChunkIndex, file: &mut File, pos: usize) -> Result<Block, chunk::Error> {
+    read_at(file, index.entries[pos].block_offset, index.block_size(pos))
+}
+
+fn read_header(index: &ChunkIndex, file: &mut File, pos: usize) -> FallibleHeader {
+    let entry = &index.entries[pos];
+    let header_end = entry.header_offset as u64 + entry.header_size as u64;
+
+    // the era tag is part of the envelope that precedes the header
+    let prefix = read_at(file, entry.block_offset, header_end)?;
+    let mut decoder = minicbor::Decoder::new(&prefix);
+    let era_tag = decoder
+        .array()
+        .and_then(|_| decoder.u8())
+        .map_err(|e| chunk::Error::CannotReadBlock(std::io::Error::other(e)))?;
+
+    Ok(Header {
+        slot: index.slots[pos],
+        hash: entry.header_hash,
+        era_tag,
+        cbor: prefix[entry.header_offset as usize..].to_vec(),
+    })
+}
+
+/// Read-only handle over the immutable chunks of an ImmutableDB directory
+///
+/// Lookups by slot or point use the primary and secondary indexes to perform a
+/// binary search over the chunks and then over the entries of a single chunk,
+/// without reading or decoding any block along the way. As with the rest of
+/// this module, the last chunk of the directory is not considered immutable
+/// and is skipped.
+///
+/// The secondary index stores the epoch of epoch boundary blocks instead of
+/// their slot, the genesis values of the network are used to convert it.
+pub struct ImmutableDb {
+    dir: PathBuf,
+    chunks: Vec<ChunkName>,
+    genesis: GenesisValues,
+}
+
+/// Location of a block: the index of its chunk and its position within the
+/// secondary index of the chunk
+type Position = (usize, usize);
+
+impl ImmutableDb {
+    pub fn open(dir: &Path, genesis: &GenesisValues) -> Result<Self, Error> {
+        let mut chunks = build_stack_of_chunk_names(dir)?;
+        chunks.reverse();
+
+        Ok(Self {
+            dir: dir.to_owned(),
+            chunks,
+            genesis: genesis.clone(),
+        })
+    }
+
+    fn load_chunk(&self, chunk: usize) -> Result<ChunkIndex, Error> {
+        ChunkIndex::load(&self.dir, &self.chunks[chunk], &self.genesis)
+            .map_err(Error::ChunkReadError)
+    }
+
+    /// Index of the last chunk whose first block has a slot lower or equal to
+    /// `slot`, if any. Chunks without blocks are skipped.
+    fn find_chunk(&self, slot: u64) -> Result<Option<usize>, Error> {
+        let (mut left, mut right) = (0, self.chunks.len());
+        let mut found = None;
+
+        while left < right {
+            let mid = left + (right - left) / 2;
+
+            let mut probe = mid;
+            let mut probe_slot = None;
+
+            while probe < right && probe_slot.is_none() {
+                probe_slot = first_slot(&self.dir, &self.chunks[probe], &self.genesis)
+                    .map_err(Error::ChunkReadError)?;
+
+                if probe_slot.is_none() {
+                    probe += 1;
+                }
+            }
+
+            match probe_slot {
+                Some(x) if x <= slot => {
+                    found = Some(probe);
+                    left = probe + 1;
+                }
+                _ => right = mid,
+            }
+        }
+
+        Ok(found)
+    }
+
+    /// Position of the first block whose slot is greater or equal to `slot`
+    fn seek_slot(&self, slot: u64) -> Result<Option<(Position, ChunkIndex)>, Error> {
+        let mut chunk = self.find_chunk(slot)?.unwrap_or_default();
+
+        while chunk < self.chunks.len() {
+            let index = self.load_chunk(chunk)?;
+            let pos = index.slots.partition_point(|x| *x < slot);
+
+            if pos < index.entries.len() {
+                return Ok(Some(((chunk, pos), index)));
+            }
+
+            chunk += 1;
+        }
+
+        Ok(None)
+    }
+
+    /// Position of the block at the given point. A point with an empty hash
+    /// matches the first block whose slot is greater or equal to the slot of
+    /// the point, in line with [`read_blocks_from_point`](super::read_blocks_from_point).
+    fn seek_point(&self, point: &Point) -> Result<Option<Position>, Error> {
+        let (slot, hash) = match point {
+            Point::Origin => return Ok(Some((0, 0))),
+            Point::Specific(slot, hash) => (*slot, hash),
+        };
+
+        let ((chunk, pos), index) = match self.seek_slot(slot)? {
+            Some(x) => x,
+            None => return Ok(None),
+        };
+
+        if hash.is_empty() {
+            return Ok(Some((chunk, pos)));
+        }
+
+        // an epoch boundary block shares its slot with the next block
+        let found = (pos..index.entries.len())
+            .take_while(|i| index.slots[*i] == slot)
+            .find(|i| index.entries[*i].header_hash.as_slice() == hash.as_slice());
+
+        Ok(found.map(|pos| (chunk, pos)))
+    }
+
+    fn cursor(&self, position: Position) -> Cursor {
+        Cursor {
+            dir: self.dir.clone(),
+            chunks: self.chunks.clone(),
+            genesis: self.genesis.clone(),
+            next_chunk: position.0,
+            current: None,
+            pos: position.1,
+        }
+    }
+
+    /// Point of the last immutable block, if any
+    pub fn tip(&self) -> Result<Option<Point>, Error> {
+        for chunk in (0..self.chunks.len()).rev() {
+            let index = self.load_chunk(chunk)?;
+
+            if let (Some(slot), Some(entry)) = (index.slots.last(), index.entries.last()) {
+                return Ok(Some(Point::Specific(*slot, entry.header_hash.to_vec())));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Point of the first block whose slot is greater or equal to `slot`
+    pub fn find_slot(&self, slot: u64) -> Result<Option<Point>, Error> {
+        let found = self.seek_slot(slot)?.map(|((_, pos), index)| {
+            Point::Specific(index.slots[pos], index.entries[pos].header_hash.to_vec())
+        });
+
+        Ok(found)
+    }
+
+    /// Reads the block at the given point, if it exists
+    pub fn read_block(&self, point: &Point) -> Result<Option<Block>, Error> {
+        if let Point::Specific(_, hash) = point {
+            if hash.is_empty() {
+                return Err(Error::CannotFindBlock(point.clone()));
+            }
+        }
+
+        match self.seek_point(point)? {
+            Some(position) => self.cursor(position).next_block().transpose(),
+            None => Ok(None),
+        }
+        .map_err(Error::ChunkReadError)
+    }
+
+    /// Reads the block with the given hash, if it exists. There's no index by
+    /// hash, so this scans the secondary index of every chunk, from the most
+    /// recent one, without reading any block.
+    pub fn read_block_by_hash(&self, hash: &[u8]) -> Result<Option<Block>, Error> {
+        for chunk in (0..self.chunks.len()).rev() {
+            let index = self.load_chunk(chunk)?;
+
+            let found = index
+                .entries
+                .iter()
+                .position(|x| x.header_hash.as_slice() == hash);
+
+            if let Some(pos) = found {
+                return self
+                    .cursor((chunk, pos))
+                    .next_block()
+                    .transpose()
+                    .map_err(Error::ChunkReadError);
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Returns a lazy iterator over the blocks starting at the given point,
+    /// which is included. Chunks are opened one at a time, as the iterator
+    /// advances.
+    ///
+    /// # Errors
+    ///
+    /// * `Error::CannotFindBlock` - If there's no block at the given point.
+    pub fn read_blocks_from(&self, point: &Point) -> Result<Blocks, Error> {
+        match self.seek_point(point)? {
+            Some(position) => Ok(Blocks(self.cursor(position))),
+            None => Err(Error::CannotFindBlock(point.clone())),
+        }
+    }
+
+    /// Returns a lazy iterator over the headers starting at the given point,
+    /// which is included. Only the header of each block is read from the
+    /// chunks, using the offsets of the secondary index.
+    ///
+    /// # Errors
+    ///
+    /// * `Error::CannotFindBlock` - If there's no block at the given point.
+    pub fn read_headers_from(&self, point: &Point) -> Result<Headers, Error> {
+        match self.seek_point(point)? {
+            Some(position) => Ok(Headers(self.cursor(position))),
+            None => Err(Error::CannotFindBlock(point.clone())),
+        }
+    }
+}
+
+struct Cursor {
+    dir: PathBuf,
+    chunks: Vec<ChunkName>,
+    genesis: GenesisValues,
+    next_chunk: usize,
+    current: Option<(ChunkIndex, File)>,
+    pos: usize,
+}
+
+impl Cursor {
+    /// Moves to the chunk that holds the next entry, loading its index if
+    /// needed. Returns `None` once all chunks have been consumed.
+    fn advance(&mut self) -> Option<Result<(), chunk::Error>> {
+        loop {
+            if let Some((index, _)) = &self.current {
+                if self.pos < index.entries.len() {
+                    return Some(Ok(()));
+                }
+
+                self.current = None;
+                self.pos = 0;
+            }
+
+            let name = self.chunks.get(self.next_chunk)?;
+            self.next_chunk += 1;
+
+            let loaded = ChunkIndex::load(&self.dir, name, &self.genesis)
+                .and_then(|index| Ok((index.open_chunk(&self.dir)?, index)));
+
+            match loaded {
+                Ok((file, index)) => self.current = Some((index, file)),
+                Err(err) => {
+                    self.next_chunk = self.chunks.len();
+                    return Some(Err(err));
+                }
+            }
+        }
+    }
+
+    fn next_with<T>(
+        &mut self,
+        read: impl FnOnce(&ChunkIndex, &mut File, usize) -> Result<T, chunk::Error>,
+    ) -> Option<Result<T, chunk::Error>> {
+        if let Err(err) = self.advance()? {
+            return Some(Err(err));
+        }
+
+        let (index, file) = self.current.as_mut()?;
+        let out = read(index, file, self.pos);
+        self.pos += 1;
+
+        Some(out)
+    }
+
+    fn next_block(&mut self) -> Option<Result<Block, chunk::Error>> {
+        self.next_with(read_block)
+    }
+}
+
+/// Lazy iterator over the blocks of an [`ImmutableDb`]
+pub struct Blocks(Cursor);
+
+impl Iterator for Blocks {
+    type Item = super::FallibleBlock;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next_block()
+    }
+}
+
+/// Lazy iterator over the headers of an [`ImmutableDb`]
+pub struct Headers(Cursor);
+
+impl Iterator for Headers {
+    type Item = FallibleHeader;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next_with(read_header)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use pallas_codec::minicbor;
+    use pallas_traverse::{wellknown::GenesisValues, MultiEraBlock};
+
+    use super::{entry_slot, secondary, ImmutableDb, Point};
+    use crate::storage::immutable::Writer;
+    use crate::storage::testing::{point_of, temp_dir};
+
+    fn byron_block(data: &str) -> Vec<u8> {
+        hex::decode(data.trim()).unwrap()
+    }
+
+    /// Byron epochs of 4320 slots, as opposed to 21600 on mainnet
+    fn devnet_genesis() -> GenesisValues {
+        GenesisValues {
+            byron_epoch_length: 86400,
+            ..GenesisValues::mainnet()
+        }
+    }
+
+    /// A minimal mainnet epoch boundary block for the given epoch
+    fn ebb_block(epoch: u64) -> Vec<u8> {
+        let mut e = minicbor::Encoder::new(vec![]);
+
+        e.array(2).unwrap().u8(0).unwrap().array(3).unwrap();
+
+        // header: magic, prev block, body proof, consensus data, extra data
+        e.array(5).unwrap().u32(764824073).unwrap();
+        e.bytes(&[1; 32]).unwrap().bytes(&[2; 32]).unwrap();
+        e.array(2).unwrap().u64(epoch).unwrap();
+        e.array(1).unwrap().u64(epoch * 21600).unwrap();
+        e.array(1).unwrap().map(0).unwrap();
+
+        // body and extra data
+        e.array(0).unwrap();
+        e.array(1).unwrap().map(0).unwrap();
+
+        e.into_writer()
+    }
+
+    #[test]
+    fn iterates_same_blocks_as_reader() {
+        let db = ImmutableDb::open(Path::new("../test_data"), &GenesisValues::mainnet()).unwrap();
+        let expected = super::super::read_blocks(Path::new("../test_data")).unwrap();
+
+        let blocks = db.read_blocks_from(&Point::Origin).unwrap();
+
+        let mut count = 0;
+
+        for (block, expected) in blocks.zip(expected) {
+            assert_eq!(block.unwrap(), expected.unwrap());
+            count += 1;
+        }
+
+        assert_eq!(count, 1777);
+    }
+
+    #[test]
+    fn headers_match_blocks() {
+        let db = ImmutableDb::open(Path::new("../test_data"), &GenesisValues::mainnet()).unwrap();
+
+        let blocks = db.read_blocks_from(&Point::Origin).unwrap();
+        let headers = db.read_headers_from(&Point::Origin).unwrap();
+
+        let mut count = 0;
+
+        for (block, header) in blocks.zip(headers) {
+            let block = block.unwrap();
+            let block = MultiEraBlock::decode(&block).unwrap();
+            let header = header.unwrap();
+
+            assert_eq!(header.slot, block.slot());
+            assert_eq!(header.hash.as_slice(), block.hash().as_ref());
+            assert_eq!(header.decode().unwrap().hash(), block.hash());
+            assert_eq!(header.decode().unwrap().cbor(), block.header().cbor());
+
+            count += 1;
+        }
+
+        assert_eq!(count, 1777);
+    }
+
+    #[test]
+    fn random_access_by_point_and_hash() {
+        let db = ImmutableDb::open(Path::new("../test_data"), &GenesisValues::mainnet()).unwrap();
+
+        let blocks: Vec<_> = db
+            .read_blocks_from(&Point::Origin)
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+
+        // both ends of the chain, both sides of the chunk boundary and a few in between
+        for i in [0, 1, 500, 863, 864, 865, 1500, blocks.len() - 1] {
+            let point = point_of(&blocks[i]);
+
+            assert_eq!(db.read_block(&point).unwrap().as_ref(), Some(&blocks[i]));
+
+            let hash = match &point {
+                Point::Specific(_, hash) => hash.clone(),
+                Point::Origin => unreachable!(),
+            };
+
+            assert_eq!(
+                db.read_block_by_hash(&hash).unwrap().as_ref(),
+                Some(&blocks[i])
+            );
+
+            let mut iter = db.read_blocks_from(&point).unwrap();
+            assert_eq!(iter.next().unwrap().unwrap(), blocks[i]);
+            assert_eq!(iter.count(), blocks.len() - i - 1);
+        }
+
+        assert_eq!(db.read_block_by_hash(&[0; 32]).unwrap(), None);
+
+        let missing = Point::Specific(27756007, vec![0; 32]);
+        assert_eq!(db.read_block(&missing).unwrap(), None);
+        assert!(matches!(
+            db.read_blocks_from(&missing),
+            Err(super::Error::CannotFindBlock(_))
+        ));
+    }
+
+    #[test]
+    fn lookup_by_slot() {
+        let db = ImmutableDb::open(Path::new("../test_data"), &GenesisValues::mainnet()).unwrap();
+
+        let tip = db.tip().unwrap().unwrap();
+        assert_eq!(
+            Some(tip.clone()),
+            super::super::get_tip(Path::new("../test_data")).unwrap()
+        );
+
+        let first = db.find_slot(0).unwrap().unwrap();
+        assert_eq!(first.slot_or_default(), 27756007);
+
+        // slot between two blocks resolves to the next one
+        let next = db.find_slot(27756008).unwrap().unwrap();
+        assert!(next.slot_or_default() > 27756008);
+
+        // fuzzy points iterate from the next block
+        let mut iter = db
+            .read_blocks_from(&Point::Specific(27756008, vec![]))
+            .unwrap();
+        assert_eq!(point_of(&iter.next().unwrap().unwrap()), next);
+
+        assert_eq!(db.find_slot(tip.slot_or_default() + 1).unwrap(), None);
+        assert_eq!(db.find_slot(tip.slot_or_default()).unwrap(), Some(tip));
+    }
+
+    #[test]
+    fn ebb_slot_depends_on_genesis() {
+        let entry = secondary::Entry {
+            block_offset: 0,
+            header_offset: 0,
+            header_size: 0,
+            checksum: 0,
+            header_hash: [0; 32],
+            block_or_ebb: 2u64.to_be_bytes(),
+        };
+
+        // 21600 slots per byron epoch on mainnet, 4320 on the devnet
+        assert_eq!(entry_slot(&entry, true, &GenesisValues::mainnet()), 43200);
+        assert_eq!(entry_slot(&entry, true, &devnet_genesis()), 8640);
+
+        // regular blocks already store their absolute slot
+        assert_eq!(entry_slot(&entry, false, &devnet_genesis()), 2);
+    }
+
+    #[test]
+    fn byron_chunks_with_ebb() {
+        let dir = temp_dir();
+
+        let blocks = [
+            byron_block(include_str!("../../../../test_data/byron4.block")),
+            ebb_block(2),
+            byron_block(include_str!("../../../../test_data/byron8.block")),
+            // lands in a later chunk, so that the previous ones are immutable
+            byron_block(include_str!("../../../../test_data/byron5.block")),
+        ];
+
+        let mut writer = Writer::open(dir.path(), 21600).unwrap();
+        for block in blocks.iter() {
+            writer.append(block).unwrap();
+        }
+        writer.sync().unwrap();
+
+        let db = ImmutableDb::open(dir.path(), &GenesisValues::mainnet()).unwrap();
+
+        let headers: Vec<_> = db
+            .read_headers_from(&Point::Origin)
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+
+        let slots: Vec<_> = headers.iter().map(|x| x.slot).collect();
+        assert_eq!(slots, [43191, 43200, 43211]);
+
+        let tags: Vec<_> = headers.iter().map(|x| x.era_tag).collect();
+        assert_eq!(tags, [1, 0, 1]);
+
+        for (header, block) in headers.iter().zip(blocks.iter()) {
+            assert_eq!(header.point(), point_of(block));
+            assert_eq!(header.decode().unwrap().hash().as_ref(), header.hash);
+        }
+
+        let ebb = point_of(&blocks[1]);
+        assert_eq!(db.read_block(&ebb).unwrap().as_ref(), Some(&blocks[1]));
+        assert_eq!(db.find_slot(43192).unwrap(), Some(ebb));
+        assert_eq!(db.tip().unwrap(), Some(point_of(&blocks[2])));
+
+        // the epoch of the boundary block is converted with the given genesis
+        let db = ImmutableDb::open(dir.path(), &devnet_genesis()).unwrap();
+        let mut headers = db.read_headers_from(&Point::Origin).unwrap();
+        assert_eq!(headers.nth(1).unwrap().unwrap().slot, 8640);
+    }
+}
@@ -9,8 +9,13 @@ use tap::Tap;
 use tracing::debug;
 
 pub mod chunk;
+pub mod db;
 pub mod primary;
 pub mod secondary;
+pub mod writer;
+
+pub use db::ImmutableDb;
+pub use writer::Writer;
 
 // TODO: we should make Point accessible in some crate more generic that
 // `network`.
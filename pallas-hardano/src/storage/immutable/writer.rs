@@ -0,0 +1,710 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+};
+
+use pallas_codec::minicbor;
+use pallas_traverse::MultiEraBlock;
+use tracing::debug;
+
+use super::{chunk, secondary};
+
+const CRC: crc::Crc<u32> = crc::Crc::<u32>::new(&crc::CRC_32_ISO_HDLC);
+
+const PRIMARY_VERSION: u8 = 1;
+
+const SECONDARY_ENTRY_SIZE: u32 = 56;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Cannot write file, error: {0}")]
+    CannotWriteFile(std::io::Error),
+    #[error("Cannot decode block, error: {0}")]
+    CannotDecodeBlock(pallas_traverse::Error),
+    #[error("Block at slot {0} can't be appended after the block at slot {1}")]
+    InvalidBlockOrder(u64, u64),
+    #[error("Block at slot {0} belongs to a chunk before the current chunk {1}")]
+    InvalidChunkOrder(u64, u64),
+    #[error("Cannot read existing chunk, error: {0}")]
+    ChunkReadError(chunk::Error),
+}
+
+/// The fields of a secondary index entry that are derived from the block
+struct BlockInfo {
+    slot: u64,
+    is_ebb: bool,
+    block_or_ebb: u64,
+    header_offset: u16,
+    header_size: u16,
+    header_hash: [u8; 32],
+}
+
+impl BlockInfo {
+    fn from_cbor(block: &[u8]) -> Result<Self, Error> {
+        let decoded = MultiEraBlock::decode(block).map_err(Error::CannotDecodeBlock)?;
+
+        let (is_ebb, block_or_ebb) = match &decoded {
+            MultiEraBlock::EpochBoundary(x) => (true, x.header.consensus_data.epoch_id),
+            _ => (false, decoded.slot()),
+        };
+
+        // blocks are wrapped as [era, [header, ...]], the header being the first
+        // item of the inner array
+        let mut decoder = minicbor::Decoder::new(block);
+        let bad_envelope = |e| Error::CannotDecodeBlock(pallas_traverse::Error::invalid_cbor(e));
+        decoder.array().map_err(bad_envelope)?;
+        decoder.skip().map_err(bad_envelope)?;
+        decoder.array().map_err(bad_envelope)?;
+        let header_offset = decoder.position();
+        decoder.skip().map_err(bad_envelope)?;
+        let header_size = decoder.position() - header_offset;
+
+        let mut header_hash = [0u8; 32];
+        header_hash.copy_from_slice(decoded.hash().as_ref());
+
+        Ok(Self {
+            slot: decoded.slot(),
+            is_ebb,
+            block_or_ebb,
+            header_offset: header_offset as u16,
+            header_size: header_size as u16,
+            header_hash,
+        })
+    }
+}
+
+fn chunk_name(number: u64) -> String {
+    format!("{number:05}")
+}
+
+fn open_file(dir: &Path, number: u64, extension: &str, truncate: bool) -> Result<File, Error> {
+    let path = dir.join(chunk_name(number)).with_extension(extension);
+
+    OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(truncate)
+        .open(path)
+        .map_err(Error::CannotWriteFile)
+}
+
+/// Offsets of a primary index, without its version byte
+fn read_offsets(primary: &mut File) -> Result<Vec<u32>, Error> {
+    let mut bytes = vec![];
+    primary
+        .read_to_end(&mut bytes)
+        .map_err(Error::CannotWriteFile)?;
+
+    let offsets = bytes
+        .get(1..)
+        .unwrap_or_default()
+        .chunks_exact(4)
+        .map(|x| u32::from_be_bytes([x[0], x[1], x[2], x[3]]))
+        .collect();
+
+    Ok(offsets)
+}
+
+/// Reads the block that starts at `offset` of a chunk, returning the offset
+/// where it ends along with its info
+fn read_block_at(chunk: &mut File, offset: u64) -> Result<(u64, BlockInfo), Error> {
+    chunk
+        .seek(SeekFrom::Start(offset))
+        .map_err(Error::CannotWriteFile)?;
+    let mut block = vec![];
+    chunk
+        .read_to_end(&mut block)
+        .map_err(Error::CannotWriteFile)?;
+
+    let mut decoder = minicbor::Decoder::new(&block);
+    decoder
+        .skip()
+        .map_err(|e| Error::CannotDecodeBlock(pallas_traverse::Error::invalid_cbor(e)))?;
+    let block = &block[..decoder.position()];
+
+    let info = BlockInfo::from_cbor(block)?;
+
+    Ok((offset + block.len() as u64, info))
+}
+
+/// Slot and kind of the last block referenced by the primary index of an
+/// existing chunk, if it has any
+fn chunk_tip(dir: &Path, number: u64) -> Result<Option<(u64, bool)>, Error> {
+    let name = chunk_name(number);
+    let open = |extension| File::open(dir.join(&name).with_extension(extension));
+
+    let mut primary = open("primary").map_err(Error::CannotWriteFile)?;
+    let count = read_offsets(&mut primary)?.last().copied().unwrap_or(0) / SECONDARY_ENTRY_SIZE;
+
+    let entries = secondary::read_entries(dir, &name)
+        .map_err(chunk::Error::SecondaryIndexError)
+        .map_err(Error::ChunkReadError)?
+        .take(count as usize)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(chunk::Error::SecondaryIndexError)
+        .map_err(Error::ChunkReadError)?;
+
+    match entries.last() {
+        Some(last) => {
+            let mut chunk = open("chunk").map_err(Error::CannotWriteFile)?;
+            let (_, info) = read_block_at(&mut chunk, last.block_offset)?;
+            Ok(Some((info.slot, info.is_ebb)))
+        }
+        None => Ok(None),
+    }
+}
+
+// Makes the creation of new files durable, fsync of the files themselves
+// doesn't cover their directory entries.
+fn sync_dir(dir: &Path) -> Result<(), Error> {
+    #[cfg(unix)]
+    File::open(dir)
+        .and_then(|x| x.sync_all())
+        .map_err(Error::CannotWriteFile)?;
+
+    #[cfg(not(unix))]
+    let _ = dir;
+
+    Ok(())
+}
+
+struct OpenChunk {
+    number: u64,
+    chunk: File,
+    primary: File,
+    secondary: File,
+    chunk_offset: u64,
+    secondary_offset: u32,
+    /// Number of relative slots that already have an offset in the primary
+    /// index, which always holds one more offset than this
+    filled_slots: u64,
+}
+
+impl OpenChunk {
+    fn create(dir: &Path, number: u64) -> Result<Self, Error> {
+        debug!(number, "creating new chunk");
+
+        let chunk = open_file(dir, number, "chunk", true)?;
+        let secondary = open_file(dir, number, "secondary", true)?;
+        let mut primary = open_file(dir, number, "primary", true)?;
+
+        let mut header = vec![PRIMARY_VERSION];
+        header.extend(0u32.to_be_bytes());
+        primary.write_all(&header).map_err(Error::CannotWriteFile)?;
+
+        Ok(Self {
+            number,
+            chunk,
+            primary,
+            secondary,
+            chunk_offset: 0,
+            secondary_offset: 0,
+            filled_slots: 0,
+        })
+    }
+
+    /// Reopens an existing chunk to continue appending to it. The primary
+    /// index is the last file written for each block, so it is authoritative:
+    /// any secondary entry or chunk data past the last block it references is
+    /// the leftover of an interrupted append and is truncated. Returns the
+    /// chunk along with the slot and kind of its last block.
+    fn resume(dir: &Path, number: u64) -> Result<(Self, Option<(u64, bool)>), Error> {
+        let name = chunk_name(number);
+
+        let mut primary = open_file(dir, number, "primary", false)?;
+        let offsets = read_offsets(&mut primary)?;
+
+        if offsets.is_empty() {
+            return Ok((Self::create(dir, number)?, None));
+        }
+
+        let inconsistent = || {
+            Error::ChunkReadError(chunk::Error::SecondaryIndexError(
+                secondary::Error::InconsistentState,
+            ))
+        };
+
+        // the last offset of the primary index is the end of the last entry
+        // of the secondary index that belongs to the chunk
+        let secondary_offset = *offsets.last().unwrap_or(&0);
+
+        if !secondary_offset.is_multiple_of(SECONDARY_ENTRY_SIZE) {
+            return Err(inconsistent());
+        }
+
+        let mut secondary = open_file(dir, number, "secondary", false)?;
+        secondary
+            .set_len(secondary_offset as u64)
+            .map_err(Error::CannotWriteFile)?;
+        secondary
+            .seek(SeekFrom::End(0))
+            .map_err(Error::CannotWriteFile)?;
+
+        let entries = secondary::read_entries(dir, &name)
+            .map_err(chunk::Error::SecondaryIndexError)
+            .map_err(Error::ChunkReadError)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(chunk::Error::SecondaryIndexError)
+            .map_err(Error::ChunkReadError)?;
+
+        if entries.len() as u32 != secondary_offset / SECONDARY_ENTRY_SIZE {
+            return Err(inconsistent());
+        }
+
+        let mut chunk = open_file(dir, number, "chunk", false)?;
+
+        let (chunk_offset, tip) = match entries.last() {
+            Some(last) => {
+                let (end, info) = read_block_at(&mut chunk, last.block_offset)?;
+                (end, Some((info.slot, info.is_ebb)))
+            }
+            None => (0, None),
+        };
+
+        let filled_slots = offsets.len() as u64 - 1;
+
+        chunk
+            .set_len(chunk_offset)
+            .map_err(Error::CannotWriteFile)?;
+        chunk
+            .seek(SeekFrom::End(0))
+            .map_err(Error::CannotWriteFile)?;
+
+        primary
+            .set_len(1 + 4 * offsets.len() as u64)
+            .map_err(Error::CannotWriteFile)?;
+        primary
+            .seek(SeekFrom::End(0))
+            .map_err(Error::CannotWriteFile)?;
+
+        let chunk = Self {
+            number,
+            chunk,
+            primary,
+            secondary,
+            chunk_offset,
+            secondary_offset,
+            filled_slots,
+        };
+
+        Ok((chunk, tip))
+    }
+
+    /// Gives the current secondary offset to the relative slots up to `slot`
+    /// (excluded), marking them as empty
+    fn fill_until(&mut self, slot: u64) -> Result<(), Error> {
+        if slot <= self.filled_slots {
+            return Ok(());
+        }
+
+        let count = (slot - self.filled_slots) as usize;
+        let offsets = self.secondary_offset.to_be_bytes().repeat(count);
+
+        self.primary
+            .write_all(&offsets)
+            .map_err(Error::CannotWriteFile)?;
+
+        self.filled_slots = slot;
+
+        Ok(())
+    }
+
+    fn append(&mut self, relative_slot: u64, block: &[u8], info: &BlockInfo) -> Result<(), Error> {
+        self.fill_until(relative_slot)?;
+
+        let mut entry = vec![0u8; SECONDARY_ENTRY_SIZE as usize];
+        let mut view = secondary::layout::View::new(&mut entry);
+        view.block_offset_mut().write(self.chunk_offset);
+        view.header_offset_mut().write(info.header_offset);
+        view.header_size_mut().write(info.header_size);
+        view.checksum_mut().write(CRC.checksum(block));
+        view.header_hash_mut().copy_from_slice(&info.header_hash);
+        view.block_or_ebb_mut()
+            .copy_from_slice(&info.block_or_ebb.to_be_bytes());
+
+        // the primary index is written last, a block isn't part of the chunk
+        // until it is referenced from there
+        self.chunk
+            .write_all(block)
+            .map_err(Error::CannotWriteFile)?;
+        self.secondary
+            .write_all(&entry)
+            .map_err(Error::CannotWriteFile)?;
+
+        self.chunk_offset += block.len() as u64;
+        self.secondary_offset += SECONDARY_ENTRY_SIZE;
+
+        self.primary
+            .write_all(&self.secondary_offset.to_be_bytes())
+            .map_err(Error::CannotWriteFile)?;
+
+        self.filled_slots = relative_slot + 1;
+
+        Ok(())
+    }
+
+    fn sync(&mut self) -> Result<(), Error> {
+        self.chunk.sync_all().map_err(Error::CannotWriteFile)?;
+        self.secondary.sync_all().map_err(Error::CannotWriteFile)?;
+        self.primary.sync_all().map_err(Error::CannotWriteFile)?;
+
+        Ok(())
+    }
+
+    /// Marks every remaining slot of the chunk as empty, as the node does
+    /// before moving to the next chunk, and flushes the chunk to disk
+    fn finalize(mut self, chunk_size: u64) -> Result<(), Error> {
+        debug!(number = self.number, "finalizing chunk");
+
+        // relative slot 0 is reserved for an epoch boundary block
+        self.fill_until(chunk_size + 1)?;
+        self.sync()
+    }
+}
+
+/// Appends blocks to an ImmutableDB directory, producing the same chunk,
+/// primary and secondary index files as the Haskell node.
+///
+/// Each chunk spans `chunk_size` slots (the length of a Byron epoch on the
+/// public networks, 21600). Blocks have to be appended in chain order. When a
+/// block belongs to a later chunk, the current chunk is finalized and synced
+/// to disk before the next one is created, creating empty chunks for any
+/// chunk skipped in between. Call [`Writer::sync`] to make the blocks of the
+/// current chunk durable.
+pub struct Writer {
+    dir: PathBuf,
+    chunk_size: u64,
+    current: Option<OpenChunk>,
+    tip: Option<(u64, bool)>,
+}
+
+impl Writer {
+    /// Opens a directory for appending, creating it if needed. If the
+    /// directory already has chunks, appending continues from the most recent
+    /// one, after the last block found in it or in the chunks before it.
+    pub fn open(dir: &Path, chunk_size: u64) -> Result<Self, Error> {
+        std::fs::create_dir_all(dir).map_err(Error::CannotWriteFile)?;
+
+        let mut chunks = Self::chunks(dir)?;
+
+        let (current, mut tip) = match chunks.pop() {
+            Some(number) => {
+                let (chunk, tip) = OpenChunk::resume(dir, number)?;
+                (Some(chunk), tip)
+            }
+            None => (None, None),
+        };
+
+        // the most recent chunk can be empty, or even be preceded by empty
+        // chunks, if the writer stopped right after rolling over
+        for number in chunks.into_iter().rev() {
+            if tip.is_some() {
+                break;
+            }
+
+            tip = chunk_tip(dir, number)?;
+        }
+
+        Ok(Self {
+            dir: dir.to_owned(),
+            chunk_size,
+            current,
+            tip,
+        })
+    }
+
+    /// Numbers of the existing chunks, in ascending order
+    fn chunks(dir: &Path) -> Result<Vec<u64>, Error> {
+        let mut chunks: Vec<_> = std::fs::read_dir(dir)
+            .map_err(Error::CannotWriteFile)?
+            .map_while(|x| x.ok())
+            .map(|x| x.path())
+            .filter(|x| x.extension().is_some_and(|x| x == "chunk"))
+            .filter_map(|x| x.file_stem()?.to_str()?.parse::<u64>().ok())
+            .collect();
+
+        chunks.sort();
+
+        Ok(chunks)
+    }
+
+    /// Slot of the last appended block, if any
+    pub fn tip_slot(&self) -> Option<u64> {
+        self.tip.map(|(slot, _)| slot)
+    }
+
+    /// Appends a block, given as the era-tagged cbor received from
+    /// blockfetch
+    pub fn append(&mut self, block: &[u8]) -> Result<(), Error> {
+        let info = BlockInfo::from_cbor(block)?;
+
+        // an epoch boundary block shares its slot with the block after it
+        if let Some((tip, tip_is_ebb)) = self.tip {
+            let follows = info.slot > tip || (info.slot == tip && tip_is_ebb && !info.is_ebb);
+
+            if !follows {
+                return Err(Error::InvalidBlockOrder(info.slot, tip));
+            }
+        }
+
+        let number = info.slot / self.chunk_size;
+
+        // reopening an earlier chunk would overwrite it
+        if let Some(current) = &self.current {
+            if number < current.number {
+                return Err(Error::InvalidChunkOrder(info.slot, current.number));
+            }
+        }
+
+        let relative_slot = match info.is_ebb {
+            true => 0,
+            false => info.slot % self.chunk_size + 1,
+        };
+
+        let mut current = match self.current.take() {
+            Some(x) if x.number == number => x,
+            Some(x) => {
+                let next = x.number + 1;
+                x.finalize(self.chunk_size)?;
+
+                for skipped in next..number {
+                    OpenChunk::create(&self.dir, skipped)?.finalize(self.chunk_size)?;
+                }
+
+                let chunk = OpenChunk::create(&self.dir, number)?;
+                sync_dir(&self.dir)?;
+                chunk
+            }
+            None => {
+                let chunk = OpenChunk::create(&self.dir, number)?;
+                sync_dir(&self.dir)?;
+                chunk
+            }
+        };
+
+        let result = current.append(relative_slot, block, &info);
+        self.current = Some(current);
+        result?;
+
+        self.tip = Some((info.slot, info.is_ebb));
+
+        Ok(())
+    }
+
+    /// Flushes the files of the current chunk to disk
+    pub fn sync(&mut self) -> Result<(), Error> {
+        match &mut self.current {
+            Some(x) => x.sync(),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use pallas_traverse::{wellknown::GenesisValues, MultiEraBlock};
+
+    use super::{Error, OpenChunk, Writer};
+    use crate::storage::immutable::{ImmutableDb, Point};
+    use crate::storage::testing::{chunk_blocks, temp_dir};
+
+    fn read_file(dir: &Path, name: &str) -> Vec<u8> {
+        std::fs::read(dir.join(name)).unwrap()
+    }
+
+    #[test]
+    fn writes_same_files_as_node() {
+        let tmp = temp_dir();
+        let dir = tmp.path();
+        let mut writer = Writer::open(dir, 21600).unwrap();
+
+        for block in chunk_blocks("01285") {
+            writer.append(&block).unwrap();
+        }
+
+        writer.sync().unwrap();
+
+        for ext in ["chunk", "secondary"] {
+            let name = format!("01285.{ext}");
+            assert_eq!(
+                read_file(dir, &name),
+                read_file(Path::new("../test_data"), &name)
+            );
+        }
+
+        // the node fills the primary index up to the end of the chunk only
+        // when moving to the next one
+        let written = read_file(dir, "01285.primary");
+        let expected = read_file(Path::new("../test_data"), "01285.primary");
+        assert!(written.len() < expected.len());
+        assert_eq!(written, expected[..written.len()]);
+    }
+
+    #[test]
+    fn rolls_over_and_resumes() {
+        let tmp = temp_dir();
+        let dir = tmp.path();
+        let blocks = chunk_blocks("01285");
+        let (first_half, second_half) = blocks.split_at(blocks.len() / 2);
+
+        // small chunks so that the blocks span many of them, some empty
+        let chunk_size = 100;
+
+        let mut writer = Writer::open(dir, chunk_size).unwrap();
+        for block in first_half {
+            writer.append(block).unwrap();
+        }
+        let tip = writer.tip_slot().unwrap();
+        drop(writer);
+
+        // simulate an interrupted append, which is discarded on resume
+        let last_chunk = dir.join(format!("{:05}.chunk", tip / chunk_size));
+        let mut leftover = std::fs::read(&last_chunk).unwrap();
+        leftover.extend([0x82, 0x01]);
+        std::fs::write(&last_chunk, leftover).unwrap();
+
+        let mut writer = Writer::open(dir, chunk_size).unwrap();
+        assert_eq!(writer.tip_slot(), Some(tip));
+        assert!(matches!(
+            writer.append(first_half.last().unwrap()),
+            Err(Error::InvalidBlockOrder(..))
+        ));
+
+        for block in second_half {
+            writer.append(block).unwrap();
+        }
+        writer.sync().unwrap();
+
+        let last_slot = MultiEraBlock::decode(blocks.last().unwrap())
+            .unwrap()
+            .slot();
+        let first_slot = MultiEraBlock::decode(&blocks[0]).unwrap().slot();
+
+        // every chunk but the last one is finalized, with an offset for each
+        // of its slots plus the one reserved for epoch boundary blocks
+        for number in first_slot / chunk_size..last_slot / chunk_size {
+            let primary = dir.join(format!("{number:05}.primary"));
+            let len = std::fs::metadata(primary).unwrap().len();
+            assert_eq!(len, 1 + 4 * (chunk_size + 2));
+        }
+
+        // everything but the last chunk, which isn't considered immutable, can
+        // be read back
+        let db = ImmutableDb::open(dir, &GenesisValues::mainnet()).unwrap();
+        let read: Vec<_> = db
+            .read_blocks_from(&Point::Origin)
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+
+        assert!(read.len() > blocks.len() / 2);
+        assert_eq!(read, blocks[..read.len()]);
+    }
+
+    #[test]
+    fn resume_after_empty_chunks_keeps_order() {
+        let blocks = chunk_blocks("01285");
+        let chunk_size = 100;
+
+        let tmp = temp_dir();
+        let dir = tmp.path();
+
+        let mut writer = Writer::open(dir, chunk_size).unwrap();
+        for block in &blocks[..2] {
+            writer.append(block).unwrap();
+        }
+        let tip = writer.tip_slot().unwrap();
+        drop(writer);
+
+        // simulate a stop right after rolling over to new chunks
+        let number = tip / chunk_size;
+        OpenChunk::create(dir, number + 1)
+            .unwrap()
+            .finalize(chunk_size)
+            .unwrap();
+        OpenChunk::create(dir, number + 2).unwrap().sync().unwrap();
+
+        let chunk_file = dir.join(format!("{number:05}.chunk"));
+        let chunk = std::fs::read(&chunk_file).unwrap();
+
+        let mut writer = Writer::open(dir, chunk_size).unwrap();
+        assert_eq!(writer.tip_slot(), Some(tip));
+
+        let result = writer.append(&blocks[1]);
+        assert!(matches!(result, Err(Error::InvalidBlockOrder(..))));
+
+        // without any block to seed the order, an earlier chunk is rejected
+        // before it gets overwritten
+        let other = temp_dir();
+        OpenChunk::create(other.path(), number + 1)
+            .unwrap()
+            .sync()
+            .unwrap();
+
+        let mut writer = Writer::open(other.path(), chunk_size).unwrap();
+        assert_eq!(writer.tip_slot(), None);
+
+        let result = writer.append(&blocks[0]);
+        assert!(matches!(result, Err(Error::InvalidChunkOrder(_, n)) if n == number + 1));
+
+        assert_eq!(std::fs::read(&chunk_file).unwrap(), chunk);
+        assert!(!other.path().join(format!("{number:05}.chunk")).exists());
+    }
+
+    #[test]
+    fn resume_drops_entries_missing_from_primary() {
+        let blocks = chunk_blocks("01285");
+        let kept = blocks.len() / 2;
+
+        let tmp = temp_dir();
+        let dir = tmp.path();
+
+        let mut writer = Writer::open(dir, 21600).unwrap();
+        for block in &blocks[..kept] {
+            writer.append(block).unwrap();
+        }
+        writer.sync().unwrap();
+        drop(writer);
+
+        // simulate a crash after the chunk and secondary writes of the next
+        // block but before its primary write
+        let other = temp_dir();
+        let mut writer = Writer::open(other.path(), 21600).unwrap();
+        for block in &blocks[..kept + 1] {
+            writer.append(block).unwrap();
+        }
+        writer.sync().unwrap();
+        drop(writer);
+
+        for ext in ["chunk", "secondary"] {
+            let name = format!("01285.{ext}");
+            std::fs::copy(other.path().join(&name), dir.join(&name)).unwrap();
+        }
+
+        let mut writer = Writer::open(dir, 21600).unwrap();
+        let tip = MultiEraBlock::decode(&blocks[kept - 1]).unwrap().slot();
+        assert_eq!(writer.tip_slot(), Some(tip));
+
+        for block in &blocks[kept..] {
+            writer.append(block).unwrap();
+        }
+        writer.sync().unwrap();
+
+        // the orphan entry was discarded, so the files match the node ones
+        for ext in ["chunk", "secondary"] {
+            let name = format!("01285.{ext}");
+            assert_eq!(
+                read_file(dir, &name),
+                read_file(Path::new("../test_data"), &name)
+            );
+        }
+
+        let written = read_file(dir, "01285.primary");
+        let expected = read_file(Path::new("../test_data"), "01285.primary");
+        assert_eq!(written, expected[..written.len()]);
+    }
+}
@@ -0,0 +1,870 @@
+//! Reader for the ledger state snapshots of the Haskell node
+//!
+//! The `ledger` directory holds snapshots of the ledger state, each one named
+//! after the slot it was taken at. A snapshot is the CBOR of the
+//! `ExtLedgerState` of the hard-fork combinator which, for the Conway era,
+//! wraps the `NewEpochState` of the ledger: account pots, certificate state,
+//! UTxO set, governance state and stake distribution.
+//!
+//! The UTxO set alone takes several gigabytes on mainnet, so the snapshot is
+//! read as a stream. Sections are decoded one at a time, in the order they
+//! appear in the file, and the UTxO entries are yielded one by one. Asking for
+//! a section skips the ones before it, but a section can't be read once the
+//! stream is past it.
+//!
+//! Only the single-file snapshots written by nodes without UTxO-HD are
+//! supported.
+
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{BufRead, BufReader, Read},
+    path::Path,
+};
+
+use pallas_codec::{
+    minicbor::{self, data::Type, Decode, Decoder},
+    utils::Bytes,
+};
+use pallas_crypto::hash::Hash;
+use pallas_network::miniprotocols::localstate::queries_v16::{
+    DRep, DRepState, GovState, PoolParams, StakeAddr,
+};
+use pallas_traverse::{Era, MultiEraOutput};
+
+use crate::storage::immutable::Point;
+
+/// Number of eras in the hard-fork telescope of a Conway snapshot
+const CONWAY_TELESCOPE_LEN: u64 = 7;
+
+/// Encoding version of the snapshot file written by the consensus layer
+const SNAPSHOT_VERSION: u64 = 1;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Cannot read snapshot file, error: {0}")]
+    CannotReadFile(std::io::Error),
+    #[error("Invalid snapshot CBOR, {0}")]
+    InvalidCbor(&'static str),
+    #[error("Cannot decode {0}, error: {1}")]
+    CannotDecode(&'static str, minicbor::decode::Error),
+    #[error("Unsupported snapshot, only the Conway era can be read")]
+    UnsupportedEra,
+    #[error("Section {0} was already read")]
+    SectionConsumed(&'static str),
+}
+
+/// Reads CBOR data items from a byte stream, one head at a time
+///
+/// Items of interest are captured into a buffer and decoded with `minicbor`,
+/// everything else is skipped without being held in memory.
+struct CborStream<R> {
+    inner: R,
+    capture: Option<Vec<u8>>,
+}
+
+impl<R: BufRead> CborStream<R> {
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), Error> {
+        self.inner.read_exact(buf).map_err(Error::CannotReadFile)?;
+
+        if let Some(capture) = &mut self.capture {
+            capture.extend_from_slice(buf);
+        }
+
+        Ok(())
+    }
+
+    fn consume(&mut self, len: u64) -> Result<(), Error> {
+        let mut chunk = (&mut self.inner).take(len);
+
+        let read = match &mut self.capture {
+            Some(capture) => std::io::copy(&mut chunk, capture),
+            None => std::io::copy(&mut chunk, &mut std::io::sink()),
+        }
+        .map_err(Error::CannotReadFile)?;
+
+        if read < len {
+            return Err(Error::CannotReadFile(
+                std::io::ErrorKind::UnexpectedEof.into(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Reads the major type and argument of the next item, the argument being
+    /// `None` for indefinite lengths
+    fn head(&mut self) -> Result<(u8, Option<u64>), Error> {
+        let mut initial = [0u8; 1];
+        self.read_exact(&mut initial)?;
+
+        let major = initial[0] >> 5;
+
+        let arg = match initial[0] & 0x1f {
+            info @ 0..=23 => Some(info as u64),
+            info @ 24..=27 => {
+                let mut buf = [0u8; 8];
+                let len = 1 << (info - 24);
+                self.read_exact(&mut buf[8 - len..])?;
+                Some(u64::from_be_bytes(buf))
+            }
+            31 => None,
+            _ => return Err(Error::InvalidCbor("reserved additional info")),
+        };
+
+        Ok((major, arg))
+    }
+
+    /// Consumes the break marker of an indefinite item if it comes next
+    fn at_break(&mut self) -> Result<bool, Error> {
+        let next = self.inner.fill_buf().map_err(Error::CannotReadFile)?;
+
+        if next.first() == Some(&0xff) {
+            self.read_exact(&mut [0u8; 1])?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// Moves to the next entry of an array or map with the given remaining
+    /// length, returning false once there are no entries left
+    fn next_entry(&mut self, remaining: &mut Option<u64>) -> Result<bool, Error> {
+        match remaining {
+            Some(0) => Ok(false),
+            Some(n) => {
+                *n -= 1;
+                Ok(true)
+            }
+            None => {
+                if self.at_break()? {
+                    *remaining = Some(0);
+                    Ok(false)
+                } else {
+                    Ok(true)
+                }
+            }
+        }
+    }
+
+    fn skip(&mut self) -> Result<(), Error> {
+        match self.head()? {
+            (0 | 1 | 7, Some(_)) => Ok(()),
+            (2 | 3, Some(len)) => self.consume(len),
+            (2 | 3, None) => {
+                while !self.at_break()? {
+                    self.skip()?;
+                }
+                Ok(())
+            }
+            (major @ (4 | 5), mut remaining) => {
+                let per_entry = if major == 5 { 2 } else { 1 };
+
+                while self.next_entry(&mut remaining)? {
+                    for _ in 0..per_entry {
+                        self.skip()?;
+                    }
+                }
+                Ok(())
+            }
+            (6, Some(_)) => self.skip(),
+            _ => Err(Error::InvalidCbor("unexpected break")),
+        }
+    }
+
+    fn skip_n(&mut self, count: u64) -> Result<(), Error> {
+        for _ in 0..count {
+            self.skip()?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads the next item as raw CBOR bytes
+    fn capture(&mut self) -> Result<Vec<u8>, Error> {
+        self.capture = Some(Vec::new());
+        let skipped = self.skip();
+        let bytes = self.capture.take().unwrap_or_default();
+
+        skipped.map(|_| bytes)
+    }
+
+    fn decode<T>(&mut self, what: &'static str) -> Result<T, Error>
+    where
+        T: for<'b> Decode<'b, ()>,
+    {
+        let bytes = self.capture()?;
+        minicbor::decode(&bytes).map_err(|e| Error::CannotDecode(what, e))
+    }
+
+    fn uint(&mut self) -> Result<u64, Error> {
+        match self.head()? {
+            (0, Some(value)) => Ok(value),
+            _ => Err(Error::InvalidCbor("expected unsigned integer")),
+        }
+    }
+
+    fn bytes(&mut self) -> Result<Vec<u8>, Error> {
+        match self.head()? {
+            (2, Some(len)) => {
+                let mut buf = vec![0u8; len as usize];
+                self.read_exact(&mut buf)?;
+                Ok(buf)
+            }
+            _ => Err(Error::InvalidCbor("expected definite byte string")),
+        }
+    }
+
+    fn array(&mut self) -> Result<Option<u64>, Error> {
+        match self.head()? {
+            (4, len) => Ok(len),
+            _ => Err(Error::InvalidCbor("expected array")),
+        }
+    }
+
+    /// Reads the head of a definite array holding at least `min` items,
+    /// returning the number of items after those
+    fn record(&mut self, min: u64) -> Result<u64, Error> {
+        match self.array()? {
+            Some(len) if len >= min => Ok(len - min),
+            _ => Err(Error::InvalidCbor("unexpected record length")),
+        }
+    }
+
+    fn map(&mut self) -> Result<Option<u64>, Error> {
+        match self.head()? {
+            (5, len) => Ok(len),
+            _ => Err(Error::InvalidCbor("expected map")),
+        }
+    }
+
+    fn decode_map<K, V>(&mut self, what: &'static str) -> Result<BTreeMap<K, V>, Error>
+    where
+        K: Ord + for<'b> Decode<'b, ()>,
+        V: for<'b> Decode<'b, ()>,
+    {
+        let mut remaining = self.map()?;
+        let mut map = BTreeMap::new();
+
+        while self.next_entry(&mut remaining)? {
+            let key = self.decode(what)?;
+            let value = self.decode(what)?;
+            map.insert(key, value);
+        }
+
+        Ok(map)
+    }
+}
+
+fn nullable<'b, T>(d: &mut Decoder<'b>) -> Result<Option<T>, minicbor::decode::Error>
+where
+    T: Decode<'b, ()>,
+{
+    if d.datatype()? == Type::Null {
+        d.skip()?;
+        Ok(None)
+    } else {
+        d.decode().map(Some)
+    }
+}
+
+/// Registered stake credential, as kept in the unified map of the ledger
+#[derive(Debug, Clone, PartialEq)]
+pub struct Account {
+    pub rewards: Option<u64>,
+    pub deposit: Option<u64>,
+    pub pool: Option<Bytes>,
+    pub drep: Option<DRep>,
+}
+
+impl<'b, C> Decode<'b, C> for Account {
+    fn decode(d: &mut Decoder<'b>, _ctx: &mut C) -> Result<Self, minicbor::decode::Error> {
+        d.array()?;
+
+        let (rewards, deposit) = match nullable::<(u64, u64)>(d)? {
+            Some((rewards, deposit)) => (Some(rewards), Some(deposit)),
+            None => (None, None),
+        };
+
+        // pointers to the certificates that registered the credential
+        d.skip()?;
+
+        Ok(Account {
+            rewards,
+            deposit,
+            pool: nullable(d)?,
+            drep: nullable(d)?,
+        })
+    }
+}
+
+/// Certificate state of the ledger: DReps, stake pools and stake accounts
+#[derive(Debug, Clone, PartialEq)]
+pub struct CertState {
+    pub dreps: BTreeMap<StakeAddr, DRepState>,
+    pub pools: BTreeMap<Bytes, PoolParams>,
+    pub accounts: BTreeMap<StakeAddr, Account>,
+}
+
+/// Entry of the UTxO set
+#[derive(Debug, Clone, PartialEq)]
+pub struct Utxo {
+    pub tx_hash: Hash<32>,
+    pub index: u64,
+    /// Conway-era CBOR of the output
+    pub output_cbor: Vec<u8>,
+}
+
+impl Utxo {
+    pub fn output(&self) -> Result<MultiEraOutput<'_>, minicbor::decode::Error> {
+        MultiEraOutput::decode(Era::Conway, &self.output_cbor)
+    }
+}
+
+/// Sections of the snapshot, in the order they appear in the file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Position {
+    Certs,
+    UtxoState,
+    Utxos,
+    GovState,
+    Done,
+}
+
+pub struct Snapshot<R> {
+    stream: CborStream<R>,
+    position: Position,
+    remaining_utxos: Option<u64>,
+    tip: Point,
+    epoch: u64,
+    treasury: u64,
+    reserves: u64,
+}
+
+impl Snapshot<BufReader<File>> {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let file = File::open(path).map_err(Error::CannotReadFile)?;
+        Self::from_reader(BufReader::new(file))
+    }
+}
+
+impl<R: BufRead> Snapshot<R> {
+    /// Reads the snapshot up to the certificate state, which is the first
+    /// section that can be requested
+    pub fn from_reader(reader: R) -> Result<Self, Error> {
+        let mut s = CborStream {
+            inner: reader,
+            capture: None,
+        };
+
+        // versioned snapshot: [version, ext ledger state]
+        s.record(2)?;
+        if s.uint()? != SNAPSHOT_VERSION {
+            return Err(Error::InvalidCbor("unknown snapshot version"));
+        }
+
+        // ext ledger state: [ledger state, header state]
+        s.record(2)?;
+
+        // telescope: one [start, end] bound pair per past era, then the
+        // current era as [start bound, ledger state]
+        let eras = s
+            .array()?
+            .ok_or(Error::InvalidCbor("indefinite telescope"))?;
+        if eras != CONWAY_TELESCOPE_LEN {
+            return Err(Error::UnsupportedEra);
+        }
+        s.skip_n(eras - 1)?;
+        s.record(2)?;
+        s.skip()?;
+
+        // versioned shelley ledger state: [version, [tip, state, transition]]
+        s.record(2)?;
+        s.uint()?;
+        s.record(3)?;
+
+        let tip = match s.array()? {
+            Some(0) => Point::Origin,
+            Some(1) => {
+                s.record(3)?;
+                let slot = s.uint()?;
+                s.uint()?;
+                Point::Specific(slot, s.bytes()?)
+            }
+            _ => return Err(Error::InvalidCbor("unexpected tip")),
+        };
+
+        // new epoch state
+        s.record(4)?;
+        let epoch = s.uint()?;
+        // blocks made during the previous and current epochs
+        s.skip_n(2)?;
+
+        // epoch state: [account state, ledger state, snapshots, non-myopic]
+        s.record(2)?;
+        s.record(2)?;
+        let treasury = s.uint()?;
+        let reserves = s.uint()?;
+
+        // ledger state: [cert state, utxo state]
+        s.record(2)?;
+
+        Ok(Self {
+            stream: s,
+            position: Position::Certs,
+            remaining_utxos: None,
+            tip,
+            epoch,
+            treasury,
+            reserves,
+        })
+    }
+
+    /// Point of the last block applied to the snapshot
+    pub fn tip(&self) -> &Point {
+        &self.tip
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    pub fn treasury(&self) -> u64 {
+        self.treasury
+    }
+
+    pub fn reserves(&self) -> u64 {
+        self.reserves
+    }
+
+    /// Skips sections until reaching the given one
+    fn seek(&mut self, target: Position) -> Result<(), Error> {
+        while self.position < target {
+            self.position = match self.position {
+                Position::Certs => {
+                    self.stream.skip()?;
+                    Position::UtxoState
+                }
+                Position::UtxoState => {
+                    self.stream.record(4)?;
+                    self.remaining_utxos = self.stream.map()?;
+                    Position::Utxos
+                }
+                Position::Utxos => {
+                    while self.stream.next_entry(&mut self.remaining_utxos)? {
+                        self.stream.skip_n(2)?;
+                    }
+                    // deposits and fees
+                    self.stream.skip_n(2)?;
+                    Position::GovState
+                }
+                Position::GovState => {
+                    self.stream.skip()?;
+                    Position::Done
+                }
+                Position::Done => Position::Done,
+            };
+        }
+
+        Ok(())
+    }
+
+    pub fn read_certs(&mut self) -> Result<CertState, Error> {
+        if self.position != Position::Certs {
+            return Err(Error::SectionConsumed("certs"));
+        }
+
+        let s = &mut self.stream;
+
+        // cert state: [vstate, pstate, dstate]
+        s.record(3)?;
+
+        let rest = s.record(1)?;
+        let dreps = s.decode_map("dreps")?;
+        s.skip_n(rest)?;
+
+        let rest = s.record(1)?;
+        let pools = s.decode_map("pools")?;
+        s.skip_n(rest)?;
+
+        // dstate, the accounts being the elements of its unified map
+        let rest = s.record(1)?;
+        let umap_rest = s.record(1)?;
+        let accounts = s.decode_map("accounts")?;
+        s.skip_n(umap_rest)?;
+        s.skip_n(rest)?;
+
+        self.position = Position::UtxoState;
+
+        Ok(CertState {
+            dreps,
+            pools,
+            accounts,
+        })
+    }
+
+    /// Iterates over the UTxO set, skipping the certificate state if it
+    /// wasn't read. An iterator left unfinished can be resumed by calling
+    /// this method again.
+    pub fn utxos(&mut self) -> Result<Utxos<'_, R>, Error> {
+        self.seek(Position::Utxos)?;
+
+        if self.position != Position::Utxos {
+            return Err(Error::SectionConsumed("utxos"));
+        }
+
+        Ok(Utxos { snapshot: self })
+    }
+
+    fn next_utxo(&mut self) -> Result<Option<Utxo>, Error> {
+        if !self.stream.next_entry(&mut self.remaining_utxos)? {
+            return Ok(None);
+        }
+
+        let key = self.stream.capture()?;
+        let (tx_hash, index) =
+            minicbor::decode(&key).map_err(|e| Error::CannotDecode("utxo", e))?;
+        let output_cbor = self.stream.capture()?;
+
+        Ok(Some(Utxo {
+            tx_hash,
+            index,
+            output_cbor,
+        }))
+    }
+
+    /// Reads the governance state, skipping any section before it that
+    /// wasn't read
+    pub fn read_gov_state(&mut self) -> Result<GovState, Error> {
+        self.seek(Position::GovState)?;
+
+        if self.position != Position::GovState {
+            return Err(Error::SectionConsumed("gov state"));
+        }
+
+        let gov = self.stream.decode("gov state")?;
+        self.position = Position::Done;
+
+        Ok(gov)
+    }
+}
+
+pub struct Utxos<'a, R> {
+    snapshot: &'a mut Snapshot<R>,
+}
+
+impl<R: BufRead> Iterator for Utxos<'_, R> {
+    type Item = Result<Utxo, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.snapshot.position != Position::Utxos {
+            return None;
+        }
+
+        let next = self.snapshot.next_utxo();
+
+        // the stream can't be trusted past a failure
+        if next.is_err() {
+            self.snapshot.position = Position::Done;
+        }
+
+        next.transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use pallas_codec::{
+        minicbor::Encoder,
+        utils::{AnyUInt, Nullable, TagWrap},
+    };
+    use pallas_network::miniprotocols::{
+        localstate::queries_v16::RationalNumber, localtxsubmission::SMaybe,
+    };
+
+    use super::*;
+
+    fn bound(e: &mut Encoder<Vec<u8>>) {
+        e.array(3)
+            .unwrap()
+            .u8(0)
+            .unwrap()
+            .u8(0)
+            .unwrap()
+            .u8(0)
+            .unwrap();
+    }
+
+    fn credential(byte: u8) -> StakeAddr {
+        (0, Bytes::from(vec![byte; 28])).into()
+    }
+
+    fn pool_params() -> PoolParams {
+        PoolParams {
+            operator: vec![1; 28].into(),
+            vrf_keyhash: vec![2; 32].into(),
+            pledge: AnyUInt::U64(1_000),
+            cost: AnyUInt::U64(340_000_000),
+            margin: RationalNumber {
+                numerator: 1,
+                denominator: 100,
+            },
+            reward_account: vec![0xe1; 29].into(),
+            pool_owners: TagWrap(BTreeSet::from([Bytes::from(vec![3; 28])])),
+            relays: vec![],
+            pool_metadata: Nullable::Null,
+        }
+    }
+
+    fn drep_state() -> DRepState {
+        DRepState {
+            expiry: 120,
+            anchor: SMaybe::None,
+            deposit: AnyUInt::U64(500_000_000),
+            delegs: TagWrap(BTreeSet::from([credential(5)])),
+        }
+    }
+
+    fn gov_state(e: &mut Encoder<Vec<u8>>) {
+        e.array(7).unwrap();
+        // proposals
+        e.array(0).unwrap();
+        // no committee
+        e.array(0).unwrap();
+        // constitution
+        e.array(2).unwrap();
+        e.array(2).unwrap();
+        e.str("https://constitution.example").unwrap();
+        e.bytes(&[4; 32]).unwrap();
+        e.null().unwrap();
+        // current and previous protocol parameters
+        for _ in 0..2 {
+            e.array(31).unwrap();
+            for _ in 0..31 {
+                e.null().unwrap();
+            }
+        }
+        // no future protocol parameters
+        e.array(1).unwrap().u8(0).unwrap();
+        // drep pulsing state
+        e.array(0).unwrap();
+    }
+
+    /// Encodes a Conway snapshot holding the given number of UTxO entries
+    fn snapshot(utxos: u8, indefinite_utxos: bool) -> Vec<u8> {
+        let mut e = Encoder::new(Vec::new());
+
+        e.array(2).unwrap().u8(1).unwrap();
+        e.array(2).unwrap();
+
+        e.array(7).unwrap();
+        for _ in 0..6 {
+            e.array(2).unwrap();
+            bound(&mut e);
+            bound(&mut e);
+        }
+        e.array(2).unwrap();
+        bound(&mut e);
+
+        e.array(2).unwrap().u8(2).unwrap();
+        e.array(3).unwrap();
+        e.array(1).unwrap();
+        e.array(3).unwrap();
+        e.u64(1_000)
+            .unwrap()
+            .u64(50)
+            .unwrap()
+            .bytes(&[9; 32])
+            .unwrap();
+
+        // new epoch state
+        e.array(7).unwrap().u64(3).unwrap();
+        e.map(0).unwrap().map(0).unwrap();
+
+        // epoch state
+        e.array(4).unwrap();
+        e.array(2).unwrap().u64(10).unwrap().u64(20).unwrap();
+
+        // ledger state
+        e.array(2).unwrap();
+
+        // cert state
+        e.array(3).unwrap();
+        e.array(3).unwrap();
+        e.map(1).unwrap();
+        e.encode(credential(5))
+            .unwrap()
+            .encode(drep_state())
+            .unwrap();
+        e.map(0).unwrap().u8(0).unwrap();
+
+        e.array(4).unwrap();
+        e.map(1).unwrap();
+        e.bytes(&[1; 28]).unwrap().encode(pool_params()).unwrap();
+        e.map(0).unwrap().map(0).unwrap().map(0).unwrap();
+
+        e.array(4).unwrap();
+        e.array(2).unwrap();
+        e.map(2).unwrap();
+        e.encode(credential(5)).unwrap();
+        e.array(4).unwrap();
+        e.array(2).unwrap().u64(7).unwrap().u64(2_000_000).unwrap();
+        e.tag(minicbor::data::Tag::new(258))
+            .unwrap()
+            .array(0)
+            .unwrap();
+        e.bytes(&[1; 28]).unwrap();
+        e.array(1).unwrap().u8(2).unwrap();
+        e.encode(credential(6)).unwrap();
+        e.array(4).unwrap();
+        e.null()
+            .unwrap()
+            .array(0)
+            .unwrap()
+            .null()
+            .unwrap()
+            .null()
+            .unwrap();
+        e.map(0).unwrap();
+        e.map(0).unwrap().map(0).unwrap();
+        e.array(0).unwrap();
+
+        // utxo state
+        e.array(6).unwrap();
+        if indefinite_utxos {
+            e.begin_map().unwrap();
+        } else {
+            e.map(utxos as u64).unwrap();
+        }
+        for i in 0..utxos {
+            e.array(2).unwrap().bytes(&[i; 32]).unwrap().u8(i).unwrap();
+            e.map(2).unwrap();
+            e.u8(0).unwrap().bytes(&[0x61; 29]).unwrap();
+            e.u8(1).unwrap().u64(1_000_000 * i as u64).unwrap();
+        }
+        if indefinite_utxos {
+            e.end().unwrap();
+        }
+        e.u64(2_500_000_000).unwrap().u64(400).unwrap();
+        gov_state(&mut e);
+        e.map(0).unwrap().u8(0).unwrap();
+
+        // snapshots and non-myopic rewards
+        e.array(0).unwrap().array(0).unwrap();
+
+        // reward pulser, pool distribution and avvm addresses
+        e.null().unwrap().map(0).unwrap().map(0).unwrap();
+
+        // transition info and header state
+        e.u8(0).unwrap().array(0).unwrap();
+
+        e.into_writer()
+    }
+
+    #[test]
+    fn reads_every_section() {
+        let bytes = snapshot(3, false);
+        let mut snapshot = Snapshot::from_reader(bytes.as_slice()).unwrap();
+
+        assert_eq!(snapshot.tip(), &Point::Specific(1_000, vec![9; 32]));
+        assert_eq!(snapshot.epoch(), 3);
+        assert_eq!(snapshot.treasury(), 10);
+        assert_eq!(snapshot.reserves(), 20);
+
+        let certs = snapshot.read_certs().unwrap();
+        assert_eq!(certs.dreps.get(&credential(5)), Some(&drep_state()));
+        assert_eq!(
+            certs.pools.get(&Bytes::from(vec![1; 28])),
+            Some(&pool_params())
+        );
+        assert_eq!(
+            certs.accounts.get(&credential(5)),
+            Some(&Account {
+                rewards: Some(7),
+                deposit: Some(2_000_000),
+                pool: Some(vec![1; 28].into()),
+                drep: Some(DRep::AlwaysAbstain),
+            })
+        );
+        assert_eq!(certs.accounts[&credential(6)].rewards, None);
+
+        let utxos: Vec<_> = snapshot.utxos().unwrap().map(Result::unwrap).collect();
+        assert_eq!(utxos.len(), 3);
+        assert_eq!(utxos[2].tx_hash, Hash::new([2; 32]));
+        assert_eq!(utxos[2].index, 2);
+        assert_eq!(utxos[2].output().unwrap().value().coin(), 2_000_000);
+
+        let gov = snapshot.read_gov_state().unwrap();
+        assert_eq!(gov.constitution.anchor.url, "https://constitution.example");
+    }
+
+    #[test]
+    fn skips_sections_not_read() {
+        let bytes = snapshot(5, true);
+
+        let mut snapshot = Snapshot::from_reader(bytes.as_slice()).unwrap();
+        let first = snapshot.utxos().unwrap().next().unwrap().unwrap();
+        assert_eq!(first.index, 0);
+        assert_eq!(snapshot.utxos().unwrap().count(), 4);
+
+        let gov = snapshot.read_gov_state().unwrap();
+        assert_eq!(gov.constitution.script, None);
+
+        assert!(matches!(
+            snapshot.read_certs(),
+            Err(Error::SectionConsumed(_))
+        ));
+        assert!(matches!(snapshot.utxos(), Err(Error::SectionConsumed(_))));
+
+        let mut snapshot = Snapshot::from_reader(bytes.as_slice()).unwrap();
+        assert!(snapshot.read_gov_state().is_ok());
+    }
+
+    fn read_node_snapshot(path: &Path) {
+        let mut snapshot = Snapshot::open(path).unwrap();
+        assert!(matches!(snapshot.tip(), Point::Specific(..)));
+
+        let certs = snapshot.read_certs().unwrap();
+        tracing::info!(
+            ?path,
+            pools = certs.pools.len(),
+            accounts = certs.accounts.len(),
+            "read cert state"
+        );
+
+        let mut count = 0;
+        for utxo in snapshot.utxos().unwrap() {
+            utxo.unwrap().output().unwrap();
+            count += 1;
+        }
+        tracing::info!(?path, count, "read utxos");
+
+        snapshot.read_gov_state().unwrap();
+    }
+
+    // Snapshots written by a node are too large to be part of the repo, so
+    // this test reads the ones found in the `ledger_snapshots` folder. It's up
+    // to the developer to copy a snapshot from the `ledger` folder of a node
+    // db into it and run the test with `--ignored`.
+    #[test]
+    #[ignore = "needs node snapshots copied into test_data/ledger_snapshots"]
+    fn reads_available_node_snapshots() {
+        let dir = std::fs::read_dir("../test_data/ledger_snapshots")
+            .expect("ledger_snapshots folder not available");
+
+        let mut read = 0;
+
+        for entry in dir {
+            let path = entry.unwrap().path();
+
+            if path.is_file() {
+                read_node_snapshot(&path);
+                read += 1;
+            }
+        }
+
+        assert!(read > 0, "no snapshots in the ledger_snapshots folder");
+    }
+}
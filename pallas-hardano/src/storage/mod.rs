@@ -1,3 +1,8 @@
 //! Storage compatible with the Haskell Cardano node implementation
 
 pub mod immutable;
+pub mod ledger;
+pub mod volatile;
+
+#[cfg(test)]
+pub(crate) mod testing;
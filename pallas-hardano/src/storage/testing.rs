@@ -0,0 +1,26 @@
+//! Helpers shared by the storage tests
+
+use std::path::Path;
+
+use pallas_traverse::MultiEraBlock;
+use tempfile::TempDir;
+
+use crate::storage::immutable::{chunk, Point};
+
+/// A new empty directory, removed once dropped
+pub(crate) fn temp_dir() -> TempDir {
+    tempfile::tempdir().unwrap()
+}
+
+/// Every block of a chunk of the test data
+pub(crate) fn chunk_blocks(name: &str) -> Vec<Vec<u8>> {
+    chunk::read_blocks(Path::new("../test_data"), name)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect()
+}
+
+pub(crate) fn point_of(block: &[u8]) -> Point {
+    let block = MultiEraBlock::decode(block).unwrap();
+    Point::Specific(block.slot(), block.hash().to_vec())
+}
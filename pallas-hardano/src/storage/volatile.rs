@@ -0,0 +1,424 @@
+//! Reader for the VolatileDB of the Haskell node
+//!
+//! The `volatile` directory holds the most recent blocks, including the ones
+//! from forks that might still be switched to. Blocks are stored in
+//! `blocks-N.dat` files, one after the other, without any index: the node
+//! rebuilds its view of the blocks by parsing every file on startup, which is
+//! what this reader does as well.
+
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+use pallas_codec::minicbor;
+use pallas_crypto::hash::Hash;
+use pallas_traverse::MultiEraBlock;
+use tracing::warn;
+
+use crate::storage::immutable::{Block, Point};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Cannot read directory, error: {0}")]
+    CannotReadDir(std::io::Error),
+    #[error("Cannot read block file, error: {0}")]
+    CannotReadFile(std::io::Error),
+    #[error("Block {0} is not part of the db")]
+    MissingBlock(Hash<32>),
+}
+
+/// What we keep from each block to rebuild the chains, the block itself is
+/// read from its file when needed
+#[derive(Debug, Clone)]
+struct BlockInfo {
+    file: usize,
+    offset: u64,
+    size: u64,
+    slot: u64,
+    number: u64,
+    leader_vrf: Option<Vec<u8>>,
+}
+
+pub struct VolatileDb {
+    files: Vec<PathBuf>,
+    blocks: HashMap<Hash<32>, BlockInfo>,
+    /// Blocks by the hash of their predecessor, `None` standing for the
+    /// genesis of the chain
+    successors: HashMap<Option<Hash<32>>, Vec<Hash<32>>>,
+}
+
+fn block_files(dir: &Path) -> Result<Vec<PathBuf>, Error> {
+    let mut files: Vec<(u64, PathBuf)> = std::fs::read_dir(dir)
+        .map_err(Error::CannotReadDir)?
+        .map_while(|e| e.ok())
+        .map(|e| e.path())
+        .filter_map(|path| {
+            let name = path.file_name()?.to_str()?;
+            let number = name.strip_prefix("blocks-")?.strip_suffix(".dat")?;
+            Some((number.parse().ok()?, path))
+        })
+        .collect();
+
+    files.sort();
+
+    Ok(files.into_iter().map(|(_, path)| path).collect())
+}
+
+/// Key of the successor map for the given point, or `None` if the point
+/// can't be part of the chain
+fn successor_key(point: &Point) -> Option<Option<Hash<32>>> {
+    match point {
+        Point::Origin => Some(None),
+        Point::Specific(_, hash) => {
+            let hash: [u8; 32] = hash.as_slice().try_into().ok()?;
+            Some(Some(Hash::new(hash)))
+        }
+    }
+}
+
+impl VolatileDb {
+    /// Parses every block file of the given `volatile` directory. As the node
+    /// does, parsing of a file stops at the first item that isn't a valid
+    /// block, which happens if the node was stopped while writing to it.
+    pub fn open(dir: &Path) -> Result<Self, Error> {
+        let files = block_files(dir)?;
+
+        let mut db = Self {
+            files,
+            blocks: HashMap::new(),
+            successors: HashMap::new(),
+        };
+
+        for file in 0..db.files.len() {
+            let data = std::fs::read(&db.files[file]).map_err(Error::CannotReadFile)?;
+            db.parse_file(file, &data);
+        }
+
+        Ok(db)
+    }
+
+    fn parse_file(&mut self, file: usize, data: &[u8]) {
+        let mut decoder = minicbor::Decoder::new(data);
+
+        while decoder.position() < data.len() {
+            let start = decoder.position();
+
+            let block = decoder
+                .skip()
+                .ok()
+                .and_then(|_| MultiEraBlock::decode(&data[start..decoder.position()]).ok());
+
+            let block = match block {
+                Some(x) => x,
+                None => {
+                    warn!(file = ?self.files[file], offset = start, "invalid block, skipping rest of file");
+                    break;
+                }
+            };
+
+            let hash = block.hash();
+            let header = block.header();
+            let prev_hash = header.previous_hash();
+
+            let info = BlockInfo {
+                file,
+                offset: start as u64,
+                size: (decoder.position() - start) as u64,
+                slot: block.slot(),
+                number: block.number(),
+                leader_vrf: header.leader_vrf_output().ok(),
+            };
+
+            if self.blocks.insert(hash, info).is_none() {
+                self.successors.entry(prev_hash).or_default().push(hash);
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    pub fn contains(&self, hash: &Hash<32>) -> bool {
+        self.blocks.contains_key(hash)
+    }
+
+    /// Hashes of the blocks that have the block at `point` as predecessor
+    pub fn successors(&self, point: &Point) -> &[Hash<32>] {
+        successor_key(point)
+            .and_then(|x| self.successors.get(&x))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    fn point(&self, hash: &Hash<32>) -> Point {
+        Point::Specific(self.blocks[hash].slot, hash.to_vec())
+    }
+
+    pub fn read_block(&self, hash: &Hash<32>) -> Result<Option<Block>, Error> {
+        let info = match self.blocks.get(hash) {
+            Some(x) => x,
+            None => return Ok(None),
+        };
+
+        let mut file = File::open(&self.files[info.file]).map_err(Error::CannotReadFile)?;
+        file.seek(SeekFrom::Start(info.offset))
+            .map_err(Error::CannotReadFile)?;
+
+        let mut buf = vec![0u8; info.size as usize];
+        file.read_exact(&mut buf).map_err(Error::CannotReadFile)?;
+
+        Ok(Some(buf))
+    }
+
+    fn read_blocks(&self, hashes: &[Hash<32>]) -> Result<Vec<Block>, Error> {
+        hashes
+            .iter()
+            .map(|x| self.read_block(x)?.ok_or(Error::MissingBlock(*x)))
+            .collect()
+    }
+
+    /// Every path from `from` to a block without successors
+    fn paths_from(&self, from: Option<Hash<32>>) -> Vec<Vec<Hash<32>>> {
+        let mut paths = vec![];
+        let mut pending: Vec<Vec<Hash<32>>> = match self.successors.get(&from) {
+            Some(next) => next.iter().map(|x| vec![*x]).collect(),
+            None => vec![],
+        };
+
+        while let Some(path) = pending.pop() {
+            let last = path.last().unwrap();
+
+            match self.successors.get(&Some(*last)) {
+                Some(next) => {
+                    for x in next {
+                        let mut path = path.clone();
+                        path.push(*x);
+                        pending.push(path);
+                    }
+                }
+                None => paths.push(path),
+            }
+        }
+
+        paths
+    }
+
+    /// Predecessors that are not part of the db, from which the stored chains
+    /// start. Normally, the only one is the tip of the ImmutableDB.
+    fn roots(&self) -> Vec<Option<Hash<32>>> {
+        let mut roots: Vec<_> = self
+            .successors
+            .keys()
+            .filter(|x| x.map(|x| !self.blocks.contains_key(&x)).unwrap_or(true))
+            .cloned()
+            .collect();
+
+        roots.sort();
+        roots
+    }
+
+    /// Compares the tips of two candidate chains following Praos: the longest
+    /// chain wins and, on ties, the lowest leader VRF output
+    fn prefer(&self, a: &Hash<32>, b: &Hash<32>) -> std::cmp::Ordering {
+        let (a, b) = (&self.blocks[a], &self.blocks[b]);
+
+        a.number
+            .cmp(&b.number)
+            .then_with(|| match (&a.leader_vrf, &b.leader_vrf) {
+                (Some(a), Some(b)) => b.cmp(a),
+                _ => std::cmp::Ordering::Equal,
+            })
+    }
+
+    fn selected_path(&self, anchor: &Point) -> Option<Vec<Hash<32>>> {
+        self.paths_from(successor_key(anchor)?)
+            .into_iter()
+            .max_by(|a, b| self.prefer(a.last().unwrap(), b.last().unwrap()))
+    }
+
+    /// Tip of the chain the node would select among the ones that extend
+    /// `anchor`, which should be the tip of the ImmutableDB
+    pub fn tip(&self, anchor: &Point) -> Option<Point> {
+        self.selected_path(anchor)
+            .and_then(|x| x.last().map(|x| self.point(x)))
+    }
+
+    /// Blocks of the chain the node would select among the ones that extend
+    /// `anchor`, in chain order
+    pub fn selected_chain(&self, anchor: &Point) -> Result<Vec<Block>, Error> {
+        match self.selected_path(anchor) {
+            Some(path) => self.read_blocks(&path),
+            None => Ok(vec![]),
+        }
+    }
+
+    /// Every branch stored in the db, in chain order. Each branch goes from
+    /// the first block whose predecessor is not in the db to a block without
+    /// successors, so branches of the same fork share their first blocks.
+    pub fn branches(&self) -> Result<Vec<Vec<Block>>, Error> {
+        self.roots()
+            .into_iter()
+            .flat_map(|root| self.paths_from(root))
+            .map(|path| self.read_blocks(&path))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pallas_crypto::hash::Hash;
+    use pallas_traverse::MultiEraBlock;
+
+    use super::{successor_key, BlockInfo, Error, VolatileDb};
+    use crate::storage::immutable::Point;
+    use crate::storage::testing::{chunk_blocks, point_of, temp_dir};
+
+    fn prev_point(block: &[u8]) -> Point {
+        let block = MultiEraBlock::decode(block).unwrap();
+        let prev = block.header().previous_hash().unwrap();
+        Point::Specific(0, prev.to_vec())
+    }
+
+    #[test]
+    fn rebuilds_chains_from_block_files() {
+        let tmp = temp_dir();
+        let dir = tmp.path();
+
+        let main = chunk_blocks("01285")[..10].to_vec();
+        let other = chunk_blocks("01836")[..3].to_vec();
+
+        // blocks are not necessarily written in chain order across files
+        std::fs::write(dir.join("blocks-0.dat"), main[4..].concat()).unwrap();
+        std::fs::write(dir.join("blocks-1.dat"), other.concat()).unwrap();
+
+        // a partially written block at the end of a file is ignored
+        let mut last = main[..4].concat();
+        last.extend(&main[4][..100]);
+        std::fs::write(dir.join("blocks-2.dat"), last).unwrap();
+
+        let db = VolatileDb::open(dir).unwrap();
+        assert_eq!(db.len(), 13);
+
+        assert_eq!(
+            db.successors(&point_of(&main[0])),
+            [MultiEraBlock::decode(&main[1]).unwrap().hash()]
+        );
+        assert!(db.successors(&point_of(&main[9])).is_empty());
+
+        let mut branches = db.branches().unwrap();
+        branches.sort();
+        let mut expected = vec![main.clone(), other.clone()];
+        expected.sort();
+        assert_eq!(branches, expected);
+
+        // only the chain that extends the anchor is selectable
+        let anchor = prev_point(&main[0]);
+        assert_eq!(db.tip(&anchor), Some(point_of(&main[9])));
+        assert_eq!(db.selected_chain(&anchor).unwrap(), main);
+
+        let anchor = point_of(&main[4]);
+        assert_eq!(db.selected_chain(&anchor).unwrap(), main[5..]);
+
+        assert_eq!(db.tip(&Point::Origin), None);
+        assert!(db.selected_chain(&Point::Origin).unwrap().is_empty());
+    }
+
+    #[test]
+    fn errors_when_files_change_after_open() {
+        let tmp = temp_dir();
+        let dir = tmp.path();
+
+        let main = chunk_blocks("01285")[..3].to_vec();
+        let file = dir.join("blocks-0.dat");
+        std::fs::write(&file, main.concat()).unwrap();
+
+        let db = VolatileDb::open(dir).unwrap();
+        let anchor = prev_point(&main[0]);
+        assert_eq!(db.selected_chain(&anchor).unwrap(), main);
+
+        // the index no longer matches the truncated file
+        let truncated = main[0].len() + main[1].len() + 10;
+        std::fs::write(&file, &main.concat()[..truncated]).unwrap();
+
+        assert!(matches!(
+            db.selected_chain(&anchor),
+            Err(Error::CannotReadFile(_))
+        ));
+        assert!(db.branches().is_err());
+    }
+
+    #[test]
+    fn errors_on_blocks_missing_from_index() {
+        let mut db = VolatileDb {
+            files: vec![],
+            blocks: Default::default(),
+            successors: Default::default(),
+        };
+
+        let hash = Hash::new([1; 32]);
+
+        assert!(matches!(
+            db.read_blocks(&[hash]),
+            Err(Error::MissingBlock(x)) if x == hash
+        ));
+
+        db.successors.entry(None).or_default().push(hash);
+        assert!(matches!(db.branches(), Err(Error::MissingBlock(_))));
+    }
+
+    // Chain selection only looks at the indexed data, so forks can be tested
+    // without actual blocks
+    #[test]
+    fn selects_longest_fork() {
+        let mut db = VolatileDb {
+            files: vec![],
+            blocks: Default::default(),
+            successors: Default::default(),
+        };
+
+        let anchor = Hash::new([0; 32]);
+        fn add(db: &mut VolatileDb, id: u8, prev: Hash<32>, number: u64, vrf: u8) -> Hash<32> {
+            let hash = Hash::new([id; 32]);
+            let info = BlockInfo {
+                file: 0,
+                offset: 0,
+                size: 0,
+                slot: number * 10 + id as u64,
+                number,
+                leader_vrf: Some(vec![vrf]),
+            };
+            db.blocks.insert(hash, info);
+            db.successors.entry(Some(prev)).or_default().push(hash);
+            hash
+        }
+
+        // anchor <- a1 <- a2 <- a3
+        //        <- b1 <- b2
+        let a1 = add(&mut db, 1, anchor, 1, 0);
+        let a2 = add(&mut db, 2, a1, 2, 0);
+        let b1 = add(&mut db, 4, anchor, 1, 0);
+        let b2 = add(&mut db, 5, b1, 2, 0);
+        let anchor = Point::Specific(0, anchor.to_vec());
+
+        // equal length, the lowest leader vrf output wins
+        let a3 = add(&mut db, 3, a2, 3, 9);
+        let b3 = add(&mut db, 6, b2, 3, 1);
+
+        assert_eq!(db.selected_path(&anchor), Some(vec![b1, b2, b3]));
+
+        // longest chain wins regardless of the leader vrf output
+        let a4 = add(&mut db, 7, a3, 4, 9);
+
+        assert_eq!(db.selected_path(&anchor), Some(vec![a1, a2, a3, a4]));
+        assert_eq!(db.tip(&anchor), Some(db.point(&a4)));
+        assert_eq!(db.paths_from(successor_key(&anchor).unwrap()).len(), 2);
+    }
+}
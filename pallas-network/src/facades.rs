@@ -21,7 +21,7 @@ use crate::miniprotocols::{
     PROTOCOL_N2N_KEEP_ALIVE, PROTOCOL_N2N_PEER_SHARING, PROTOCOL_N2N_TX_SUBMISSION,
 };
 
-use crate::multiplexer::{self, Bearer, RunningPlexer};
+use crate::multiplexer::{self, Bearer, IngressLimits, RunningPlexer};
 
 #[derive(Debug, Error)]
 pub enum Error {
@@ -42,6 +42,9 @@ pub enum Error {
 
     #[error("handshake version not accepted")]
     IncompatibleVersion,
+
+    #[error("peer only supports initiator mode")]
+    InitiatorOnlyPeer,
 }
 
 pub const DEFAULT_KEEP_ALIVE_INTERVAL_SEC: u64 = 20;
@@ -116,7 +119,7 @@ impl PeerClient {
             .await
             .map_err(Error::ConnectFailure)?;
 
-        let mut plexer = multiplexer::Plexer::new(bearer);
+        let mut plexer = multiplexer::Plexer::with_ingress_limits(bearer, IngressLimits::n2n());
 
         let channel = plexer.subscribe_client(PROTOCOL_N2N_HANDSHAKE);
         let mut handshake = handshake::Client::new(channel);
@@ -152,9 +155,12 @@ impl PeerClient {
         let client = Self {
             plexer,
             keepalive,
-            chainsync: chainsync::Client::new(cs_channel),
-            blockfetch: blockfetch::Client::new(bf_channel),
-            txsubmission: txsubmission::Client::new(txsub_channel),
+            chainsync: chainsync::Client::new(cs_channel)
+                .with_time_limits(chainsync::TimeLimits::n2n()),
+            blockfetch: blockfetch::Client::new(bf_channel)
+                .with_time_limits(blockfetch::TimeLimits::n2n()),
+            txsubmission: txsubmission::Client::new(txsub_channel)
+                .with_time_limits(txsubmission::TimeLimits::n2n()),
             peersharing: peersharing::Client::new(peersharing_channel),
         };
 
@@ -169,7 +175,7 @@ impl PeerClient {
             .await
             .map_err(Error::ConnectFailure)?;
 
-        let mut plexer = multiplexer::Plexer::new(bearer);
+        let mut plexer = multiplexer::Plexer::with_ingress_limits(bearer, IngressLimits::n2n());
 
         let channel = plexer.subscribe_client(PROTOCOL_N2N_HANDSHAKE);
         let mut handshake = handshake::Client::new(channel);
@@ -246,7 +252,7 @@ pub struct PeerServer {
 
 impl PeerServer {
     pub fn new(bearer: Bearer) -> Self {
-        let mut plexer = multiplexer::Plexer::new(bearer);
+        let mut plexer = multiplexer::Plexer::with_ingress_limits(bearer, IngressLimits::n2n());
 
         let hs_channel = plexer.subscribe_server(PROTOCOL_N2N_HANDSHAKE);
         let cs_channel = plexer.subscribe_server(PROTOCOL_N2N_CHAIN_SYNC);
@@ -256,9 +262,12 @@ impl PeerServer {
         let peersharing_channel = plexer.subscribe_server(PROTOCOL_N2N_PEER_SHARING);
 
         let hs = handshake::N2NServer::new(hs_channel);
-        let cs = chainsync::N2NServer::new(cs_channel);
-        let bf = blockfetch::Server::new(bf_channel);
-        let txsub = txsubmission::Server::new(txsub_channel);
+        let cs =
+            chainsync::N2NServer::new(cs_channel).with_time_limits(chainsync::TimeLimits::n2n());
+        let bf =
+            blockfetch::Server::new(bf_channel).with_time_limits(blockfetch::TimeLimits::n2n());
+        let txsub = txsubmission::Server::new(txsub_channel)
+            .with_time_limits(txsubmission::TimeLimits::n2n());
         let keepalive = keepalive::Server::new(keepalive_channel);
         let peersharing = peersharing::Server::new(peersharing_channel);
 
@@ -337,6 +346,193 @@ impl PeerServer {
     }
 }
 
+/// Duplex N2N connection, where both peers act as initiator and responder
+///
+/// Runs the client and server side of each mini-protocol over the same bearer.
+/// The keep-alive agents aren't driven automatically, see [KeepAliveLoop].
+pub struct PeerDuplex {
+    pub plexer: RunningPlexer,
+    handshake_client: handshake::N2NClient,
+    handshake_server: handshake::N2NServer,
+    pub chainsync_client: chainsync::N2NClient,
+    pub chainsync_server: chainsync::N2NServer,
+    pub blockfetch_client: blockfetch::Client,
+    pub blockfetch_server: blockfetch::Server,
+    pub txsubmission_client: txsubmission::Client,
+    pub txsubmission_server: txsubmission::Server,
+    pub keepalive_client: keepalive::Client,
+    pub keepalive_server: keepalive::Server,
+    pub peersharing_client: peersharing::Client,
+    pub peersharing_server: peersharing::Server,
+    accepted_address: Option<SocketAddr>,
+    accepted_version: Option<(u64, n2n::VersionData)>,
+}
+
+impl PeerDuplex {
+    pub fn new(bearer: Bearer) -> Self {
+        let mut plexer = multiplexer::Plexer::with_ingress_limits(bearer, IngressLimits::n2n());
+
+        let hs_client = plexer.subscribe_client(PROTOCOL_N2N_HANDSHAKE);
+        let hs_server = plexer.subscribe_server(PROTOCOL_N2N_HANDSHAKE);
+        let cs_client = plexer.subscribe_client(PROTOCOL_N2N_CHAIN_SYNC);
+        let cs_server = plexer.subscribe_server(PROTOCOL_N2N_CHAIN_SYNC);
+        let bf_client = plexer.subscribe_client(PROTOCOL_N2N_BLOCK_FETCH);
+        let bf_server = plexer.subscribe_server(PROTOCOL_N2N_BLOCK_FETCH);
+        let txsub_client = plexer.subscribe_client(PROTOCOL_N2N_TX_SUBMISSION);
+        let txsub_server = plexer.subscribe_server(PROTOCOL_N2N_TX_SUBMISSION);
+        let keepalive_client = plexer.subscribe_client(PROTOCOL_N2N_KEEP_ALIVE);
+        let keepalive_server = plexer.subscribe_server(PROTOCOL_N2N_KEEP_ALIVE);
+        let peersharing_client = plexer.subscribe_client(PROTOCOL_N2N_PEER_SHARING);
+        let peersharing_server = plexer.subscribe_server(PROTOCOL_N2N_PEER_SHARING);
+
+        let plexer = plexer.spawn();
+
+        Self {
+            plexer,
+            handshake_client: handshake::Client::new(hs_client),
+            handshake_server: handshake::Server::new(hs_server),
+            chainsync_client: chainsync::Client::new(cs_client)
+                .with_time_limits(chainsync::TimeLimits::n2n()),
+            chainsync_server: chainsync::Server::new(cs_server)
+                .with_time_limits(chainsync::TimeLimits::n2n()),
+            blockfetch_client: blockfetch::Client::new(bf_client)
+                .with_time_limits(blockfetch::TimeLimits::n2n()),
+            blockfetch_server: blockfetch::Server::new(bf_server)
+                .with_time_limits(blockfetch::TimeLimits::n2n()),
+            txsubmission_client: txsubmission::Client::new(txsub_client)
+                .with_time_limits(txsubmission::TimeLimits::n2n()),
+            txsubmission_server: txsubmission::Server::new(txsub_server)
+                .with_time_limits(txsubmission::TimeLimits::n2n()),
+            keepalive_client: keepalive::Client::new(keepalive_client),
+            keepalive_server: keepalive::Server::new(keepalive_server),
+            peersharing_client: peersharing::Client::new(peersharing_client),
+            peersharing_server: peersharing::Server::new(peersharing_server),
+            accepted_address: None,
+            accepted_version: None,
+        }
+    }
+
+    /// Connects to a peer, proposing to run in initiator and responder mode
+    pub async fn connect(addr: impl ToSocketAddrs, magic: u64) -> Result<Self, Error> {
+        let bearer = Bearer::connect_tcp(addr)
+            .await
+            .map_err(Error::ConnectFailure)?;
+
+        let mut duplex = Self::new(bearer);
+
+        let versions =
+            n2n::VersionTable::v7_and_above(magic).with_initiator_only_diffusion_mode(false);
+
+        let handshake = duplex
+            .handshake_client
+            .handshake(versions)
+            .await
+            .map_err(Error::HandshakeProtocol)?;
+
+        match handshake {
+            Confirmation::Accepted(version, data) if !data.initiator_only_diffusion_mode => {
+                duplex.accepted_version = Some((version, data));
+                Ok(duplex)
+            }
+            Confirmation::Accepted(..) => {
+                error!("peer accepted handshake in initiator only mode");
+                duplex.abort().await;
+                Err(Error::InitiatorOnlyPeer)
+            }
+            Confirmation::Rejected(reason) => {
+                error!(?reason, "handshake refused");
+                duplex.abort().await;
+                Err(Error::IncompatibleVersion)
+            }
+            Confirmation::QueryReply(_) => {
+                duplex.abort().await;
+                Err(Error::HandshakeProtocol(handshake::Error::InvalidInbound))
+            }
+        }
+    }
+
+    /// Accepts a connection from a peer that proposes to run in initiator and
+    /// responder mode
+    pub async fn accept(listener: &TcpListener, magic: u64) -> Result<Self, Error> {
+        let (bearer, address) = Bearer::accept_tcp(listener)
+            .await
+            .map_err(Error::ConnectFailure)?;
+
+        let mut duplex = Self::new(bearer);
+
+        let versions =
+            n2n::VersionTable::v7_and_above(magic).with_initiator_only_diffusion_mode(false);
+
+        let accepted_version = duplex
+            .handshake_server
+            .handshake(versions)
+            .await
+            .map_err(Error::HandshakeProtocol)?;
+
+        if let Some((version, data)) = accepted_version {
+            duplex.accepted_address = Some(address);
+            duplex.accepted_version = Some((version, data));
+            Ok(duplex)
+        } else {
+            duplex.abort().await;
+            Err(Error::IncompatibleVersion)
+        }
+    }
+
+    pub fn chainsync_client(&mut self) -> &mut chainsync::N2NClient {
+        &mut self.chainsync_client
+    }
+
+    pub fn chainsync_server(&mut self) -> &mut chainsync::N2NServer {
+        &mut self.chainsync_server
+    }
+
+    pub fn blockfetch_client(&mut self) -> &mut blockfetch::Client {
+        &mut self.blockfetch_client
+    }
+
+    pub fn blockfetch_server(&mut self) -> &mut blockfetch::Server {
+        &mut self.blockfetch_server
+    }
+
+    pub fn txsubmission_client(&mut self) -> &mut txsubmission::Client {
+        &mut self.txsubmission_client
+    }
+
+    pub fn txsubmission_server(&mut self) -> &mut txsubmission::Server {
+        &mut self.txsubmission_server
+    }
+
+    pub fn keepalive_client(&mut self) -> &mut keepalive::Client {
+        &mut self.keepalive_client
+    }
+
+    pub fn keepalive_server(&mut self) -> &mut keepalive::Server {
+        &mut self.keepalive_server
+    }
+
+    pub fn peersharing_client(&mut self) -> &mut peersharing::Client {
+        &mut self.peersharing_client
+    }
+
+    pub fn peersharing_server(&mut self) -> &mut peersharing::Server {
+        &mut self.peersharing_server
+    }
+
+    /// Address of the peer, when the connection was accepted
+    pub fn accepted_address(&self) -> Option<&SocketAddr> {
+        self.accepted_address.as_ref()
+    }
+
+    pub fn accepted_version(&self) -> Option<&(u64, n2n::VersionData)> {
+        self.accepted_version.as_ref()
+    }
+
+    pub async fn abort(self) {
+        self.plexer.abort().await
+    }
+}
+
 /// Client of N2C Ouroboros
 pub struct NodeClient {
     plexer: RunningPlexer,
@@ -349,7 +545,7 @@ pub struct NodeClient {
 
 impl NodeClient {
     pub fn new(bearer: Bearer) -> Self {
-        let mut plexer = multiplexer::Plexer::new(bearer);
+        let mut plexer = multiplexer::Plexer::with_ingress_limits(bearer, IngressLimits::n2c());
 
         let hs_channel = plexer.subscribe_client(PROTOCOL_N2C_HANDSHAKE);
         let cs_channel = plexer.subscribe_client(PROTOCOL_N2C_CHAIN_SYNC);
@@ -428,7 +624,7 @@ impl NodeClient {
         bearer: Bearer,
         magic: u64,
     ) -> Result<handshake::n2c::VersionTable, Error> {
-        let mut plexer = multiplexer::Plexer::new(bearer);
+        let mut plexer = multiplexer::Plexer::with_ingress_limits(bearer, IngressLimits::n2c());
 
         let hs_channel = plexer.subscribe_client(PROTOCOL_N2C_HANDSHAKE);
 
@@ -498,7 +694,7 @@ pub struct NodeServer {
 #[cfg(unix)]
 impl NodeServer {
     pub async fn new(bearer: Bearer) -> Self {
-        let mut plexer = multiplexer::Plexer::new(bearer);
+        let mut plexer = multiplexer::Plexer::with_ingress_limits(bearer, IngressLimits::n2c());
 
         let hs_channel = plexer.subscribe_server(PROTOCOL_N2C_HANDSHAKE);
         let cs_channel = plexer.subscribe_server(PROTOCOL_N2C_CHAIN_SYNC);
@@ -587,7 +783,7 @@ pub struct DmqClient {
 
 impl DmqClient {
     pub fn new(bearer: Bearer) -> Self {
-        let mut plexer = multiplexer::Plexer::new(bearer);
+        let mut plexer = multiplexer::Plexer::with_ingress_limits(bearer, IngressLimits::n2c());
 
         let hs_channel = plexer.subscribe_client(PROTOCOL_N2C_HANDSHAKE);
         let msg_submission_channel = plexer.subscribe_client(PROTOCOL_N2C_MSG_SUBMISSION);
@@ -662,7 +858,7 @@ impl DmqClient {
         bearer: Bearer,
         magic: u64,
     ) -> Result<handshake::n2c::VersionTable, Error> {
-        let mut plexer = multiplexer::Plexer::new(bearer);
+        let mut plexer = multiplexer::Plexer::with_ingress_limits(bearer, IngressLimits::n2c());
 
         let hs_channel = plexer.subscribe_client(PROTOCOL_N2C_HANDSHAKE);
 
@@ -725,7 +921,7 @@ pub struct DmqServer {
 #[cfg(unix)]
 impl DmqServer {
     pub async fn new(bearer: Bearer) -> Self {
-        let mut plexer = multiplexer::Plexer::new(bearer);
+        let mut plexer = multiplexer::Plexer::with_ingress_limits(bearer, IngressLimits::n2c());
 
         let hs_channel = plexer.subscribe_server(PROTOCOL_N2C_HANDSHAKE);
         let msg_notification_channel = plexer.subscribe_server(PROTOCOL_N2C_MSG_NOTIFICATION);
@@ -0,0 +1,373 @@
+//! A chain follower that keeps in sync with a remote peer
+//!
+//! The follower pipelines chain-sync requests to receive headers, fetches the
+//! corresponding blocks in batches through block-fetch and emits a sequence of
+//! roll-forward / roll-back events. If the connection drops, it reconnects and
+//! intersects the chain again starting from the latest points it emitted.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use pallas_traverse::{MultiEraBlock, MultiEraHeader};
+use thiserror::Error;
+use tracing::{debug, warn};
+
+use crate::facades::{self, PeerClient};
+use crate::miniprotocols::blockfetch;
+use crate::miniprotocols::chainsync::{
+    self, HeaderContent, NextResponse, RollbackBuffer, RollbackEffect, Tip,
+};
+use crate::miniprotocols::Point;
+
+/// Amount of recent points used to re-intersect after a reconnection
+const REINTERSECT_POINTS: usize = 10;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("error connecting to peer")]
+    Connect(#[source] facades::Error),
+
+    #[error("chain-sync error")]
+    ChainSync(#[source] chainsync::ClientError),
+
+    #[error("block-fetch error")]
+    BlockFetch(#[source] blockfetch::ClientError),
+
+    #[error("peer sent {1} blocks for a range of {0} headers")]
+    UnexpectedBlockCount(usize, usize),
+
+    #[error("peer sent block {1:?} for header {0:?}")]
+    UnexpectedBlock(Point, Point),
+
+    #[error("invalid header")]
+    InvalidHeader(#[source] pallas_traverse::Error),
+
+    #[error("invalid block")]
+    InvalidBlock(#[source] pallas_traverse::Error),
+
+    #[error("no intersection found with the peer")]
+    IntersectionNotFound,
+}
+
+impl Error {
+    /// Whether the follower can recover from the error by reconnecting
+    fn is_recoverable(&self) -> bool {
+        !matches!(
+            self,
+            Error::InvalidHeader(_) | Error::InvalidBlock(_) | Error::IntersectionNotFound
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FollowerConfig {
+    pub address: String,
+    pub magic: u64,
+    /// Points used to intersect the chain on the first connection
+    pub intersect: Vec<Point>,
+    /// Max number of chain-sync requests in flight
+    pub pipeline_depth: usize,
+    /// Max number of blocks requested in a single block-fetch range
+    pub fetch_batch_size: usize,
+    /// Number of emitted points kept in memory to handle rollbacks
+    pub rollback_window: usize,
+    pub reconnect_delay: Duration,
+}
+
+impl FollowerConfig {
+    pub fn new(address: impl Into<String>, magic: u64) -> Self {
+        Self {
+            address: address.into(),
+            magic,
+            intersect: vec![Point::Origin],
+            pipeline_depth: 50,
+            fetch_batch_size: 50,
+            rollback_window: 2160,
+            reconnect_delay: Duration::from_secs(5),
+        }
+    }
+
+    pub fn with_intersect(mut self, points: Vec<Point>) -> Self {
+        self.intersect = points;
+        self
+    }
+
+    pub fn with_pipeline_depth(mut self, depth: usize) -> Self {
+        self.pipeline_depth = depth;
+        self
+    }
+
+    pub fn with_fetch_batch_size(mut self, size: usize) -> Self {
+        self.fetch_batch_size = size;
+        self
+    }
+
+    pub fn with_rollback_window(mut self, window: usize) -> Self {
+        self.rollback_window = window;
+        self
+    }
+
+    pub fn with_reconnect_delay(mut self, delay: Duration) -> Self {
+        self.reconnect_delay = delay;
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FollowEvent {
+    RollForward(Point, blockfetch::Body),
+    RollBack(Point),
+}
+
+fn header_point(header: &HeaderContent) -> Result<(Point, u64), Error> {
+    let subtag = header.byron_prefix.map(|(subtag, _)| subtag);
+    let header = MultiEraHeader::decode(header.variant, subtag, &header.cbor)
+        .map_err(Error::InvalidHeader)?;
+
+    let point = Point::Specific(header.slot(), header.hash().to_vec());
+
+    Ok((point, header.number()))
+}
+
+/// Follows the chain of a N2N peer, emitting events with full blocks
+pub struct ChainFollower {
+    config: FollowerConfig,
+    peer: Option<PeerClient>,
+    /// Points already emitted, used to handle rollbacks and re-intersect
+    emitted: RollbackBuffer,
+    /// Points received through chain-sync whose blocks weren't fetched yet
+    pending: Vec<Point>,
+    events: VecDeque<FollowEvent>,
+    tip: Option<Tip>,
+    at_tip: bool,
+}
+
+impl ChainFollower {
+    pub fn new(config: FollowerConfig) -> Self {
+        Self {
+            config,
+            peer: None,
+            emitted: RollbackBuffer::new(),
+            pending: vec![],
+            events: VecDeque::new(),
+            tip: None,
+            at_tip: false,
+        }
+    }
+
+    /// Latest tip reported by the peer
+    pub fn tip(&self) -> Option<&Tip> {
+        self.tip.as_ref()
+    }
+
+    /// Waits for the next event of the chain. Connection failures are
+    /// retried, any other error is returned.
+    pub async fn next(&mut self) -> Result<FollowEvent, Error> {
+        loop {
+            if let Some(event) = self.events.pop_front() {
+                return Ok(event);
+            }
+
+            match self.step().await {
+                Ok(()) => (),
+                Err(err) if err.is_recoverable() => {
+                    warn!(%err, "lost connection to peer, reconnecting");
+                    tokio::time::sleep(self.config.reconnect_delay).await;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    /// Disconnects from the peer
+    pub async fn abort(mut self) {
+        if let Some(peer) = self.peer.take() {
+            peer.abort().await;
+        }
+    }
+
+    fn intersect_points(&self) -> Vec<Point> {
+        let mut points: Vec<_> = self
+            .emitted
+            .peek()
+            .rev()
+            .take(REINTERSECT_POINTS)
+            .cloned()
+            .collect();
+
+        if let Some(oldest) = self.emitted.oldest() {
+            if !points.contains(oldest) {
+                points.push(oldest.clone());
+            }
+        }
+
+        for point in &self.config.intersect {
+            if !points.contains(point) {
+                points.push(point.clone());
+            }
+        }
+
+        points
+    }
+
+    async fn connect(&mut self) -> Result<PeerClient, Error> {
+        let mut peer = PeerClient::connect(self.config.address.as_str(), self.config.magic)
+            .await
+            .map_err(Error::Connect)?;
+
+        let points = self.intersect_points();
+
+        let intersect = match peer.chainsync().find_intersect(points).await {
+            Ok(intersect) => intersect,
+            Err(err) => {
+                peer.abort().await;
+                return Err(Error::ChainSync(err));
+            }
+        };
+
+        match intersect {
+            (Some(point), tip) => {
+                debug!(?point, "intersection found");
+                self.tip = Some(tip);
+                self.pending.clear();
+                self.at_tip = false;
+                Ok(peer)
+            }
+            (None, _) => {
+                peer.abort().await;
+                Err(Error::IntersectionNotFound)
+            }
+        }
+    }
+
+    async fn step(&mut self) -> Result<(), Error> {
+        let mut peer = match self.peer.take() {
+            Some(peer) => peer,
+            None => self.connect().await?,
+        };
+
+        match self.drive(&mut peer).await {
+            Ok(()) => {
+                self.peer = Some(peer);
+                Ok(())
+            }
+            Err(err) => {
+                peer.abort().await;
+                Err(err)
+            }
+        }
+    }
+
+    /// Keeps the pipeline full and processes the next chain-sync response
+    async fn drive(&mut self, peer: &mut PeerClient) -> Result<(), Error> {
+        // close to the tip there's nothing to gain from pipelining
+        let depth = match self.at_tip {
+            true => 1,
+            false => self.config.pipeline_depth.max(1),
+        };
+
+        let chainsync = peer.chainsync();
+
+        while chainsync.pipelined() < depth {
+            chainsync
+                .send_request_next_pipelined()
+                .await
+                .map_err(Error::ChainSync)?;
+        }
+
+        let response = chainsync
+            .recv_pipelined_response()
+            .await
+            .map_err(Error::ChainSync)?;
+
+        match response {
+            NextResponse::RollForward(header, tip) => {
+                let (point, number) = header_point(&header)?;
+                debug!(?point, "roll forward");
+
+                self.at_tip = tip.1.saturating_sub(number) < self.config.pipeline_depth as u64;
+                self.tip = Some(tip);
+                self.pending.push(point);
+
+                if self.at_tip || self.pending.len() >= self.config.fetch_batch_size {
+                    self.fetch_pending(peer).await?;
+                }
+            }
+            NextResponse::RollBackward(point, tip) => {
+                debug!(?point, "roll backward");
+                self.tip = Some(tip);
+                self.roll_back(point);
+            }
+            NextResponse::Await => {
+                debug!("reached the tip of the chain");
+                self.at_tip = true;
+                self.fetch_pending(peer).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn roll_back(&mut self, point: Point) {
+        // orphaned headers that weren't emitted yet are dropped silently
+        if let Some(pos) = self.pending.iter().position(|p| *p == point) {
+            self.pending.truncate(pos + 1);
+            return;
+        }
+
+        self.pending.clear();
+
+        // after an intersection the peer rolls back to the point we already
+        // have as our latest
+        if self.emitted.latest() == Some(&point) {
+            return;
+        }
+
+        // a rollback deeper than the window clears the buffer, the point is kept
+        // as an anchor so that a reconnection doesn't start over from the
+        // configured intersection
+        if let RollbackEffect::OutOfScope = self.emitted.roll_back(&point) {
+            self.emitted.roll_forward(point.clone());
+        }
+
+        self.events.push_back(FollowEvent::RollBack(point));
+    }
+
+    async fn fetch_pending(&mut self, peer: &mut PeerClient) -> Result<(), Error> {
+        let (Some(first), Some(last)) = (self.pending.first(), self.pending.last()) else {
+            return Ok(());
+        };
+
+        let blocks = peer
+            .blockfetch()
+            .fetch_range((first.clone(), last.clone()))
+            .await
+            .map_err(Error::BlockFetch)?;
+
+        if blocks.len() != self.pending.len() {
+            return Err(Error::UnexpectedBlockCount(
+                self.pending.len(),
+                blocks.len(),
+            ));
+        }
+
+        for (point, block) in self.pending.iter().zip(&blocks) {
+            let block = MultiEraBlock::decode(block).map_err(Error::InvalidBlock)?;
+            let received = Point::Specific(block.slot(), block.hash().to_vec());
+
+            if received != *point {
+                return Err(Error::UnexpectedBlock(point.clone(), received));
+            }
+        }
+
+        for (point, block) in self.pending.drain(..).zip(blocks) {
+            self.emitted.roll_forward(point.clone());
+            self.events
+                .push_back(FollowEvent::RollForward(point, block));
+        }
+
+        self.emitted.pop_with_depth(self.config.rollback_window);
+
+        Ok(())
+    }
+}
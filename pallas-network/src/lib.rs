@@ -1,5 +1,6 @@
 //! Network stack compatible with the Ouroboros protocol
 
 pub mod facades;
+pub mod follower;
 pub mod miniprotocols;
 pub mod multiplexer;
@@ -4,7 +4,7 @@ use tracing::{debug, warn};
 use crate::miniprotocols::common::Point;
 use crate::multiplexer;
 
-use super::{Message, State};
+use super::{Message, State, TimeLimits};
 
 #[derive(Error, Debug)]
 pub enum ClientError {
@@ -38,7 +38,7 @@ pub type HasBlocks = Option<()>;
 /// This struct is used to interact with the Cardano network and fetch blocks
 /// from a remote node. It handles the state transitions and message exchange
 /// required to communicate with the network using the BlockFetch mini-protocol.
-pub struct Client(State, multiplexer::ChannelBuffer);
+pub struct Client(State, multiplexer::ChannelBuffer, TimeLimits);
 
 impl Client {
     /// Create a new BlockFetch client from a multiplexer agent channel.
@@ -48,7 +48,17 @@ impl Client {
     /// * `channel` - A multiplexer agent channel used for communication with
     ///   the remote node.
     pub fn new(channel: multiplexer::AgentChannel) -> Self {
-        Self(State::Idle, multiplexer::ChannelBuffer::new(channel))
+        Self(
+            State::Idle,
+            multiplexer::ChannelBuffer::new(channel),
+            TimeLimits::default(),
+        )
+    }
+
+    /// Sets the max time to wait for the messages of the peer
+    pub fn with_time_limits(mut self, limits: TimeLimits) -> Self {
+        self.2 = limits;
+        self
     }
 
     /// Get the current state of the client.
@@ -121,7 +131,12 @@ impl Client {
 
     pub async fn recv_message(&mut self) -> Result<Message, ClientError> {
         self.assert_agency_is_theirs()?;
-        let msg = self.1.recv_full_msg().await.map_err(ClientError::Plexer)?;
+        let limit = self.2.for_state(&self.0);
+        let msg = self
+            .1
+            .recv_full_msg_within(limit)
+            .await
+            .map_err(ClientError::Plexer)?;
         self.assert_inbound_state(&msg)?;
 
         Ok(msg)
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use crate::miniprotocols::Point;
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -8,6 +10,35 @@ pub enum State {
     Done,
 }
 
+/// Max time to wait for a message of the peer in each of the states where
+/// agency is theirs. A `None` limit waits forever.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TimeLimits {
+    pub idle: Option<Duration>,
+    pub busy: Option<Duration>,
+    pub streaming: Option<Duration>,
+}
+
+impl TimeLimits {
+    /// Limits of the node-to-node protocol
+    pub fn n2n() -> Self {
+        Self {
+            idle: None,
+            busy: Some(Duration::from_secs(60)),
+            streaming: Some(Duration::from_secs(60)),
+        }
+    }
+
+    pub fn for_state(&self, state: &State) -> Option<Duration> {
+        match state {
+            State::Idle => self.idle,
+            State::Busy => self.busy,
+            State::Streaming => self.streaming,
+            State::Done => None,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum Message {
     RequestRange { range: (Point, Point) },
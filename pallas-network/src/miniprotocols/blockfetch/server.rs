@@ -2,7 +2,7 @@ use thiserror::Error;
 
 use crate::multiplexer;
 
-use super::{Body, Message, Range, State};
+use super::{Body, Message, Range, State, TimeLimits};
 
 #[derive(Error, Debug)]
 pub enum ServerError {
@@ -26,7 +26,7 @@ pub enum ServerError {
 pub struct BlockRequest(pub Range);
 
 /// Represents the server for the BlockFetch mini-protocol.
-pub struct Server(State, multiplexer::ChannelBuffer);
+pub struct Server(State, multiplexer::ChannelBuffer, TimeLimits);
 
 impl Server {
     /// Create a new BlockFetch server from a multiplexer agent channel.
@@ -36,7 +36,17 @@ impl Server {
     /// * `channel` - A multiplexer agent channel used for communication with
     ///   the server.
     pub fn new(channel: multiplexer::AgentChannel) -> Self {
-        Self(State::Idle, multiplexer::ChannelBuffer::new(channel))
+        Self(
+            State::Idle,
+            multiplexer::ChannelBuffer::new(channel),
+            TimeLimits::default(),
+        )
+    }
+
+    /// Sets the max time to wait for the messages of the peer
+    pub fn with_time_limits(mut self, limits: TimeLimits) -> Self {
+        self.2 = limits;
+        self
     }
 
     /// Get the current state of the server.
@@ -109,7 +119,12 @@ impl Server {
 
     pub async fn recv_message(&mut self) -> Result<Message, ServerError> {
         self.assert_agency_is_theirs()?;
-        let msg = self.1.recv_full_msg().await.map_err(ServerError::Plexer)?;
+        let limit = self.2.for_state(&self.0);
+        let msg = self
+            .1
+            .recv_full_msg_within(limit)
+            .await
+            .map_err(ServerError::Plexer)?;
         self.assert_inbound_state(&msg)?;
 
         Ok(msg)
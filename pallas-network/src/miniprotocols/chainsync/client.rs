@@ -6,7 +6,7 @@ use tracing::debug;
 use crate::miniprotocols::Point;
 use crate::multiplexer;
 
-use super::{BlockContent, HeaderContent, IntersectResponse, Message, State, Tip};
+use super::{BlockContent, HeaderContent, IntersectResponse, Message, State, TimeLimits, Tip};
 
 #[derive(Error, Debug)]
 pub enum ClientError {
@@ -36,9 +36,17 @@ pub enum NextResponse<CONTENT> {
     Await,
 }
 
-pub struct Client<O>(State, multiplexer::ChannelBuffer, PhantomData<O>)
+pub struct Client<O>
 where
-    Message<O>: Fragment;
+    Message<O>: Fragment,
+{
+    state: State,
+    muxer: multiplexer::ChannelBuffer,
+    pd_content: PhantomData<O>,
+    time_limits: TimeLimits,
+    /// Pipelined `RequestNext` messages that haven't been answered yet
+    in_flight: usize,
+}
 
 impl<O> Client<O>
 where
@@ -51,21 +59,29 @@ where
     /// * `channel` - An instance of `multiplexer::AgentChannel` to be used for
     ///   communication.
     pub fn new(channel: multiplexer::AgentChannel) -> Self {
-        Self(
-            State::Idle,
-            multiplexer::ChannelBuffer::new(channel),
-            PhantomData {},
-        )
+        Self {
+            state: State::Idle,
+            muxer: multiplexer::ChannelBuffer::new(channel),
+            pd_content: PhantomData {},
+            time_limits: TimeLimits::default(),
+            in_flight: 0,
+        }
+    }
+
+    /// Sets the max time to wait for the messages of the peer
+    pub fn with_time_limits(mut self, limits: TimeLimits) -> Self {
+        self.time_limits = limits;
+        self
     }
 
     /// Returns the current state of the client.
     pub fn state(&self) -> &State {
-        &self.0
+        &self.state
     }
 
     /// Checks if the client is done.
     pub fn is_done(&self) -> bool {
-        self.0 == State::Done
+        self.state == State::Done
     }
 
     /// Checks if the client has agency.
@@ -96,7 +112,7 @@ where
     }
 
     fn assert_outbound_state(&self, msg: &Message<O>) -> Result<(), ClientError> {
-        match (&self.0, msg) {
+        match (&self.state, msg) {
             (State::Idle, Message::RequestNext) => Ok(()),
             (State::Idle, Message::FindIntersect(_)) => Ok(()),
             (State::Idle, Message::Done) => Ok(()),
@@ -105,7 +121,7 @@ where
     }
 
     fn assert_inbound_state(&self, msg: &Message<O>) -> Result<(), ClientError> {
-        match (&self.0, msg) {
+        match (&self.state, msg) {
             (State::CanAwait, Message::RollForward(_, _)) => Ok(()),
             (State::CanAwait, Message::RollBackward(_, _)) => Ok(()),
             (State::CanAwait, Message::AwaitReply) => Ok(()),
@@ -131,7 +147,7 @@ where
         self.assert_agency_is_ours()?;
         self.assert_outbound_state(msg)?;
 
-        self.1
+        self.muxer
             .send_msg_chunks(msg)
             .await
             .map_err(ClientError::Plexer)?;
@@ -148,7 +164,13 @@ where
     pub async fn recv_message(&mut self) -> Result<Message<O>, ClientError> {
         self.assert_agency_is_theirs()?;
 
-        let msg = self.1.recv_full_msg().await.map_err(ClientError::Plexer)?;
+        let limit = self.time_limits.for_state(&self.state);
+
+        let msg = self
+            .muxer
+            .recv_full_msg_within(limit)
+            .await
+            .map_err(ClientError::Plexer)?;
 
         self.assert_inbound_state(&msg)?;
 
@@ -169,7 +191,7 @@ where
     pub async fn send_find_intersect(&mut self, points: Vec<Point>) -> Result<(), ClientError> {
         let msg = Message::FindIntersect(points);
         self.send_message(&msg).await?;
-        self.0 = State::Intersect;
+        self.state = State::Intersect;
 
         debug!("send find intersect");
 
@@ -186,11 +208,11 @@ where
 
         match self.recv_message().await? {
             Message::IntersectFound(point, tip) => {
-                self.0 = State::Idle;
+                self.state = State::Idle;
                 Ok((Some(point), tip))
             }
             Message::IntersectNotFound(tip) => {
-                self.0 = State::Idle;
+                self.state = State::Idle;
                 Ok((None, tip))
             }
             _ => Err(ClientError::InvalidInbound),
@@ -219,11 +241,118 @@ where
     pub async fn send_request_next(&mut self) -> Result<(), ClientError> {
         let msg = Message::RequestNext;
         self.send_message(&msg).await?;
-        self.0 = State::CanAwait;
+        self.state = State::CanAwait;
+
+        Ok(())
+    }
+
+    /// Returns the number of pipelined `RequestNext` messages that haven't
+    /// been answered yet.
+    pub fn pipelined(&self) -> usize {
+        self.in_flight
+    }
+
+    /// Sends a RequestNext message without waiting for the responses of the
+    /// ones already in flight.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the message cannot be sent or if the client is
+    /// intersecting or done.
+    pub async fn send_request_next_pipelined(&mut self) -> Result<(), ClientError> {
+        match self.state {
+            State::Idle => self.state = State::CanAwait,
+            State::CanAwait | State::MustReply if self.in_flight > 0 => (),
+            _ => return Err(ClientError::InvalidOutbound),
+        }
+
+        self.muxer
+            .send_msg_chunks(&Message::<O>::RequestNext)
+            .await
+            .map_err(ClientError::Plexer)?;
+
+        self.in_flight += 1;
+
+        Ok(())
+    }
+
+    /// Receives the response to the oldest pipelined RequestNext. An `Await`
+    /// response leaves the request in flight until its roll arrives.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there's no request in flight or if the inbound
+    /// message is invalid.
+    pub async fn recv_pipelined_response(&mut self) -> Result<NextResponse<O>, ClientError> {
+        if self.in_flight == 0 {
+            return Err(ClientError::AgencyIsOurs);
+        }
+
+        let response = match self.state {
+            State::CanAwait => self.recv_while_can_await().await?,
+            _ => self.recv_while_must_reply().await?,
+        };
+
+        if !matches!(response, NextResponse::Await) {
+            self.in_flight -= 1;
+
+            if self.in_flight > 0 {
+                self.state = State::CanAwait;
+            }
+        }
+
+        Ok(response)
+    }
+
+    /// Pipelines `n` RequestNext messages without waiting for any response.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the messages cannot be sent or if the client is
+    /// intersecting or done.
+    pub async fn send_request_next_batch(&mut self, n: usize) -> Result<(), ClientError> {
+        debug!(n, "pipelining request next");
+
+        for _ in 0..n {
+            self.send_request_next_pipelined().await?;
+        }
 
         Ok(())
     }
 
+    /// Receives the responses to every pipelined RequestNext, in the order
+    /// they were sent by the server. An `Await` response is followed by the
+    /// roll that eventually answers the same request, so close to the tip
+    /// this waits for as many new blocks as requests are in flight.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the inbound messages are invalid.
+    pub async fn recv_pipelined_batch(&mut self) -> Result<Vec<NextResponse<O>>, ClientError> {
+        let mut responses = Vec::with_capacity(self.in_flight);
+
+        while self.in_flight > 0 {
+            responses.push(self.recv_pipelined_response().await?);
+        }
+
+        Ok(responses)
+    }
+
+    /// Pipelines `n` RequestNext messages and collects all of their
+    /// responses, trading one round trip per block for one per batch.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the messages cannot be sent or if the inbound
+    /// messages are invalid.
+    pub async fn request_next_pipelined(
+        &mut self,
+        n: usize,
+    ) -> Result<Vec<NextResponse<O>>, ClientError> {
+        self.send_request_next_batch(n).await?;
+        self.recv_pipelined_batch().await
+    }
+
     /// Receives a response while the client is in the CanAwait state.
     ///
     /// # Errors
@@ -232,15 +361,15 @@ where
     pub async fn recv_while_can_await(&mut self) -> Result<NextResponse<O>, ClientError> {
         match self.recv_message().await? {
             Message::AwaitReply => {
-                self.0 = State::MustReply;
+                self.state = State::MustReply;
                 Ok(NextResponse::Await)
             }
             Message::RollForward(a, b) => {
-                self.0 = State::Idle;
+                self.state = State::Idle;
                 Ok(NextResponse::RollForward(a, b))
             }
             Message::RollBackward(a, b) => {
-                self.0 = State::Idle;
+                self.state = State::Idle;
                 Ok(NextResponse::RollBackward(a, b))
             }
             _ => Err(ClientError::InvalidInbound),
@@ -255,11 +384,11 @@ where
     pub async fn recv_while_must_reply(&mut self) -> Result<NextResponse<O>, ClientError> {
         match self.recv_message().await? {
             Message::RollForward(a, b) => {
-                self.0 = State::Idle;
+                self.state = State::Idle;
                 Ok(NextResponse::RollForward(a, b))
             }
             Message::RollBackward(a, b) => {
-                self.0 = State::Idle;
+                self.state = State::Idle;
                 Ok(NextResponse::RollBackward(a, b))
             }
             _ => Err(ClientError::InvalidInbound),
@@ -327,7 +456,7 @@ where
     pub async fn send_done(&mut self) -> Result<(), ClientError> {
         let msg = Message::Done;
         self.send_message(&msg).await?;
-        self.0 = State::Done;
+        self.state = State::Done;
 
         Ok(())
     }
@@ -1,4 +1,4 @@
-use std::{fmt::Debug, ops::Deref};
+use std::{fmt::Debug, ops::Deref, time::Duration};
 
 use crate::miniprotocols::Point;
 
@@ -17,6 +17,39 @@ pub enum State {
     Done,
 }
 
+/// Max time to wait for a message of the peer in each of the states where
+/// agency is theirs. A `None` limit waits forever.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TimeLimits {
+    pub idle: Option<Duration>,
+    pub intersect: Option<Duration>,
+    pub can_await: Option<Duration>,
+    pub must_reply: Option<Duration>,
+}
+
+impl TimeLimits {
+    /// Limits of the node-to-node protocol. The node picks the must-reply
+    /// limit at random between 135s and 269s, we use the upper bound.
+    pub fn n2n() -> Self {
+        Self {
+            idle: Some(Duration::from_secs(3673)),
+            intersect: Some(Duration::from_secs(10)),
+            can_await: Some(Duration::from_secs(10)),
+            must_reply: Some(Duration::from_secs(269)),
+        }
+    }
+
+    pub fn for_state(&self, state: &State) -> Option<Duration> {
+        match state {
+            State::Idle => self.idle,
+            State::Intersect => self.intersect,
+            State::CanAwait => self.can_await,
+            State::MustReply => self.must_reply,
+            State::Done => None,
+        }
+    }
+}
+
 /// A generic chain-sync message for either header or block content
 #[derive(Debug)]
 pub enum Message<C> {
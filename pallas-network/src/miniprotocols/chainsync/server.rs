@@ -6,7 +6,7 @@ use tracing::debug;
 use crate::miniprotocols::Point;
 use crate::multiplexer;
 
-use super::{BlockContent, HeaderContent, Message, State, Tip};
+use super::{BlockContent, HeaderContent, Message, State, TimeLimits, Tip};
 
 #[derive(Error, Debug)]
 pub enum ServerError {
@@ -32,7 +32,12 @@ pub enum ClientRequest {
     RequestNext,
 }
 
-pub struct Server<O>(State, multiplexer::ChannelBuffer, PhantomData<O>)
+pub struct Server<O>(
+    State,
+    multiplexer::ChannelBuffer,
+    PhantomData<O>,
+    TimeLimits,
+)
 where
     Message<O>: Fragment;
 
@@ -51,9 +56,16 @@ where
             State::Idle,
             multiplexer::ChannelBuffer::new(channel),
             PhantomData {},
+            TimeLimits::default(),
         )
     }
 
+    /// Sets the max time to wait for the messages of the peer
+    pub fn with_time_limits(mut self, limits: TimeLimits) -> Self {
+        self.3 = limits;
+        self
+    }
+
     /// Returns the current state of the server.
     pub fn state(&self) -> &State {
         &self.0
@@ -144,7 +156,13 @@ where
     async fn recv_message(&mut self) -> Result<Message<O>, ServerError> {
         self.assert_agency_is_theirs()?;
 
-        let msg = self.1.recv_full_msg().await.map_err(ServerError::Plexer)?;
+        let limit = self.3.for_state(&self.0);
+
+        let msg = self
+            .1
+            .recv_full_msg_within(limit)
+            .await
+            .map_err(ServerError::Plexer)?;
 
         self.assert_inbound_state(&msg)?;
 
@@ -149,6 +149,17 @@ impl VersionTable {
 
         VersionTable { values }
     }
+
+    /// Sets the diffusion mode proposed by every version of the table. Use
+    /// `false` to run both the initiator and responder side of the
+    /// mini-protocols over the same connection.
+    pub fn with_initiator_only_diffusion_mode(mut self, initiator_only: bool) -> VersionTable {
+        for data in self.values.values_mut() {
+            data.initiator_only_diffusion_mode = initiator_only;
+        }
+
+        self
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
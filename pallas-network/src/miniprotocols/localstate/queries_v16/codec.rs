@@ -795,6 +795,209 @@ impl<'b, C> minicbor::Decode<'b, C> for CostModels {
     }
 }
 
+impl<'b, C> minicbor::decode::Decode<'b, C> for EraBound {
+    fn decode(
+        d: &mut minicbor::Decoder<'b>,
+        _ctx: &mut C,
+    ) -> Result<Self, minicbor::decode::Error> {
+        d.array()?;
+
+        // picoseconds overflow a u64 after about 200 days, then come as bignums
+        let time = match d.datatype()? {
+            minicbor::data::Type::Tag => {
+                if d.tag()? != Tag::new(2) {
+                    return Err(minicbor::decode::Error::message(
+                        "invalid era bound time tag",
+                    ));
+                }
+                let bytes = d.bytes()?;
+                if bytes.len() > 16 {
+                    return Err(minicbor::decode::Error::message("era bound time too large"));
+                }
+                bytes.iter().fold(0u128, |acc, b| (acc << 8) | *b as u128)
+            }
+            _ => d.u64()? as u128,
+        };
+
+        Ok(EraBound {
+            time,
+            slot: d.u64()?,
+            epoch: d.u64()?,
+        })
+    }
+}
+
+impl<C> minicbor::encode::Encode<C> for EraBound {
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut minicbor::Encoder<W>,
+        _ctx: &mut C,
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        e.array(3)?;
+
+        match u64::try_from(self.time) {
+            Ok(time) => e.u64(time)?,
+            Err(_) => {
+                let bytes = self.time.to_be_bytes();
+                let leading = bytes.iter().take_while(|b| **b == 0).count();
+                e.tag(Tag::new(2))?.bytes(&bytes[leading..])?
+            }
+        };
+
+        e.u64(self.slot)?;
+        e.u64(self.epoch)?;
+
+        Ok(())
+    }
+}
+
+impl<'b, C> minicbor::decode::Decode<'b, C> for SafeZone {
+    fn decode(
+        d: &mut minicbor::Decoder<'b>,
+        _ctx: &mut C,
+    ) -> Result<Self, minicbor::decode::Error> {
+        d.array()?;
+
+        match d.u16()? {
+            0 => {
+                let safe_zone = d.u64()?;
+                // legacy "safe before epoch", always empty
+                d.skip()?;
+                Ok(SafeZone::StandardSafeZone(safe_zone))
+            }
+            1 => Ok(SafeZone::UnsafeIndefiniteSafeZone),
+            _ => Err(minicbor::decode::Error::message("invalid safe zone tag")),
+        }
+    }
+}
+
+impl<C> minicbor::encode::Encode<C> for SafeZone {
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut minicbor::Encoder<W>,
+        _ctx: &mut C,
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        match self {
+            SafeZone::StandardSafeZone(safe_zone) => {
+                e.array(3)?;
+                e.u16(0)?;
+                e.u64(*safe_zone)?;
+                e.array(1)?;
+                e.u16(0)?;
+            }
+            SafeZone::UnsafeIndefiniteSafeZone => {
+                e.array(1)?;
+                e.u16(1)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'b, C> minicbor::decode::Decode<'b, C> for EraParams {
+    fn decode(d: &mut minicbor::Decoder<'b>, ctx: &mut C) -> Result<Self, minicbor::decode::Error> {
+        let len = d.array()?;
+
+        Ok(EraParams {
+            epoch_size: d.u64()?,
+            slot_length: d.u64()?,
+            safe_zone: d.decode_with(ctx)?,
+            genesis_window: match len {
+                Some(3) => None,
+                _ => Some(d.u64()?),
+            },
+        })
+    }
+}
+
+impl<C> minicbor::encode::Encode<C> for EraParams {
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut minicbor::Encoder<W>,
+        ctx: &mut C,
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        e.array(if self.genesis_window.is_some() { 4 } else { 3 })?;
+        e.u64(self.epoch_size)?;
+        e.u64(self.slot_length)?;
+        e.encode_with(&self.safe_zone, ctx)?;
+
+        if let Some(genesis_window) = self.genesis_window {
+            e.u64(genesis_window)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'b, C> minicbor::decode::Decode<'b, C> for EraSummary {
+    fn decode(d: &mut minicbor::Decoder<'b>, ctx: &mut C) -> Result<Self, minicbor::decode::Error> {
+        d.array()?;
+
+        let start = d.decode_with(ctx)?;
+        let end = match d.datatype()? {
+            minicbor::data::Type::Null => {
+                d.skip()?;
+                None
+            }
+            _ => Some(d.decode_with(ctx)?),
+        };
+
+        Ok(EraSummary {
+            start,
+            end,
+            params: d.decode_with(ctx)?,
+        })
+    }
+}
+
+impl<C> minicbor::encode::Encode<C> for EraSummary {
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut minicbor::Encoder<W>,
+        ctx: &mut C,
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        e.array(3)?;
+        e.encode_with(&self.start, ctx)?;
+
+        match &self.end {
+            Some(end) => e.encode_with(end, ctx)?,
+            None => e.null()?,
+        };
+
+        e.encode_with(&self.params, ctx)?;
+
+        Ok(())
+    }
+}
+
+impl<'b, C> minicbor::decode::Decode<'b, C> for EraHistory {
+    fn decode(d: &mut minicbor::Decoder<'b>, ctx: &mut C) -> Result<Self, minicbor::decode::Error> {
+        let eras = d.array_iter_with(ctx)?.collect::<Result<Vec<_>, _>>()?;
+
+        Ok(EraHistory { eras })
+    }
+}
+
+impl<C> minicbor::encode::Encode<C> for EraHistory {
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut minicbor::Encoder<W>,
+        ctx: &mut C,
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        // the node encodes the summaries as a Haskell list
+        e.begin_array()?;
+
+        for era in &self.eras {
+            e.encode_with(era, ctx)?;
+        }
+
+        e.end()?;
+
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use pallas_codec::minicbor;
@@ -856,6 +1059,136 @@ pub mod tests {
         }
     }
 
+    /// Byron, Shelley and the beginning of Allegra on mainnet
+    fn mainnet_history() -> crate::miniprotocols::localstate::queries_v16::EraHistory {
+        use crate::miniprotocols::localstate::queries_v16::*;
+
+        let bound = |seconds: u128, slot, epoch| EraBound {
+            time: seconds * 1_000_000_000_000,
+            slot,
+            epoch,
+        };
+
+        let shelley_params = EraParams {
+            epoch_size: 432_000,
+            slot_length: 1_000,
+            safe_zone: SafeZone::StandardSafeZone(129_600),
+            genesis_window: Some(36_000),
+        };
+
+        EraHistory {
+            eras: vec![
+                EraSummary {
+                    start: bound(0, 0, 0),
+                    end: Some(bound(89_856_000, 4_492_800, 208)),
+                    params: EraParams {
+                        epoch_size: 21_600,
+                        slot_length: 20_000,
+                        safe_zone: SafeZone::StandardSafeZone(4_320),
+                        genesis_window: Some(36_000),
+                    },
+                },
+                EraSummary {
+                    start: bound(89_856_000, 4_492_800, 208),
+                    end: Some(bound(101_952_000, 16_588_800, 236)),
+                    params: shelley_params.clone(),
+                },
+                EraSummary {
+                    start: bound(101_952_000, 16_588_800, 236),
+                    end: Some(bound(103_680_000, 18_316_800, 240)),
+                    params: shelley_params,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn era_history_roundtrip() {
+        use crate::miniprotocols::localstate::queries_v16::*;
+
+        let history = mainnet_history();
+        let bytes = minicbor::to_vec(&history).unwrap();
+        assert_eq!(minicbor::decode::<EraHistory>(&bytes).unwrap(), history);
+
+        // older nodes don't send the genesis window
+        let params = EraParams {
+            epoch_size: 21_600,
+            slot_length: 20_000,
+            safe_zone: SafeZone::UnsafeIndefiniteSafeZone,
+            genesis_window: None,
+        };
+        let bytes = minicbor::to_vec(&params).unwrap();
+        assert_eq!(hex::encode(&bytes), "83195460194e208101");
+        assert_eq!(minicbor::decode::<EraParams>(&bytes).unwrap(), params);
+    }
+
+    #[test]
+    fn era_history_conversions() {
+        use crate::miniprotocols::localstate::queries_v16::*;
+
+        let history = mainnet_history();
+
+        let system_start = SystemStart {
+            year: BigInt::Int(2017.into()),
+            day_of_year: 266,
+            picoseconds_of_day: BigInt::Int(78_291_000_000_000_000i64.into()),
+        };
+        let start = system_start.to_posix().unwrap();
+        assert_eq!(start, 1_506_203_091);
+
+        assert_eq!(history.slot_to_posix(0, start), Ok(start));
+        assert_eq!(history.slot_to_posix(4_492_799, start), Ok(1_596_059_071));
+        assert_eq!(history.slot_to_posix(4_492_810, start), Ok(1_596_059_101));
+        assert_eq!(history.slot_to_posix(17_000_000, start), Ok(1_608_566_291));
+        assert_eq!(
+            history.slot_to_posix(18_316_800, start),
+            Err(EraHistoryError::OutOfRange)
+        );
+
+        assert_eq!(history.posix_to_slot(1_596_059_071, start), Ok(4_492_799));
+        assert_eq!(history.posix_to_slot(1_596_059_080, start), Ok(4_492_799));
+        assert_eq!(history.posix_to_slot(1_608_566_291, start), Ok(17_000_000));
+        assert_eq!(
+            history.posix_to_slot(start - 1, start),
+            Err(EraHistoryError::OutOfRange)
+        );
+
+        assert_eq!(history.slot_to_epoch(21_601), Ok((1, 1)));
+        assert_eq!(history.slot_to_epoch(4_492_800 + 432_005), Ok((209, 5)));
+        assert_eq!(history.slot_to_epoch(17_000_000), Ok((236, 411_200)));
+        assert_eq!(
+            history.slot_to_epoch(20_000_000),
+            Err(EraHistoryError::OutOfRange)
+        );
+    }
+
+    #[test]
+    fn era_history_rejects_zero_params() {
+        use crate::miniprotocols::localstate::queries_v16::*;
+
+        // a peer can send any value, a zero epoch size or slot length must not
+        // make the conversions panic
+        let mut history = mainnet_history();
+        history.eras[1].params.epoch_size = 0;
+        history.eras[1].params.slot_length = 0;
+
+        let bytes = minicbor::to_vec(&history).unwrap();
+        let history: EraHistory = minicbor::decode(&bytes).unwrap();
+
+        assert_eq!(
+            history.slot_to_epoch(4_492_800),
+            Err(EraHistoryError::InvalidEraParams)
+        );
+        assert_eq!(
+            history.relative_time_to_slot(89_856_000 * 1_000_000_000_000),
+            Err(EraHistoryError::InvalidEraParams)
+        );
+
+        // other eras are still usable
+        assert_eq!(history.slot_to_epoch(21_601), Ok((1, 1)));
+        assert_eq!(history.relative_time_to_slot(20_000_000_000_000), Ok(1));
+    }
+
     // TODO: DRY with other decode/encode roundtripss
     /// Decode a value of type T, transform it to U and encode that again to form a roundtrip.
     fn roundtrips_with<T, U>(message_str: &str, transform: fn(T) -> U)
@@ -4,6 +4,7 @@ use pallas_crypto::hash::Hash;
 use std::collections::{BTreeMap, BTreeSet};
 use std::hash::Hash as StdHash;
 use std::ops::Deref;
+use thiserror::Error;
 // required for derive attrs to work
 use pallas_codec::minicbor::{self};
 
@@ -288,6 +289,162 @@ pub struct SystemStart {
     pub picoseconds_of_day: BigInt,
 }
 
+impl SystemStart {
+    /// POSIX time of the system start, in seconds
+    pub fn to_posix(&self) -> Option<u64> {
+        let year = match &self.year {
+            BigInt::Int(year) => i64::try_from(i128::from(*year)).ok()?,
+            _ => return None,
+        };
+        let picoseconds = match &self.picoseconds_of_day {
+            BigInt::Int(picos) => u64::try_from(i128::from(*picos)).ok()?,
+            _ => return None,
+        };
+
+        // days elapsed since 0001-01-01 until the first day of the year
+        let days_before = |year: i64| {
+            let y = year - 1;
+            365 * y + y / 4 - y / 100 + y / 400
+        };
+        let days = days_before(year) - days_before(1970) + self.day_of_year - 1;
+        let seconds = days * 86_400 + (picoseconds / 1_000_000_000_000) as i64;
+
+        u64::try_from(seconds).ok()
+    }
+}
+
+/// Boundary of an era, as [in the Haskell sources](https://github.com/IntersectMBO/ouroboros-consensus/blob/358305b09f8fa1a85f076b20a51b4af03e827071/ouroboros-consensus/src/ouroboros-consensus/Ouroboros/Consensus/HardFork/History/Summary.hs#L98-L103).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct EraBound {
+    /// Picoseconds elapsed since the system start
+    pub time: u128,
+    pub slot: u64,
+    pub epoch: u64,
+}
+
+/// Safe zone of an era, as [in the Haskell sources](https://github.com/IntersectMBO/ouroboros-consensus/blob/358305b09f8fa1a85f076b20a51b4af03e827071/ouroboros-consensus/src/ouroboros-consensus/Ouroboros/Consensus/HardFork/History/EraParams.hs#L154-L176).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum SafeZone {
+    StandardSafeZone(u64),
+    UnsafeIndefiniteSafeZone,
+}
+
+/// Parameters of an era, as [in the Haskell sources](https://github.com/IntersectMBO/ouroboros-consensus/blob/358305b09f8fa1a85f076b20a51b4af03e827071/ouroboros-consensus/src/ouroboros-consensus/Ouroboros/Consensus/HardFork/History/EraParams.hs#L134-L141).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct EraParams {
+    pub epoch_size: u64,
+    /// Slot length in milliseconds
+    pub slot_length: u64,
+    pub safe_zone: SafeZone,
+    /// Absent in the responses of older nodes
+    pub genesis_window: Option<u64>,
+}
+
+/// Summary of an era, as [in the Haskell sources](https://github.com/IntersectMBO/ouroboros-consensus/blob/358305b09f8fa1a85f076b20a51b4af03e827071/ouroboros-consensus/src/ouroboros-consensus/Ouroboros/Consensus/HardFork/History/Summary.hs#L177-L182).
+/// An era without end is unbounded.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct EraSummary {
+    pub start: EraBound,
+    pub end: Option<EraBound>,
+    pub params: EraParams,
+}
+
+impl EraSummary {
+    fn slot_length_picos(&self) -> u128 {
+        self.params.slot_length as u128 * 1_000_000_000
+    }
+
+    fn contains_slot(&self, slot: u64) -> bool {
+        slot >= self.start.slot && self.end.as_ref().is_none_or(|end| slot < end.slot)
+    }
+
+    fn contains_time(&self, time: u128) -> bool {
+        time >= self.start.time && self.end.as_ref().is_none_or(|end| time < end.time)
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum EraHistoryError {
+    #[error("slot or time outside of the eras known to the node")]
+    OutOfRange,
+
+    #[error("era with a zero epoch size or slot length")]
+    InvalidEraParams,
+}
+
+/// History of the eras known to the node, as returned by the `GetInterpreter`
+/// query. Conversions fail for slots and times past the end of the last era,
+/// which is as far as the node can safely tell. The era parameters come from
+/// the node, so conversions also fail on eras with a zero epoch size or slot
+/// length instead of dividing by zero.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct EraHistory {
+    pub eras: Vec<EraSummary>,
+}
+
+impl EraHistory {
+    fn era_for_slot(&self, slot: u64) -> Result<&EraSummary, EraHistoryError> {
+        self.eras
+            .iter()
+            .find(|era| era.contains_slot(slot))
+            .ok_or(EraHistoryError::OutOfRange)
+    }
+
+    /// Picoseconds elapsed between the system start and the given slot
+    pub fn slot_to_relative_time(&self, slot: u64) -> Result<u128, EraHistoryError> {
+        let era = self.era_for_slot(slot)?;
+        let elapsed = (slot - era.start.slot) as u128 * era.slot_length_picos();
+
+        Ok(era.start.time + elapsed)
+    }
+
+    /// Slot at the given number of picoseconds since the system start
+    pub fn relative_time_to_slot(&self, time: u128) -> Result<u64, EraHistoryError> {
+        let era = self
+            .eras
+            .iter()
+            .find(|era| era.contains_time(time))
+            .ok_or(EraHistoryError::OutOfRange)?;
+
+        let elapsed = (time - era.start.time)
+            .checked_div(era.slot_length_picos())
+            .ok_or(EraHistoryError::InvalidEraParams)?;
+
+        Ok(era.start.slot + elapsed as u64)
+    }
+
+    /// POSIX time of the given slot in seconds, given the POSIX time of the
+    /// system start
+    pub fn slot_to_posix(&self, slot: u64, system_start: u64) -> Result<u64, EraHistoryError> {
+        let relative = self.slot_to_relative_time(slot)? / 1_000_000_000_000;
+
+        Ok(system_start + relative as u64)
+    }
+
+    /// Slot at the given POSIX time in seconds, given the POSIX time of the
+    /// system start
+    pub fn posix_to_slot(&self, time: u64, system_start: u64) -> Result<u64, EraHistoryError> {
+        let relative = time
+            .checked_sub(system_start)
+            .ok_or(EraHistoryError::OutOfRange)? as u128
+            * 1_000_000_000_000;
+
+        self.relative_time_to_slot(relative)
+    }
+
+    /// Epoch of the given slot, along with the slot position within the epoch
+    pub fn slot_to_epoch(&self, slot: u64) -> Result<(Epoch, u64), EraHistoryError> {
+        let era = self.era_for_slot(slot)?;
+        let era_slot = slot - era.start.slot;
+
+        let epoch = era_slot
+            .checked_div(era.params.epoch_size)
+            .ok_or(EraHistoryError::InvalidEraParams)?;
+
+        Ok((era.start.epoch + epoch, era_slot % era.params.epoch_size))
+    }
+}
+
 #[derive(Debug, Encode, Decode, PartialEq)]
 pub struct ChainBlockNumber {
     #[n(0)]
@@ -1003,6 +1160,16 @@ pub async fn get_current_era(client: &mut Client) -> Result<Era, ClientError> {
     Ok(result)
 }
 
+/// Get the era history, used to convert between slots, epochs and time.
+pub async fn get_interpreter(client: &mut Client) -> Result<EraHistory, ClientError> {
+    let query = HardForkQuery::GetInterpreter;
+    let query = LedgerQuery::HardForkQuery(query);
+    let query = Request::LedgerQuery(query);
+    let result = client.query(query).await?;
+
+    Ok(result)
+}
+
 /// Get the system start time.
 pub async fn get_system_start(client: &mut Client) -> Result<SystemStart, ClientError> {
     let query = Request::GetSystemStart;
@@ -0,0 +1,271 @@
+//! Inbound side of tx-submission, collecting the txs announced by a peer
+//!
+//! The driver keeps the FIFO of tx ids announced by the peer that weren't
+//! acknowledged yet. Txs already known by the mempool are skipped, the rest are
+//! downloaded in the order they were announced and handed to the mempool. Ids
+//! are acknowledged once resolved, without ever letting the peer hold more than
+//! the configured amount of unacknowledged ids. Every downloaded tx is hashed
+//! and must match one of the requested ids.
+
+use std::collections::VecDeque;
+
+use pallas_traverse::{Era, MultiEraTx};
+use thiserror::Error;
+use tracing::debug;
+
+use super::{EraTxBody, EraTxId, Error, Reply, Server, State, TxCount, TxIdAndSize};
+
+/// Destination of the txs collected from a peer
+pub trait Mempool {
+    /// Whether the tx is already known, in which case it isn't downloaded
+    fn contains(&self, id: &EraTxId) -> bool;
+
+    /// Receives a tx downloaded from the peer
+    fn receive(&mut self, tx: EraTxBody);
+}
+
+#[derive(Debug, Error)]
+pub enum InboundError {
+    #[error("tx-submission protocol error")]
+    Protocol(#[source] Error),
+
+    #[error("peer replied with {1} tx ids when {0} were requested")]
+    TooManyTxIds(TxCount, usize),
+
+    #[error("peer replied to a blocking request without tx ids")]
+    EmptyBlockingReply,
+
+    #[error("peer replied with {1} txs when {0} were requested")]
+    TooManyTxs(usize, usize),
+
+    #[error("peer replied with a tx of era {0} that can't be decoded")]
+    InvalidTx(u16),
+
+    #[error("peer replied with tx {0:?} that wasn't requested")]
+    UnsolicitedTx(EraTxId),
+}
+
+/// Id of a tx as announced by the peer, the hash of its body tagged with the
+/// same era. Eras are numbered by the hard-fork combinator, starting with Byron
+/// at zero.
+fn tx_id(tx: &EraTxBody) -> Option<EraTxId> {
+    let EraTxBody(era, cbor) = tx;
+
+    let decoded = Era::try_from(era.checked_add(1)?)
+        .ok()
+        .and_then(|x| MultiEraTx::decode_for_era(x, cbor).ok())?;
+
+    Some(EraTxId(*era, decoded.hash().to_vec()))
+}
+
+#[derive(Debug, Clone)]
+pub struct InboundConfig {
+    /// Max number of tx ids the peer may hold without being acknowledged
+    pub max_unacked: TxCount,
+    /// Max number of txs requested in a single message
+    pub max_txs_per_request: usize,
+}
+
+impl Default for InboundConfig {
+    fn default() -> Self {
+        Self {
+            max_unacked: 10,
+            max_txs_per_request: 3,
+        }
+    }
+}
+
+impl InboundConfig {
+    pub fn with_max_unacked(mut self, max: TxCount) -> Self {
+        self.max_unacked = max;
+        self
+    }
+
+    pub fn with_max_txs_per_request(mut self, max: usize) -> Self {
+        self.max_txs_per_request = max;
+        self
+    }
+}
+
+/// Drives the server side of tx-submission, handing the txs of the peer to a
+/// mempool
+pub struct InboundDriver<M> {
+    config: InboundConfig,
+    mempool: M,
+    /// Ids announced by the peer and not yet acknowledged, flagged once the tx
+    /// was either downloaded or skipped
+    outstanding: VecDeque<(EraTxId, bool)>,
+    /// Ids resolved since the last request of ids, acknowledged with the next
+    acknowledge: TxCount,
+}
+
+impl<M: Mempool> InboundDriver<M> {
+    pub fn new(mempool: M, config: InboundConfig) -> Self {
+        Self {
+            config,
+            mempool,
+            outstanding: VecDeque::new(),
+            acknowledge: 0,
+        }
+    }
+
+    pub fn mempool(&self) -> &M {
+        &self.mempool
+    }
+
+    pub fn mempool_mut(&mut self) -> &mut M {
+        &mut self.mempool
+    }
+
+    pub fn into_mempool(self) -> M {
+        self.mempool
+    }
+
+    /// Amount of ids announced by the peer that weren't acknowledged yet
+    pub fn unacknowledged(&self) -> usize {
+        self.outstanding.len()
+    }
+
+    /// Collects txs from the peer until it terminates the protocol. Waits for
+    /// the init message first if the protocol isn't initialized yet.
+    pub async fn run(&mut self, server: &mut Server) -> Result<(), InboundError> {
+        if *server.state() == State::Init {
+            server
+                .wait_for_init()
+                .await
+                .map_err(InboundError::Protocol)?;
+        }
+
+        while self.step(server).await? {}
+
+        Ok(())
+    }
+
+    /// Runs a single request / reply exchange with the peer. Returns `false`
+    /// once the peer terminated the protocol.
+    pub async fn step(&mut self, server: &mut Server) -> Result<bool, InboundError> {
+        self.resolve_known();
+
+        let pending: Vec<_> = self
+            .outstanding
+            .iter()
+            .filter(|(_, resolved)| !resolved)
+            .take(self.config.max_txs_per_request.max(1))
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        match pending.is_empty() {
+            true => self.request_tx_ids(server).await,
+            false => self.request_txs(server, pending).await.map(|_| true),
+        }
+    }
+
+    /// Skips the txs the mempool already has and drops the resolved ids from
+    /// the front of the FIFO, to acknowledge them with the next request
+    fn resolve_known(&mut self) {
+        for (id, resolved) in self.outstanding.iter_mut() {
+            if !*resolved && self.mempool.contains(id) {
+                *resolved = true;
+            }
+        }
+
+        while let Some((_, true)) = self.outstanding.front() {
+            self.outstanding.pop_front();
+            self.acknowledge += 1;
+        }
+    }
+
+    /// Requests new ids once all the previous ones are resolved, so the
+    /// request can be a blocking one
+    async fn request_tx_ids(&mut self, server: &mut Server) -> Result<bool, InboundError> {
+        let count = self.config.max_unacked.max(1) - self.outstanding.len() as TxCount;
+
+        debug!(ack = self.acknowledge, count, "requesting tx ids");
+
+        server
+            .acknowledge_and_request_tx_ids(true, self.acknowledge, count)
+            .await
+            .map_err(InboundError::Protocol)?;
+
+        self.acknowledge = 0;
+
+        let ids = match server
+            .receive_next_reply()
+            .await
+            .map_err(InboundError::Protocol)?
+        {
+            Reply::TxIds(ids) => ids,
+            Reply::Done => return Ok(false),
+            Reply::Txs(_) => return Err(InboundError::Protocol(Error::InvalidInbound)),
+        };
+
+        if ids.is_empty() {
+            return Err(InboundError::EmptyBlockingReply);
+        }
+
+        if ids.len() > count as usize {
+            return Err(InboundError::TooManyTxIds(count, ids.len()));
+        }
+
+        for TxIdAndSize(id, _) in ids {
+            // an id announced twice is downloaded only once
+            let duplicated = self.outstanding.iter().any(|(other, _)| *other == id);
+            self.outstanding.push_back((id, duplicated));
+        }
+
+        Ok(true)
+    }
+
+    async fn request_txs(
+        &mut self,
+        server: &mut Server,
+        ids: Vec<EraTxId>,
+    ) -> Result<(), InboundError> {
+        let requested = ids.len();
+
+        debug!(requested, "requesting txs");
+
+        server
+            .request_txs(ids.clone())
+            .await
+            .map_err(InboundError::Protocol)?;
+
+        let txs = match server
+            .receive_next_reply()
+            .await
+            .map_err(InboundError::Protocol)?
+        {
+            Reply::Txs(txs) => txs,
+            _ => return Err(InboundError::Protocol(Error::InvalidInbound)),
+        };
+
+        if txs.len() > requested {
+            return Err(InboundError::TooManyTxs(requested, txs.len()));
+        }
+
+        // each requested id can be matched by a single tx of the reply
+        let mut unmatched = ids.clone();
+
+        for tx in txs.iter() {
+            let id = tx_id(tx).ok_or(InboundError::InvalidTx(tx.0))?;
+
+            match unmatched.iter().position(|x| *x == id) {
+                Some(index) => unmatched.swap_remove(index),
+                None => return Err(InboundError::UnsolicitedTx(id)),
+            };
+        }
+
+        for tx in txs {
+            self.mempool.receive(tx);
+        }
+
+        // txs missing from the reply are no longer available at the peer, they
+        // are resolved all the same
+        self.outstanding
+            .iter_mut()
+            .filter(|(id, resolved)| !resolved && ids.contains(id))
+            .for_each(|(_, resolved)| *resolved = true);
+
+        Ok(())
+    }
+}
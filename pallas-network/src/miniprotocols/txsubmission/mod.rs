@@ -1,8 +1,10 @@
 mod client;
 mod codec;
+mod inbound;
 mod protocol;
 mod server;
 
 pub use client::*;
+pub use inbound::*;
 pub use protocol::*;
 pub use server::*;
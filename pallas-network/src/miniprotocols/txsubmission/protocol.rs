@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use thiserror::Error;
 
 use crate::multiplexer;
@@ -19,7 +21,7 @@ pub type TxCount = u16;
 pub type TxSizeInBytes = u32;
 
 // The bytes of a txId, tagged with an era number
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct EraTxId(pub u16, pub Vec<u8>);
 
 // The bytes of a transaction, with an era number and some raw CBOR
@@ -29,6 +31,41 @@ pub struct EraTxBody(pub u16, pub Vec<u8>);
 #[derive(Debug)]
 pub struct TxIdAndSize<TxID>(pub TxID, pub TxSizeInBytes);
 
+/// Max time to wait for a message of the peer in each of the states where
+/// agency is theirs. A `None` limit waits forever.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TimeLimits {
+    pub init: Option<Duration>,
+    pub idle: Option<Duration>,
+    pub tx_ids_blocking: Option<Duration>,
+    pub tx_ids_non_blocking: Option<Duration>,
+    pub txs: Option<Duration>,
+}
+
+impl TimeLimits {
+    /// Limits of the node-to-node protocol
+    pub fn n2n() -> Self {
+        Self {
+            init: None,
+            idle: None,
+            tx_ids_blocking: None,
+            tx_ids_non_blocking: Some(Duration::from_secs(10)),
+            txs: Some(Duration::from_secs(10)),
+        }
+    }
+
+    pub fn for_state(&self, state: &State) -> Option<Duration> {
+        match state {
+            State::Init => self.init,
+            State::Idle => self.idle,
+            State::TxIdsBlocking => self.tx_ids_blocking,
+            State::TxIdsNonBlocking => self.tx_ids_non_blocking,
+            State::Txs => self.txs,
+            State::Done => None,
+        }
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum Error {
     #[error("attempted to receive message while agency is ours")]
@@ -3,7 +3,7 @@ use std::marker::PhantomData;
 use pallas_codec::Fragment;
 
 use super::{
-    protocol::{Blocking, Error, Message, State, TxCount, TxIdAndSize},
+    protocol::{Blocking, Error, Message, State, TimeLimits, TxCount, TxIdAndSize},
     EraTxBody, EraTxId,
 };
 use crate::multiplexer;
@@ -21,6 +21,7 @@ pub struct GenericServer<TxId, TxBody>(
     multiplexer::ChannelBuffer,
     PhantomData<TxId>,
     PhantomData<TxBody>,
+    TimeLimits,
 )
 where
     Message<TxId, TxBody>: Fragment;
@@ -38,9 +39,16 @@ where
             multiplexer::ChannelBuffer::new(channel),
             PhantomData {},
             PhantomData {},
+            TimeLimits::default(),
         )
     }
 
+    /// Sets the max time to wait for the messages of the peer
+    pub fn with_time_limits(mut self, limits: TimeLimits) -> Self {
+        self.4 = limits;
+        self
+    }
+
     pub fn state(&self) -> &State {
         &self.0
     }
@@ -100,7 +108,12 @@ where
 
     pub async fn recv_message(&mut self) -> Result<Message<TxId, TxBody>, Error> {
         self.assert_agency_is_theirs()?;
-        let msg = self.1.recv_full_msg().await.map_err(Error::Plexer)?;
+        let limit = self.4.for_state(&self.0);
+        let msg = self
+            .1
+            .recv_full_msg_within(limit)
+            .await
+            .map_err(Error::Plexer)?;
         self.assert_inbound_state(&msg)?;
 
         Ok(msg)
@@ -1,6 +1,9 @@
 //! A multiplexer of several mini-protocols through a single bearer
 
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 
 use byteorder::{ByteOrder, NetworkEndian};
 use pallas_codec::{minicbor, Fragment};
@@ -247,19 +250,113 @@ pub enum Error {
 
     #[error("failure to abort the plexer threads")]
     AbortFailure,
+
+    #[error("ingress queue of protocol {0} exceeded its limit of {1} bytes")]
+    IngressLimitExceeded(Protocol, usize),
+
+    #[error("no message received for protocol {0} within {1:?}")]
+    TimeLimitExceeded(Protocol, Duration),
+}
+
+/// Max number of bytes that a peer can have buffered for each mini-protocol
+/// before being considered misbehaving. Protocols without a limit are
+/// unbounded.
+#[derive(Debug, Clone, Default)]
+pub struct IngressLimits(HashMap<Protocol, usize>);
+
+impl IngressLimits {
+    /// Limits used by the node for the node-to-node protocols
+    pub fn n2n() -> Self {
+        Self::default()
+            .with_limit(0, 5760)
+            .with_limit(2, 462_000)
+            .with_limit(3, 230_686_940)
+            .with_limit(4, 721_424)
+            .with_limit(8, 1408)
+            .with_limit(10, 5760)
+    }
+
+    /// Limits used by the node for the node-to-client protocols, which trust
+    /// the local client and only cap each queue at `u32::MAX` bytes
+    pub fn n2c() -> Self {
+        [0, 1, 2, 5, 6, 7, 9]
+            .into_iter()
+            .fold(Self::default(), |limits, protocol| {
+                limits.with_limit(protocol, u32::MAX as usize)
+            })
+    }
+
+    pub fn with_limit(mut self, protocol: Protocol, bytes: usize) -> Self {
+        self.0.insert(protocol & !0x8000, bytes);
+        self
+    }
+
+    pub fn get(&self, protocol: Protocol) -> Option<usize> {
+        self.0.get(&(protocol & !0x8000)).copied()
+    }
+}
+
+/// Bytes of a protocol that were demuxed but not yet dequeued by its agent
+#[derive(Debug)]
+pub struct IngressQueue {
+    protocol: Protocol,
+    limit: Option<usize>,
+    buffered: AtomicUsize,
+    exceeded: AtomicBool,
+}
+
+impl IngressQueue {
+    fn new(protocol: Protocol, limit: Option<usize>) -> Self {
+        Self {
+            protocol: protocol & !0x8000,
+            limit,
+            buffered: AtomicUsize::new(0),
+            exceeded: AtomicBool::new(false),
+        }
+    }
+
+    /// Accounts for an incoming payload, failing if it overflows the limit
+    fn push(&self, len: usize) -> Result<(), Error> {
+        let buffered = self.buffered.fetch_add(len, Ordering::SeqCst) + len;
+
+        match self.limit {
+            Some(limit) if buffered > limit => {
+                self.exceeded.store(true, Ordering::SeqCst);
+                Err(Error::IngressLimitExceeded(self.protocol, limit))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn pop(&self, len: usize) {
+        self.buffered.fetch_sub(len, Ordering::SeqCst);
+    }
+
+    fn exceeded_error(&self) -> Option<Error> {
+        match self.limit {
+            Some(limit) if self.exceeded.load(Ordering::SeqCst) => {
+                Some(Error::IngressLimitExceeded(self.protocol, limit))
+            }
+            _ => None,
+        }
+    }
 }
 
 type EgressChannel = tokio::sync::mpsc::Sender<Payload>;
-type Egress = HashMap<Protocol, EgressChannel>;
+type Egress = HashMap<Protocol, (EgressChannel, Arc<IngressQueue>)>;
 
 const EGRESS_MSG_QUEUE_BUFFER: usize = 100;
 
-pub struct Demuxer(BearerReadHalf, Egress);
+pub struct Demuxer(BearerReadHalf, Egress, IngressLimits);
 
 impl Demuxer {
     pub fn new(bearer: BearerReadHalf) -> Self {
+        Self::with_ingress_limits(bearer, IngressLimits::default())
+    }
+
+    pub fn with_ingress_limits(bearer: BearerReadHalf, limits: IngressLimits) -> Self {
         let egress = HashMap::new();
-        Self(bearer, egress)
+        Self(bearer, egress, limits)
     }
 
     pub async fn read_segment(&mut self) -> Result<(Protocol, Payload), Error> {
@@ -279,7 +376,9 @@ impl Demuxer {
     async fn demux(&mut self, protocol: Protocol, payload: Payload) -> Result<(), Error> {
         let channel = self.1.get(&protocol);
 
-        if let Some(sender) = channel {
+        if let Some((sender, queue)) = channel {
+            queue.push(payload.len())?;
+
             sender
                 .send(payload)
                 .await
@@ -291,14 +390,18 @@ impl Demuxer {
         Ok(())
     }
 
-    pub fn subscribe(&mut self, protocol: Protocol) -> tokio::sync::mpsc::Receiver<Payload> {
+    pub fn subscribe(
+        &mut self,
+        protocol: Protocol,
+    ) -> (tokio::sync::mpsc::Receiver<Payload>, Arc<IngressQueue>) {
         let (sender, recv) = tokio::sync::mpsc::channel(EGRESS_MSG_QUEUE_BUFFER);
+        let queue = Arc::new(IngressQueue::new(protocol, self.2.get(protocol)));
 
         // keep track of the sender
-        self.1.insert(protocol, sender);
+        self.1.insert(protocol, (sender, queue.clone()));
 
         // return the receiver for the agent
-        recv
+        (recv, queue)
     }
 
     pub async fn tick(&mut self) -> Result<(), Error> {
@@ -397,33 +500,40 @@ pub struct AgentChannel {
     protocol: Protocol,
     to_plexer: ToPlexerPort,
     from_plexer: FromPlexerPort,
+    ingress: Arc<IngressQueue>,
 }
 
 impl AgentChannel {
     fn for_client(
         protocol: Protocol,
         to_plexer: ToPlexerPort,
-        from_plexer: FromPlexerPort,
+        (from_plexer, ingress): (FromPlexerPort, Arc<IngressQueue>),
     ) -> Self {
         Self {
             protocol,
             from_plexer,
             to_plexer,
+            ingress,
         }
     }
 
     fn for_server(
         protocol: Protocol,
         to_plexer: ToPlexerPort,
-        from_plexer: FromPlexerPort,
+        (from_plexer, ingress): (FromPlexerPort, Arc<IngressQueue>),
     ) -> Self {
         Self {
             protocol,
             from_plexer,
             to_plexer,
+            ingress,
         }
     }
 
+    pub fn ingress_limit(&self) -> Option<usize> {
+        self.ingress.limit
+    }
+
     pub async fn enqueue_chunk(&mut self, chunk: Payload) -> Result<(), Error> {
         self.to_plexer
             .send((self.protocol, chunk))
@@ -432,7 +542,14 @@ impl AgentChannel {
     }
 
     pub async fn dequeue_chunk(&mut self) -> Result<Payload, Error> {
-        self.from_plexer.recv().await.ok_or(Error::AgentDequeue)
+        match self.from_plexer.recv().await {
+            Some(chunk) => {
+                self.ingress.pop(chunk.len());
+                Ok(chunk)
+            }
+            // the demuxer drops every channel once a peer overflows a queue
+            None => Err(self.ingress.exceeded_error().unwrap_or(Error::AgentDequeue)),
+        }
     }
 }
 
@@ -454,11 +571,17 @@ pub struct Plexer {
 }
 
 impl Plexer {
+    /// Creates a plexer without ingress limits, see
+    /// [`Plexer::with_ingress_limits`] to bound the ingress queues
     pub fn new(bearer: Bearer) -> Self {
+        Self::with_ingress_limits(bearer, IngressLimits::default())
+    }
+
+    pub fn with_ingress_limits(bearer: Bearer, limits: IngressLimits) -> Self {
         let (r, w) = bearer.into_split();
 
         Self {
-            demuxer: Demuxer::new(r),
+            demuxer: Demuxer::with_ingress_limits(r, limits),
             muxer: Muxer::new(w),
         }
     }
@@ -542,6 +665,21 @@ impl ChannelBuffer {
         Ok(())
     }
 
+    /// Reads from the channel until a complete message is found, failing if
+    /// it doesn't arrive within the given time limit. A `None` limit waits
+    /// forever.
+    pub async fn recv_full_msg_within<M>(&mut self, limit: Option<Duration>) -> Result<M, Error>
+    where
+        M: Fragment,
+    {
+        match limit {
+            Some(limit) => tokio::time::timeout(limit, self.recv_full_msg())
+                .await
+                .map_err(|_| Error::TimeLimitExceeded(self.channel.protocol & !0x8000, limit))?,
+            None => self.recv_full_msg().await,
+        }
+    }
+
     /// Reads from the channel until a complete message is found
     pub async fn recv_full_msg<M>(&mut self) -> Result<M, Error>
     where
@@ -562,6 +700,16 @@ impl ChannelBuffer {
             let chunk = self.channel.dequeue_chunk().await?;
             self.temp.extend(chunk);
 
+            // a partial message can't grow past the ingress limit either
+            if let Some(limit) = self.channel.ingress_limit() {
+                if self.temp.len() > limit {
+                    return Err(Error::IngressLimitExceeded(
+                        self.channel.ingress.protocol,
+                        limit,
+                    ));
+                }
+            }
+
             if let Some(msg) = try_decode_message::<M>(&mut self.temp)? {
                 debug!("decoding done");
                 return Ok(msg);
@@ -599,7 +747,8 @@ mod tests {
         let (to_plexer, _) = tokio::sync::mpsc::channel(100);
         let (into_plexer, from_plexer) = tokio::sync::mpsc::channel(100);
 
-        let channel = AgentChannel::for_client(0, to_plexer, from_plexer);
+        let ingress = Arc::new(IngressQueue::new(0, None));
+        let channel = AgentChannel::for_client(0, to_plexer, (from_plexer, ingress));
 
         into_plexer.send(input).await.unwrap();
 
@@ -621,7 +770,8 @@ mod tests {
         let (to_plexer, _) = tokio::sync::mpsc::channel(100);
         let (into_plexer, from_plexer) = tokio::sync::mpsc::channel(100);
 
-        let channel = AgentChannel::for_client(0, to_plexer, from_plexer);
+        let ingress = Arc::new(IngressQueue::new(0, None));
+        let channel = AgentChannel::for_client(0, to_plexer, (from_plexer, ingress));
 
         while !input.is_empty() {
             let chunk = Vec::from(input.drain(0..2).as_slice());
@@ -637,4 +787,41 @@ mod tests {
 
         assert_eq!(msg, out_msg);
     }
+
+    #[tokio::test]
+    async fn message_not_received_within_time_limit() {
+        let (to_plexer, _) = tokio::sync::mpsc::channel(100);
+        let (_into_plexer, from_plexer) = tokio::sync::mpsc::channel(100);
+
+        let ingress = Arc::new(IngressQueue::new(0, None));
+        let channel = AgentChannel::for_client(2, to_plexer, (from_plexer, ingress));
+
+        let mut buf = ChannelBuffer::new(channel);
+
+        let limit = Duration::from_millis(10);
+        let result = buf.recv_full_msg_within::<(u8, u8)>(Some(limit)).await;
+
+        assert!(matches!(result, Err(Error::TimeLimitExceeded(2, x)) if x == limit));
+    }
+
+    #[tokio::test]
+    async fn partial_message_over_ingress_limit() {
+        let mut input = Vec::new();
+        minicbor::encode(vec![0u8; 64], &mut input).unwrap();
+
+        let (to_plexer, _) = tokio::sync::mpsc::channel(100);
+        let (into_plexer, from_plexer) = tokio::sync::mpsc::channel(100);
+
+        let ingress = Arc::new(IngressQueue::new(0x8002, Some(32)));
+        let channel = AgentChannel::for_client(2, to_plexer, (from_plexer, ingress));
+
+        for chunk in input.chunks(16) {
+            into_plexer.send(chunk.to_vec()).await.unwrap();
+        }
+
+        let mut buf = ChannelBuffer::new(channel);
+        let result = buf.recv_full_msg::<Vec<u8>>().await;
+
+        assert!(matches!(result, Err(Error::IngressLimitExceeded(2, 32))));
+    }
 }
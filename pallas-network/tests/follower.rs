@@ -0,0 +1,312 @@
+use std::net::{Ipv4Addr, SocketAddrV4};
+use std::time::Duration;
+
+use pallas_network::facades::PeerServer;
+use pallas_network::follower::{ChainFollower, FollowEvent, FollowerConfig};
+use pallas_network::miniprotocols::blockfetch::{self, BlockRequest};
+use pallas_network::miniprotocols::chainsync::{self, ClientRequest, HeaderContent, Tip};
+use pallas_network::miniprotocols::Point;
+use pallas_traverse::MultiEraBlock;
+use tokio::net::TcpListener;
+
+struct TestBlock {
+    point: Point,
+    number: u64,
+    header_cbor: Vec<u8>,
+    body: Vec<u8>,
+}
+
+impl TestBlock {
+    fn header(&self) -> HeaderContent {
+        HeaderContent {
+            variant: 5,
+            byron_prefix: None,
+            cbor: self.header_cbor.clone(),
+        }
+    }
+}
+
+fn load_block(name: &str) -> TestBlock {
+    let path = format!("../test_data/{name}.block");
+    let body = hex::decode(std::fs::read_to_string(path).unwrap().trim()).unwrap();
+
+    let block = MultiEraBlock::decode(&body).unwrap();
+    let header = block.header();
+
+    TestBlock {
+        point: Point::Specific(header.slot(), header.hash().to_vec()),
+        number: header.number(),
+        header_cbor: header.cbor().to_vec(),
+        body,
+    }
+}
+
+async fn expect_request_next(server: &mut chainsync::N2NServer) {
+    match server.recv_while_idle().await.unwrap().unwrap() {
+        ClientRequest::RequestNext => (),
+        ClientRequest::Intersect(_) => panic!("unexpected intersect"),
+    }
+}
+
+async fn serve_blocks(server: &mut blockfetch::Server, blocks: &[TestBlock], last: &Point) {
+    loop {
+        let BlockRequest((from, to)) = server.recv_while_idle().await.unwrap().unwrap();
+
+        let from = blocks.iter().position(|b| b.point == from).unwrap();
+        let to = blocks.iter().position(|b| b.point == to).unwrap();
+
+        let bodies = blocks[from..=to].iter().map(|b| b.body.clone()).collect();
+        server.send_block_range(bodies).await.unwrap();
+
+        if blocks[to].point == *last {
+            break;
+        }
+    }
+}
+
+#[tokio::test]
+async fn follower_fetches_blocks_and_reconnects() {
+    let blocks: Vec<_> = ["babbage1", "babbage2", "babbage3", "babbage4"]
+        .into_iter()
+        .map(load_block)
+        .collect();
+
+    let far_tip = Tip(Point::Origin, u64::MAX);
+
+    let listener = TcpListener::bind(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 30007))
+        .await
+        .unwrap();
+
+    let expected = vec![
+        FollowEvent::RollBack(Point::Origin),
+        FollowEvent::RollForward(blocks[0].point.clone(), blocks[0].body.clone()),
+        FollowEvent::RollForward(blocks[1].point.clone(), blocks[1].body.clone()),
+        FollowEvent::RollForward(blocks[2].point.clone(), blocks[2].body.clone()),
+        FollowEvent::RollForward(blocks[3].point.clone(), blocks[3].body.clone()),
+        FollowEvent::RollBack(blocks[2].point.clone()),
+    ];
+
+    let server = tokio::spawn(async move {
+        // first connection: serves two blocks, then drops after a third header
+
+        let mut peer = PeerServer::accept(&listener, 0).await.unwrap();
+        let cs = &mut peer.chainsync;
+
+        match cs.recv_while_idle().await.unwrap().unwrap() {
+            ClientRequest::Intersect(points) => assert_eq!(points, vec![Point::Origin]),
+            ClientRequest::RequestNext => panic!("unexpected request next"),
+        }
+
+        cs.send_intersect_found(Point::Origin, far_tip.clone())
+            .await
+            .unwrap();
+
+        expect_request_next(cs).await;
+        cs.send_roll_backward(Point::Origin, far_tip.clone())
+            .await
+            .unwrap();
+
+        for block in &blocks[0..2] {
+            expect_request_next(cs).await;
+            cs.send_roll_forward(block.header(), far_tip.clone())
+                .await
+                .unwrap();
+        }
+
+        serve_blocks(&mut peer.blockfetch, &blocks, &blocks[1].point).await;
+
+        let cs = &mut peer.chainsync;
+        expect_request_next(cs).await;
+        cs.send_roll_forward(blocks[2].header(), far_tip.clone())
+            .await
+            .unwrap();
+
+        // the follower asks for more only once it received the blocks
+        expect_request_next(cs).await;
+        peer.abort().await;
+
+        // second connection: resumes from the latest emitted point
+
+        let mut peer = PeerServer::accept(&listener, 0).await.unwrap();
+        let cs = &mut peer.chainsync;
+
+        match cs.recv_while_idle().await.unwrap().unwrap() {
+            ClientRequest::Intersect(points) => {
+                assert_eq!(points[0], blocks[1].point);
+                assert_eq!(points.last(), Some(&Point::Origin));
+            }
+            ClientRequest::RequestNext => panic!("unexpected request next"),
+        }
+
+        cs.send_intersect_found(blocks[1].point.clone(), far_tip.clone())
+            .await
+            .unwrap();
+
+        expect_request_next(cs).await;
+        cs.send_roll_backward(blocks[1].point.clone(), far_tip.clone())
+            .await
+            .unwrap();
+
+        let tip = Tip(blocks[3].point.clone(), blocks[3].number);
+
+        for block in &blocks[2..4] {
+            expect_request_next(cs).await;
+            cs.send_roll_forward(block.header(), tip.clone())
+                .await
+                .unwrap();
+        }
+
+        serve_blocks(&mut peer.blockfetch, &blocks, &blocks[3].point).await;
+
+        let cs = &mut peer.chainsync;
+        expect_request_next(cs).await;
+        cs.send_roll_backward(blocks[2].point.clone(), tip.clone())
+            .await
+            .unwrap();
+
+        peer
+    });
+
+    let config = FollowerConfig::new("localhost:30007", 0)
+        .with_pipeline_depth(2)
+        .with_fetch_batch_size(2)
+        .with_reconnect_delay(Duration::from_millis(100));
+
+    let mut follower = ChainFollower::new(config);
+
+    for event in expected {
+        let received = tokio::time::timeout(Duration::from_secs(10), follower.next())
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(received, event);
+    }
+
+    server.await.unwrap().abort().await;
+    follower.abort().await;
+}
+
+#[tokio::test]
+async fn follower_anchors_deep_rollbacks_and_checks_blocks() {
+    let blocks: Vec<_> = ["babbage1", "babbage2", "babbage3"]
+        .into_iter()
+        .map(load_block)
+        .collect();
+
+    let far_tip = Tip(Point::Origin, u64::MAX);
+
+    let listener = TcpListener::bind(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 30010))
+        .await
+        .unwrap();
+
+    let expected = vec![
+        FollowEvent::RollBack(Point::Origin),
+        FollowEvent::RollForward(blocks[0].point.clone(), blocks[0].body.clone()),
+        FollowEvent::RollForward(blocks[1].point.clone(), blocks[1].body.clone()),
+        FollowEvent::RollBack(blocks[0].point.clone()),
+        FollowEvent::RollForward(blocks[1].point.clone(), blocks[1].body.clone()),
+    ];
+
+    let server = tokio::spawn(async move {
+        // first connection: rolls back beyond the window, then sends the
+        // wrong block for a header
+
+        let mut peer = PeerServer::accept(&listener, 0).await.unwrap();
+        let cs = &mut peer.chainsync;
+
+        match cs.recv_while_idle().await.unwrap().unwrap() {
+            ClientRequest::Intersect(points) => assert_eq!(points, vec![Point::Origin]),
+            ClientRequest::RequestNext => panic!("unexpected request next"),
+        }
+
+        cs.send_intersect_found(Point::Origin, far_tip.clone())
+            .await
+            .unwrap();
+
+        expect_request_next(cs).await;
+        cs.send_roll_backward(Point::Origin, far_tip.clone())
+            .await
+            .unwrap();
+
+        for block in &blocks[0..2] {
+            let cs = &mut peer.chainsync;
+            expect_request_next(cs).await;
+            cs.send_roll_forward(block.header(), far_tip.clone())
+                .await
+                .unwrap();
+
+            serve_blocks(&mut peer.blockfetch, &blocks, &block.point).await;
+        }
+
+        // only the latest point is kept, so this one is out of scope
+        let cs = &mut peer.chainsync;
+        expect_request_next(cs).await;
+        cs.send_roll_backward(blocks[0].point.clone(), far_tip.clone())
+            .await
+            .unwrap();
+
+        expect_request_next(cs).await;
+        cs.send_roll_forward(blocks[1].header(), far_tip.clone())
+            .await
+            .unwrap();
+
+        let BlockRequest(range) = peer.blockfetch.recv_while_idle().await.unwrap().unwrap();
+        assert_eq!(range, (blocks[1].point.clone(), blocks[1].point.clone()));
+
+        peer.blockfetch
+            .send_block_range(vec![blocks[2].body.clone()])
+            .await
+            .unwrap();
+
+        // second connection: resumes from the rollback point
+
+        let mut peer = PeerServer::accept(&listener, 0).await.unwrap();
+        let cs = &mut peer.chainsync;
+
+        match cs.recv_while_idle().await.unwrap().unwrap() {
+            ClientRequest::Intersect(points) => {
+                assert_eq!(points, vec![blocks[0].point.clone(), Point::Origin]);
+            }
+            ClientRequest::RequestNext => panic!("unexpected request next"),
+        }
+
+        cs.send_intersect_found(blocks[0].point.clone(), far_tip.clone())
+            .await
+            .unwrap();
+
+        expect_request_next(cs).await;
+        cs.send_roll_backward(blocks[0].point.clone(), far_tip.clone())
+            .await
+            .unwrap();
+
+        expect_request_next(cs).await;
+        cs.send_roll_forward(blocks[1].header(), far_tip.clone())
+            .await
+            .unwrap();
+
+        serve_blocks(&mut peer.blockfetch, &blocks, &blocks[1].point).await;
+
+        peer
+    });
+
+    let config = FollowerConfig::new("localhost:30010", 0)
+        .with_pipeline_depth(1)
+        .with_fetch_batch_size(1)
+        .with_rollback_window(1)
+        .with_reconnect_delay(Duration::from_millis(100));
+
+    let mut follower = ChainFollower::new(config);
+
+    for event in expected {
+        let received = tokio::time::timeout(Duration::from_secs(10), follower.next())
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(received, event);
+    }
+
+    server.await.unwrap().abort().await;
+    follower.abort().await;
+}
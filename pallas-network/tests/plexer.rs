@@ -1,6 +1,6 @@
 use std::net::{Ipv4Addr, SocketAddrV4};
 
-use pallas_network::multiplexer::{Bearer, Plexer};
+use pallas_network::multiplexer::{Bearer, Error, IngressLimits, Plexer};
 use rand::{distributions::Uniform, Rng};
 use tokio::net::TcpListener;
 
@@ -60,3 +60,45 @@ async fn one_way_small_sequence_of_payloads() {
     passive.abort().await;
     active.abort().await;
 }
+
+#[tokio::test]
+async fn payload_over_ingress_limit() {
+    let server = TcpListener::bind(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 50302))
+        .await
+        .unwrap();
+
+    let passive = tokio::task::spawn(async move {
+        let (bearer, _) = Bearer::accept_tcp(&server).await.unwrap();
+        Plexer::with_ingress_limits(bearer, IngressLimits::default().with_limit(3, 100))
+    });
+
+    let mut active = setup_active_muxer::<50302>().await;
+    let mut passive = passive.await.unwrap();
+
+    let mut sender_channel = active.subscribe_client(3);
+    let mut receiver_channel = passive.subscribe_server(3);
+
+    let passive = passive.spawn();
+    let active = active.spawn();
+
+    // nobody dequeues the first payload, so the second one overflows the queue
+    sender_channel
+        .enqueue_chunk(random_payload(60))
+        .await
+        .unwrap();
+    sender_channel
+        .enqueue_chunk(random_payload(60))
+        .await
+        .unwrap();
+
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+    let first = receiver_channel.dequeue_chunk().await.unwrap();
+    assert_eq!(first.len(), 60);
+
+    let result = receiver_channel.dequeue_chunk().await;
+    assert!(matches!(result, Err(Error::IngressLimitExceeded(3, 100))));
+
+    passive.abort().await;
+    active.abort().await;
+}
@@ -10,7 +10,7 @@ use pallas_network::miniprotocols::localstate::queries_v16::{
 };
 use pallas_network::miniprotocols::localtxsubmission::SMaybe;
 use pallas_network::{
-    facades::{DmqClient, NodeClient, PeerClient, PeerServer},
+    facades::{DmqClient, NodeClient, PeerClient, PeerDuplex, PeerServer},
     miniprotocols::{
         blockfetch,
         blockfetch::BlockRequest,
@@ -474,6 +474,189 @@ pub async fn chainsync_server_and_client_happy_path_n2n() {
     tokio::try_join!(client, server).unwrap();
 }
 
+#[tokio::test]
+pub async fn chainsync_pipelined_requests_n2n() {
+    let point1 = Point::Specific(1, vec![0x01]);
+    let point2 = Point::Specific(2, vec![0x02]);
+    let tip = Tip(point2.clone(), 2);
+
+    let header = |cbor: &str| HeaderContent {
+        variant: 1,
+        byron_prefix: None,
+        cbor: hex::decode(cbor).unwrap(),
+    };
+
+    let listener = TcpListener::bind(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 30008))
+        .await
+        .unwrap();
+
+    let server = tokio::spawn({
+        let point1 = point1.clone();
+        let tip = tip.clone();
+
+        async move {
+            let mut peer_server = PeerServer::accept(&listener, 0).await.unwrap();
+            let server_cs = peer_server.chainsync();
+
+            // server answers the pipelined requests one at a time
+
+            for i in 0..3 {
+                match server_cs.recv_while_idle().await.unwrap().unwrap() {
+                    ClientRequest::RequestNext => (),
+                    ClientRequest::Intersect(_) => panic!("unexpected message"),
+                }
+
+                match i {
+                    0 => server_cs
+                        .send_roll_forward(header("c0ffee"), tip.clone())
+                        .await
+                        .unwrap(),
+                    1 => server_cs
+                        .send_roll_backward(point1.clone(), tip.clone())
+                        .await
+                        .unwrap(),
+                    _ => {
+                        server_cs.send_await_reply().await.unwrap();
+                        server_cs
+                            .send_roll_forward(header("deadbeef"), tip.clone())
+                            .await
+                            .unwrap();
+                    }
+                }
+            }
+
+            assert!(server_cs.recv_while_idle().await.unwrap().is_none());
+        }
+    });
+
+    let client = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+
+        let mut client_to_server_conn = PeerClient::connect("localhost:30008", 0).await.unwrap();
+        let client_cs = client_to_server_conn.chainsync();
+
+        let responses = client_cs.request_next_pipelined(3).await.unwrap();
+
+        assert_eq!(responses.len(), 4);
+
+        match &responses[0] {
+            NextResponse::RollForward(content, _) => {
+                assert_eq!(content.cbor, vec![0xc0, 0xff, 0xee])
+            }
+            _ => panic!("unexpected response"),
+        }
+
+        match &responses[1] {
+            NextResponse::RollBackward(point, _) => assert_eq!(*point, point1),
+            _ => panic!("unexpected response"),
+        }
+
+        assert!(matches!(responses[2], NextResponse::Await));
+
+        match &responses[3] {
+            NextResponse::RollForward(content, tip) => {
+                assert_eq!(content.cbor, vec![0xde, 0xad, 0xbe, 0xef]);
+                assert_eq!(tip.0, point2);
+            }
+            _ => panic!("unexpected response"),
+        }
+
+        assert_eq!(client_cs.pipelined(), 0);
+        assert_eq!(*client_cs.state(), chainsync::State::Idle);
+
+        client_cs.send_done().await.unwrap();
+    });
+
+    tokio::try_join!(client, server).unwrap();
+}
+
+#[tokio::test]
+pub async fn chainsync_partial_pipelined_batch_n2n() {
+    let point1 = Point::Specific(1, vec![0x01]);
+    let tip = Tip(point1.clone(), 1);
+
+    let header = |cbor: &str| HeaderContent {
+        variant: 1,
+        byron_prefix: None,
+        cbor: hex::decode(cbor).unwrap(),
+    };
+
+    let listener = TcpListener::bind(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 30011))
+        .await
+        .unwrap();
+
+    let server = tokio::spawn({
+        let point1 = point1.clone();
+        let tip = tip.clone();
+
+        async move {
+            let mut peer_server = PeerServer::accept(&listener, 0).await.unwrap();
+            let server_cs = peer_server.chainsync();
+
+            // the rollback arrives while other requests are still in flight
+
+            for i in 0..4 {
+                match server_cs.recv_while_idle().await.unwrap().unwrap() {
+                    ClientRequest::RequestNext => (),
+                    ClientRequest::Intersect(_) => panic!("unexpected message"),
+                }
+
+                match i {
+                    1 => server_cs
+                        .send_roll_backward(point1.clone(), tip.clone())
+                        .await
+                        .unwrap(),
+                    _ => server_cs
+                        .send_roll_forward(header("c0ffee"), tip.clone())
+                        .await
+                        .unwrap(),
+                }
+            }
+
+            assert!(server_cs.recv_while_idle().await.unwrap().is_none());
+        }
+    });
+
+    let client = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+
+        let mut client_to_server_conn = PeerClient::connect("localhost:30011", 0).await.unwrap();
+        let client_cs = client_to_server_conn.chainsync();
+
+        client_cs.send_request_next_batch(3).await.unwrap();
+
+        // take a single response and top up the pipeline before draining it
+
+        let response = client_cs.recv_pipelined_response().await.unwrap();
+        assert!(matches!(response, NextResponse::RollForward(..)));
+        assert_eq!(client_cs.pipelined(), 2);
+        assert_eq!(*client_cs.state(), chainsync::State::CanAwait);
+
+        client_cs.send_request_next_pipelined().await.unwrap();
+        assert_eq!(client_cs.pipelined(), 3);
+
+        let response = client_cs.recv_pipelined_response().await.unwrap();
+        match response {
+            NextResponse::RollBackward(point, _) => assert_eq!(point, point1),
+            _ => panic!("unexpected response"),
+        }
+        assert_eq!(client_cs.pipelined(), 2);
+
+        let responses = client_cs.recv_pipelined_batch().await.unwrap();
+        assert_eq!(responses.len(), 2);
+        assert!(responses
+            .iter()
+            .all(|r| matches!(r, NextResponse::RollForward(..))));
+
+        assert_eq!(client_cs.pipelined(), 0);
+        assert_eq!(*client_cs.state(), chainsync::State::Idle);
+
+        client_cs.send_done().await.unwrap();
+    });
+
+    tokio::try_join!(client, server).unwrap();
+}
+
 #[cfg(unix)]
 #[tokio::test]
 pub async fn local_state_query_server_and_client_happy_path() {
@@ -1662,6 +1845,176 @@ pub async fn txsubmission_server_and_client_happy_path_n2n() {
     tokio::try_join!(client, server).unwrap();
 }
 
+#[derive(Default)]
+struct TestMempool {
+    known: Vec<txsubmission::EraTxId>,
+    received: Vec<EraTxBody>,
+}
+
+impl txsubmission::Mempool for TestMempool {
+    fn contains(&self, id: &txsubmission::EraTxId) -> bool {
+        self.known.contains(id)
+    }
+
+    fn receive(&mut self, tx: EraTxBody) {
+        self.received.push(tx);
+    }
+}
+
+fn babbage_tx(x: u8) -> (txsubmission::EraTxId, EraTxBody) {
+    let cbor = hex::decode(
+        fs::read_to_string(format!("../test_data/babbage{x}.tx"))
+            .unwrap()
+            .trim(),
+    )
+    .unwrap();
+
+    let hash = pallas_traverse::MultiEraTx::decode_for_era(pallas_traverse::Era::Babbage, &cbor)
+        .unwrap()
+        .hash();
+
+    // babbage is the sixth era of the hard-fork combinator
+    (txsubmission::EraTxId(5, hash.to_vec()), EraTxBody(5, cbor))
+}
+
+#[tokio::test]
+pub async fn txsubmission_inbound_driver_n2n() {
+    let tx_id = |x: u8| babbage_tx(x).0;
+    let tx_body = |x: u8| babbage_tx(x).1;
+
+    let listener = TcpListener::bind(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 30009))
+        .await
+        .unwrap();
+
+    let server = tokio::spawn(async move {
+        let mut peer_server = PeerServer::accept(&listener, 0).await.unwrap();
+
+        let mempool = TestMempool {
+            known: vec![tx_id(2)],
+            ..Default::default()
+        };
+
+        let config = txsubmission::InboundConfig::default()
+            .with_max_unacked(3)
+            .with_max_txs_per_request(1);
+
+        let mut driver = txsubmission::InboundDriver::new(mempool, config);
+
+        driver.run(peer_server.txsubmission()).await.unwrap();
+
+        assert!(peer_server.txsubmission().is_done());
+        assert_eq!(driver.unacknowledged(), 0);
+        assert_eq!(driver.mempool().received, vec![tx_body(1)]);
+
+        peer_server.abort().await;
+    });
+
+    let mut peer_client = PeerClient::connect("localhost:30009", 0).await.unwrap();
+    let client_txsub = peer_client.txsubmission();
+
+    client_txsub.send_init().await.unwrap();
+
+    // the driver never asks for more ids than the unacknowledged limit
+
+    match client_txsub.next_request().await.unwrap() {
+        txsubmission::Request::TxIds(0, 3) => (),
+        _ => panic!("unexpected request"),
+    }
+
+    let ids = [1, 2, 3]
+        .into_iter()
+        .map(|x| TxIdAndSize(tx_id(x), 3))
+        .collect();
+
+    client_txsub.reply_tx_ids(ids).await.unwrap();
+
+    // tx 2 is already in the mempool, the others are downloaded one at a time
+
+    match client_txsub.next_request().await.unwrap() {
+        txsubmission::Request::Txs(ids) => assert_eq!(ids, vec![tx_id(1)]),
+        _ => panic!("unexpected request"),
+    }
+
+    client_txsub.reply_txs(vec![tx_body(1)]).await.unwrap();
+
+    match client_txsub.next_request().await.unwrap() {
+        txsubmission::Request::Txs(ids) => assert_eq!(ids, vec![tx_id(3)]),
+        _ => panic!("unexpected request"),
+    }
+
+    // tx 3 is no longer available
+
+    client_txsub.reply_txs(vec![]).await.unwrap();
+
+    // every announced id gets acknowledged
+
+    match client_txsub.next_request().await.unwrap() {
+        txsubmission::Request::TxIds(3, 3) => (),
+        _ => panic!("unexpected request"),
+    }
+
+    client_txsub.send_done().await.unwrap();
+
+    server.await.unwrap();
+    peer_client.abort().await;
+}
+
+#[tokio::test]
+pub async fn txsubmission_inbound_driver_rejects_unsolicited_txs_n2n() {
+    let listener = TcpListener::bind(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 30013))
+        .await
+        .unwrap();
+
+    let server = tokio::spawn(async move {
+        let mut peer_server = PeerServer::accept(&listener, 0).await.unwrap();
+
+        let config = txsubmission::InboundConfig::default().with_max_txs_per_request(1);
+
+        let mut driver = txsubmission::InboundDriver::new(TestMempool::default(), config);
+
+        let result = driver.run(peer_server.txsubmission()).await;
+
+        // the tx that wasn't requested never reaches the mempool
+        match result {
+            Err(txsubmission::InboundError::UnsolicitedTx(id)) => assert_eq!(id, babbage_tx(2).0),
+            x => panic!("unexpected result {x:?}"),
+        }
+
+        assert!(driver.mempool().received.is_empty());
+
+        peer_server.abort().await;
+    });
+
+    let mut peer_client = PeerClient::connect("localhost:30013", 0).await.unwrap();
+    let client_txsub = peer_client.txsubmission();
+
+    client_txsub.send_init().await.unwrap();
+
+    match client_txsub.next_request().await.unwrap() {
+        txsubmission::Request::TxIds(0, _) => (),
+        _ => panic!("unexpected request"),
+    }
+
+    let ids = [1, 2]
+        .into_iter()
+        .map(|x| TxIdAndSize(babbage_tx(x).0, 3))
+        .collect();
+
+    client_txsub.reply_tx_ids(ids).await.unwrap();
+
+    match client_txsub.next_request().await.unwrap() {
+        txsubmission::Request::Txs(ids) => assert_eq!(ids, vec![babbage_tx(1).0]),
+        _ => panic!("unexpected request"),
+    }
+
+    // tx 2 was announced but not requested yet
+
+    client_txsub.reply_txs(vec![babbage_tx(2).1]).await.unwrap();
+
+    server.await.unwrap();
+    peer_client.abort().await;
+}
+
 #[tokio::test]
 #[ignore]
 pub async fn txsubmission_submit_to_mainnet_peer_n2n() {
@@ -1847,6 +2200,204 @@ pub async fn peer_sharing_server_and_client_happy_path() {
     tokio::try_join!(client, server).unwrap();
 }
 
+#[tokio::test]
+pub async fn duplex_keepalive_both_directions() {
+    let listener = TcpListener::bind(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 30005))
+        .await
+        .unwrap();
+
+    let responder = tokio::spawn(async move {
+        let mut duplex = PeerDuplex::accept(&listener, 0).await.unwrap();
+
+        let (_, data) = duplex.accepted_version().unwrap();
+        assert!(!data.initiator_only_diffusion_mode);
+
+        // the initiator pings us, then we ping the initiator back
+        duplex
+            .keepalive_server()
+            .keepalive_roundtrip()
+            .await
+            .unwrap();
+        duplex
+            .keepalive_client()
+            .keepalive_roundtrip()
+            .await
+            .unwrap();
+    });
+
+    let initiator = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+
+        let mut duplex = PeerDuplex::connect("localhost:30005", 0).await.unwrap();
+
+        let (_, data) = duplex.accepted_version().unwrap();
+        assert!(!data.initiator_only_diffusion_mode);
+
+        duplex
+            .keepalive_client()
+            .keepalive_roundtrip()
+            .await
+            .unwrap();
+        duplex
+            .keepalive_server()
+            .keepalive_roundtrip()
+            .await
+            .unwrap();
+    });
+
+    tokio::try_join!(initiator, responder).unwrap();
+}
+
+// Serves a chain of a single block to the peer: the intersection, the header
+// and then the body.
+async fn serve_single_block(
+    server_cs: &mut chainsync::N2NServer,
+    server_bf: &mut blockfetch::Server,
+    point: Point,
+    header: &str,
+    body: &[u8],
+) {
+    let tip = Tip(point.clone(), 1);
+
+    match server_cs.recv_while_idle().await.unwrap().unwrap() {
+        ClientRequest::Intersect(points) => assert_eq!(points, vec![point.clone()]),
+        ClientRequest::RequestNext => panic!("unexpected message"),
+    };
+
+    server_cs
+        .send_intersect_found(point.clone(), tip.clone())
+        .await
+        .unwrap();
+
+    match server_cs.recv_while_idle().await.unwrap().unwrap() {
+        ClientRequest::RequestNext => (),
+        ClientRequest::Intersect(_) => panic!("unexpected message"),
+    };
+
+    let content = HeaderContent {
+        variant: 6,
+        byron_prefix: None,
+        cbor: hex::decode(header).unwrap(),
+    };
+
+    server_cs.send_roll_forward(content, tip).await.unwrap();
+
+    let BlockRequest(range) = server_bf.recv_while_idle().await.unwrap().unwrap();
+    assert_eq!(range, (point.clone(), point));
+
+    server_bf
+        .send_block_range(vec![body.to_vec()])
+        .await
+        .unwrap();
+
+    assert!(server_bf.recv_while_idle().await.unwrap().is_none());
+    assert!(server_cs.recv_while_idle().await.unwrap().is_none());
+}
+
+// Follows the single block chain served by the peer, returning the header and
+// the body of the block.
+async fn follow_single_block(
+    client_cs: &mut chainsync::N2NClient,
+    client_bf: &mut blockfetch::Client,
+    point: Point,
+) -> (Vec<u8>, Vec<u8>) {
+    let (intersection, _) = client_cs.find_intersect(vec![point.clone()]).await.unwrap();
+    assert_eq!(intersection, Some(point.clone()));
+
+    let header = match client_cs.request_next().await.unwrap() {
+        NextResponse::RollForward(content, _) => content.cbor,
+        _ => panic!("unexpected response"),
+    };
+
+    let body = client_bf.fetch_single(point).await.unwrap();
+
+    client_bf.send_done().await.unwrap();
+    client_cs.send_done().await.unwrap();
+
+    (header, body)
+}
+
+#[tokio::test]
+pub async fn duplex_chainsync_and_blockfetch_both_directions() {
+    let initiator_point = Point::Specific(10, vec![0x10; 32]);
+    let responder_point = Point::Specific(20, vec![0x20; 32]);
+
+    let listener = TcpListener::bind(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 30012))
+        .await
+        .unwrap();
+
+    // each peer serves its own chain while following the one of the other peer,
+    // all over the same bearer
+
+    let responder = tokio::spawn({
+        let initiator_point = initiator_point.clone();
+        let responder_point = responder_point.clone();
+
+        async move {
+            let mut duplex = PeerDuplex::accept(&listener, 0).await.unwrap();
+
+            let ((), followed) = tokio::join!(
+                serve_single_block(
+                    &mut duplex.chainsync_server,
+                    &mut duplex.blockfetch_server,
+                    responder_point,
+                    "deadbeef",
+                    b"responder block",
+                ),
+                follow_single_block(
+                    &mut duplex.chainsync_client,
+                    &mut duplex.blockfetch_client,
+                    initiator_point,
+                ),
+            );
+
+            assert_eq!(
+                followed,
+                (hex::decode("c0ffee").unwrap(), b"initiator block".to_vec())
+            );
+
+            duplex
+        }
+    });
+
+    let initiator = tokio::spawn(async move {
+        let mut duplex = PeerDuplex::connect("localhost:30012", 0).await.unwrap();
+
+        let ((), followed) = tokio::join!(
+            serve_single_block(
+                &mut duplex.chainsync_server,
+                &mut duplex.blockfetch_server,
+                initiator_point,
+                "c0ffee",
+                b"initiator block",
+            ),
+            follow_single_block(
+                &mut duplex.chainsync_client,
+                &mut duplex.blockfetch_client,
+                responder_point,
+            ),
+        );
+
+        assert_eq!(
+            followed,
+            (
+                hex::decode("deadbeef").unwrap(),
+                b"responder block".to_vec()
+            )
+        );
+
+        duplex
+    });
+
+    // the peers are aborted only once both are done, so that the last messages
+    // of either get delivered
+
+    let (initiator, responder) = tokio::try_join!(initiator, responder).unwrap();
+
+    initiator.abort().await;
+    responder.abort().await;
+}
+
 #[cfg(unix)]
 #[tokio::test]
 pub async fn local_message_notification_server_and_client_happy_path() {
@@ -449,7 +449,9 @@ pub struct Constitution {
     pub guardrail_script: Option<ScriptHash>,
 }
 
-#[derive(Encode, Decode, Serialize, Deserialize, Debug, PartialEq, PartialOrd, Eq, Ord, Clone)]
+#[derive(
+    Encode, Decode, Serialize, Deserialize, Debug, PartialEq, PartialOrd, Eq, Ord, Clone, Hash,
+)]
 #[cbor(flat)]
 pub enum Voter {
     #[n(0)]
@@ -690,16 +692,16 @@ pub struct Block<'b> {
     pub header: KeepRaw<'b, Header>,
 
     #[b(1)]
-    pub transaction_bodies: Vec<KeepRaw<'b, TransactionBody<'b>>>,
+    pub transaction_bodies: KeepRaw<'b, Vec<KeepRaw<'b, TransactionBody<'b>>>>,
 
-    #[n(2)]
-    pub transaction_witness_sets: Vec<KeepRaw<'b, WitnessSet<'b>>>,
+    #[b(2)]
+    pub transaction_witness_sets: KeepRaw<'b, Vec<KeepRaw<'b, WitnessSet<'b>>>>,
 
-    #[n(3)]
-    pub auxiliary_data_set: BTreeMap<TransactionIndex, KeepRaw<'b, AuxiliaryData>>,
+    #[b(3)]
+    pub auxiliary_data_set: KeepRaw<'b, BTreeMap<TransactionIndex, KeepRaw<'b, AuxiliaryData>>>,
 
-    #[n(4)]
-    pub invalid_transactions: Option<Vec<TransactionIndex>>,
+    #[b(4)]
+    pub invalid_transactions: Option<KeepRaw<'b, Vec<TransactionIndex>>>,
 }
 
 #[deprecated(since = "1.0.0-alpha", note = "use `Block` instead")]
@@ -0,0 +1,1061 @@
+use std::collections::{BTreeMap, HashSet};
+
+use pallas_addresses::{Address as PallasAddress, ShelleyPaymentPart};
+use pallas_crypto::hash::Hash;
+use pallas_primitives::{
+    conway::{Certificate, Redeemers, StakeCredential, Tx, VKeyWitness, Voter},
+    Fragment, NonEmptySet, RationalNumber,
+};
+use pallas_validate::utils::ConwayProtParams;
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    conway::BuildConway,
+    transaction::{
+        model::{BuiltTransaction, Input, Output, StagingTransaction},
+        Hash28,
+    },
+    TxBuilderError,
+};
+
+/// Strategy used to pick inputs from the candidate UTxOs when balancing a
+/// transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoinSelection {
+    /// Take the candidates holding the most of each missing asset first
+    LargestFirst,
+    /// Random selection followed by an improvement phase, as in CIP-2
+    RandomImprove,
+}
+
+/// Size of the reference script tiers used by the Conway min fee
+const REF_SCRIPT_TIER_SIZE: u128 = 25_600;
+
+type AssetId = (Hash28, Vec<u8>);
+
+/// Signed lovelace and asset amounts, used to track what the selected inputs
+/// still have to cover
+#[derive(Debug, Default, Clone)]
+struct Balance {
+    lovelace: i128,
+    assets: BTreeMap<AssetId, i128>,
+}
+
+impl Balance {
+    fn of_output(output: &Output) -> Self {
+        let mut balance = Balance {
+            lovelace: output.lovelace as i128,
+            ..Default::default()
+        };
+
+        for (policy, assets) in output.assets.iter().flat_map(|x| x.iter()) {
+            for (name, amount) in assets {
+                balance.add_asset((policy.clone(), name.0.clone()), *amount as i128);
+            }
+        }
+
+        balance
+    }
+
+    fn add_asset(&mut self, asset: AssetId, amount: i128) {
+        let total = self.assets.entry(asset.clone()).or_default();
+        *total += amount;
+
+        if *total == 0 {
+            self.assets.remove(&asset);
+        }
+    }
+
+    fn add(&mut self, other: &Balance) {
+        self.lovelace += other.lovelace;
+
+        for (asset, amount) in other.assets.iter() {
+            self.add_asset(asset.clone(), *amount);
+        }
+    }
+
+    fn sub(&mut self, other: &Balance) {
+        self.lovelace -= other.lovelace;
+
+        for (asset, amount) in other.assets.iter() {
+            self.add_asset(asset.clone(), -*amount);
+        }
+    }
+
+    /// The first asset, or else the lovelace, that is still missing
+    fn first_deficit(&self) -> Option<(Option<&AssetId>, u64)> {
+        self.assets
+            .iter()
+            .find(|(_, amount)| **amount < 0)
+            .map(|(asset, amount)| (Some(asset), amount.unsigned_abs() as u64))
+            .or_else(|| (self.lovelace < 0).then(|| (None, self.lovelace.unsigned_abs() as u64)))
+    }
+
+    fn into_output(self, address: PallasAddress) -> Result<Output, TxBuilderError> {
+        let mut output = Output::new(address, self.lovelace as u64);
+
+        for ((policy, name), amount) in self.assets {
+            output = output.add_asset(Hash::from(policy.0), name, amount as u64)?;
+        }
+
+        Ok(output)
+    }
+}
+
+fn amount_of(output: &Output, asset: Option<&AssetId>) -> u64 {
+    match asset {
+        None => output.lovelace,
+        Some((policy, name)) => output
+            .assets
+            .as_ref()
+            .and_then(|x| x.get(policy))
+            .and_then(|x| x.iter().find(|(n, _)| &n.0 == name))
+            .map(|(_, amount)| *amount)
+            .unwrap_or_default(),
+    }
+}
+
+/// Move candidates from `pool` into `selected` until `target` of the given
+/// asset (or lovelace) is covered
+fn select<R: Rng>(
+    pool: &mut Vec<(Input, Output)>,
+    selected: &mut Vec<(Input, Output)>,
+    asset: Option<&AssetId>,
+    target: u64,
+    strategy: CoinSelection,
+    rng: &mut R,
+) -> Result<(), TxBuilderError> {
+    let mut candidates = (0..pool.len())
+        .filter(|i| amount_of(&pool[*i].1, asset) > 0)
+        .collect::<Vec<_>>();
+
+    let available: u64 = candidates
+        .iter()
+        .map(|i| amount_of(&pool[*i].1, asset))
+        .sum();
+
+    if available < target {
+        return Err(TxBuilderError::InsufficientFunds);
+    }
+
+    let mut chosen = vec![];
+    let mut covered = 0;
+
+    match strategy {
+        CoinSelection::LargestFirst => {
+            candidates.sort_by_key(|i| std::cmp::Reverse(amount_of(&pool[*i].1, asset)));
+
+            for i in candidates {
+                if covered >= target {
+                    break;
+                }
+
+                covered += amount_of(&pool[i].1, asset);
+                chosen.push(i);
+            }
+        }
+        CoinSelection::RandomImprove => {
+            candidates.shuffle(rng);
+
+            let mut remaining = candidates.into_iter();
+
+            for i in remaining.by_ref() {
+                covered += amount_of(&pool[i].1, asset);
+                chosen.push(i);
+
+                if covered >= target {
+                    break;
+                }
+            }
+
+            // improve towards twice the target, never going above three times
+            let (ideal, maximum) = (target.saturating_mul(2), target.saturating_mul(3));
+
+            for i in remaining {
+                let amount = amount_of(&pool[i].1, asset);
+                let improved = covered + amount;
+
+                if improved <= maximum && improved.abs_diff(ideal) < covered.abs_diff(ideal) {
+                    covered = improved;
+                    chosen.push(i);
+                }
+            }
+        }
+    }
+
+    chosen.sort_unstable_by(|a, b| b.cmp(a));
+
+    for i in chosen {
+        selected.push(pool.remove(i));
+    }
+
+    Ok(())
+}
+
+fn min_utxo(output: &Output, pparams: &ConwayProtParams) -> Result<u64, TxBuilderError> {
+    let size = output
+        .build_babbage_raw()?
+        .encode_fragment()
+        .map_err(|_| TxBuilderError::CorruptedTxBytes)?
+        .len() as u64;
+
+    Ok((160 + size) * pparams.ada_per_utxo_byte)
+}
+
+/// Deposits paid and refunded by the staged certificates and proposals. Pool
+/// registrations are assumed to be new registrations.
+fn deposits_and_refunds(staging: &StagingTransaction, pparams: &ConwayProtParams) -> (u64, u64) {
+    let (mut deposits, mut refunds) = (0, 0);
+
+    for cert in staging.certificates.iter().flatten() {
+        match cert {
+            Certificate::StakeRegistration(_) => deposits += pparams.key_deposit,
+            Certificate::StakeDeregistration(_) => refunds += pparams.key_deposit,
+            Certificate::PoolRegistration { .. } => deposits += pparams.pool_deposit,
+            Certificate::Reg(_, coin)
+            | Certificate::StakeRegDeleg(_, _, coin)
+            | Certificate::VoteRegDeleg(_, _, coin)
+            | Certificate::StakeVoteRegDeleg(_, _, _, coin)
+            | Certificate::RegDRepCert(_, coin, _) => deposits += coin,
+            Certificate::UnReg(_, coin) | Certificate::UnRegDRepCert(_, coin) => refunds += coin,
+            _ => (),
+        }
+    }
+
+    for proposal in staging.proposal_procedures.iter().flatten() {
+        deposits += proposal.deposit;
+    }
+
+    (deposits, refunds)
+}
+
+/// Number of distinct key hashes expected to sign the transaction
+fn estimated_signers(staging: &StagingTransaction, spent: &[&Output]) -> usize {
+    if let Some(amount) = staging.signature_amount_override {
+        return amount as usize;
+    }
+
+    let mut keys: HashSet<Vec<u8>> = HashSet::new();
+
+    for output in spent {
+        match &output.address.0 {
+            PallasAddress::Shelley(addr) => {
+                if let ShelleyPaymentPart::Key(hash) = addr.payment() {
+                    keys.insert(hash.to_vec());
+                }
+            }
+            PallasAddress::Byron(addr) => {
+                keys.insert(addr.to_vec());
+            }
+            PallasAddress::Stake(_) => (),
+        }
+    }
+
+    for signer in staging.disclosed_signers.iter().flatten() {
+        keys.insert(signer.0.to_vec());
+    }
+
+    for account in staging.withdrawals.iter().flat_map(|x| x.keys()) {
+        if account.0.first().is_some_and(|header| header & 0x10 == 0) {
+            keys.insert(account.0[1..].to_vec());
+        }
+    }
+
+    for cert in staging.certificates.iter().flatten() {
+        let credential = match cert {
+            Certificate::StakeRegistration(_) => None,
+            Certificate::StakeDeregistration(cred)
+            | Certificate::StakeDelegation(cred, _)
+            | Certificate::Reg(cred, _)
+            | Certificate::UnReg(cred, _)
+            | Certificate::VoteDeleg(cred, _)
+            | Certificate::StakeVoteDeleg(cred, _, _)
+            | Certificate::StakeRegDeleg(cred, _, _)
+            | Certificate::VoteRegDeleg(cred, _, _)
+            | Certificate::StakeVoteRegDeleg(cred, _, _, _)
+            | Certificate::AuthCommitteeHot(cred, _)
+            | Certificate::ResignCommitteeCold(cred, _)
+            | Certificate::RegDRepCert(cred, _, _)
+            | Certificate::UnRegDRepCert(cred, _)
+            | Certificate::UpdateDRepCert(cred, _) => Some(cred),
+            Certificate::PoolRegistration {
+                operator,
+                pool_owners,
+                ..
+            } => {
+                keys.insert(operator.to_vec());
+                keys.extend(pool_owners.iter().map(|x| x.to_vec()));
+                None
+            }
+            Certificate::PoolRetirement(operator, _) => {
+                keys.insert(operator.to_vec());
+                None
+            }
+        };
+
+        if let Some(StakeCredential::AddrKeyhash(hash)) = credential {
+            keys.insert(hash.to_vec());
+        }
+    }
+
+    for (voter, _, _) in staging.voting_procedures.iter().flatten() {
+        match voter {
+            Voter::ConstitutionalCommitteeKey(hash)
+            | Voter::DRepKey(hash)
+            | Voter::StakePoolKey(hash) => {
+                keys.insert(hash.to_vec());
+            }
+            Voter::ConstitutionalCommitteeScript(_) | Voter::DRepScript(_) => (),
+        }
+    }
+
+    keys.len()
+}
+
+/// Cost of the execution units, rounded up
+fn ex_units_fee(mem: u64, steps: u64, pparams: &ConwayProtParams) -> u64 {
+    let RationalNumber {
+        numerator: mem_num,
+        denominator: mem_den,
+    } = pparams.execution_costs.mem_price;
+
+    let RationalNumber {
+        numerator: step_num,
+        denominator: step_den,
+    } = pparams.execution_costs.step_price;
+
+    let num = mem as u128 * mem_num as u128 * step_den as u128
+        + steps as u128 * step_num as u128 * mem_den as u128;
+
+    num.div_ceil(mem_den as u128 * step_den as u128) as u64
+}
+
+/// Tiered cost of the reference scripts, where the price per byte grows by a
+/// factor of 1.2 for every tier of 25 KiB
+fn ref_scripts_fee(size: u64, pparams: &ConwayProtParams) -> u64 {
+    let RationalNumber {
+        numerator,
+        denominator,
+    } = pparams.minfee_refscript_cost_per_byte;
+
+    let (mut acc, mut price, mut den) = (0u128, numerator as u128, denominator as u128);
+    let mut remaining = size as u128;
+
+    while remaining > 0 {
+        let chunk = remaining.min(REF_SCRIPT_TIER_SIZE);
+
+        acc += chunk * price;
+        remaining -= chunk;
+
+        if remaining > 0 {
+            acc *= 5;
+            price *= 6;
+            den *= 5;
+        }
+    }
+
+    (acc / den) as u64
+}
+
+/// Minimum fee of a built transaction once it carries `signers` vkey witnesses
+fn min_fee(
+    built: &BuiltTransaction,
+    signers: usize,
+    ref_scripts_size: u64,
+    pparams: &ConwayProtParams,
+) -> Result<u64, TxBuilderError> {
+    let mut tx =
+        Tx::decode_fragment(&built.tx_bytes.0).map_err(|_| TxBuilderError::CorruptedTxBytes)?;
+
+    let witness = VKeyWitness {
+        vkey: vec![0; 32].into(),
+        signature: vec![0; 64].into(),
+    };
+
+    tx.transaction_witness_set.vkeywitness = NonEmptySet::from_vec(vec![witness; signers]);
+
+    let size = tx
+        .encode_fragment()
+        .map_err(|_| TxBuilderError::CorruptedTxBytes)?
+        .len() as u64;
+
+    let (mem, steps) = match tx.transaction_witness_set.redeemer.as_deref() {
+        Some(Redeemers::List(x)) => x.iter().fold((0, 0), |(m, s), r| {
+            (m + r.ex_units.mem, s + r.ex_units.steps)
+        }),
+        Some(Redeemers::Map(x)) => x.values().fold((0, 0), |(m, s), r| {
+            (m + r.ex_units.mem, s + r.ex_units.steps)
+        }),
+        None => (0, 0),
+    };
+
+    Ok(pparams.minfee_a as u64 * size
+        + pparams.minfee_b as u64
+        + ex_units_fee(mem, steps, pparams)
+        + ref_scripts_fee(ref_scripts_size, pparams))
+}
+
+/// Collateral put up for a transaction with scripts
+struct Collateral {
+    inputs: Vec<(Input, Output)>,
+    output: Option<Output>,
+    total: u64,
+}
+
+fn is_vkey_locked(output: &Output) -> bool {
+    match &output.address.0 {
+        PallasAddress::Shelley(addr) => matches!(addr.payment(), ShelleyPaymentPart::Key(_)),
+        PallasAddress::Byron(_) => true,
+        PallasAddress::Stake(_) => false,
+    }
+}
+
+/// Split collateral inputs worth `total` into the collateral paid and the
+/// output returning the rest, if they can cover `required`
+fn settle_collateral(
+    mut total: Balance,
+    required: u64,
+    address: &PallasAddress,
+    pparams: &ConwayProtParams,
+) -> Result<Option<(Option<Output>, u64)>, TxBuilderError> {
+    if total.lovelace < required as i128 {
+        return Ok(None);
+    }
+
+    if total.lovelace == required as i128 && total.assets.is_empty() {
+        return Ok(Some((None, required)));
+    }
+
+    let has_assets = !total.assets.is_empty();
+    let paid = total.lovelace as u64;
+
+    total.lovelace -= required as i128;
+
+    let output = total.into_output(address.clone())?;
+
+    if output.lovelace >= min_utxo(&output, pparams)? {
+        Ok(Some((Some(output), required)))
+    } else if !has_assets {
+        // too little to return, so all of it is put up as collateral
+        Ok(Some((None, paid)))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Collateral for a transaction paying `fee`, using the staged collateral
+/// inputs or else choosing vkey-locked UTxOs, pure-ADA ones first
+fn select_collateral(
+    staging: &StagingTransaction,
+    utxos: &[(Input, Output)],
+    fee: u64,
+    pparams: &ConwayProtParams,
+    address: &PallasAddress,
+) -> Result<Option<Collateral>, TxBuilderError> {
+    let has_redeemers = staging.redeemers.as_ref().is_some_and(|x| !x.is_empty());
+
+    if !has_redeemers || staging.collateral_output.is_some() {
+        return Ok(None);
+    }
+
+    let required = (fee * pparams.collateral_percentage as u64).div_ceil(100);
+
+    let resolve = |input: &Input| utxos.iter().find(|(x, _)| x == input).cloned();
+
+    if let Some(inputs) = staging.collateral_inputs.as_ref().filter(|x| !x.is_empty()) {
+        if inputs.len() > pparams.max_collateral_inputs as usize {
+            return Err(TxBuilderError::TooManyCollateralInputs);
+        }
+
+        let inputs = inputs
+            .iter()
+            .map(resolve)
+            .collect::<Option<Vec<_>>>()
+            .ok_or(TxBuilderError::UnresolvedInput)?;
+
+        let mut total = Balance::default();
+
+        for (_, output) in inputs.iter() {
+            total.add(&Balance::of_output(output));
+        }
+
+        let (output, total) = settle_collateral(total, required, address, pparams)?
+            .ok_or(TxBuilderError::InsufficientCollateral)?;
+
+        return Ok(Some(Collateral {
+            inputs,
+            output,
+            total,
+        }));
+    }
+
+    let reference_inputs = staging
+        .reference_inputs
+        .iter()
+        .flatten()
+        .collect::<Vec<_>>();
+
+    let mut candidates = utxos
+        .iter()
+        .filter(|(x, o)| is_vkey_locked(o) && !reference_inputs.contains(&x))
+        .collect::<Vec<_>>();
+
+    candidates.sort_by_key(|(_, o)| (o.assets.is_some(), std::cmp::Reverse(o.lovelace)));
+
+    let mut inputs = vec![];
+    let mut total = Balance::default();
+
+    for candidate in candidates
+        .into_iter()
+        .take(pparams.max_collateral_inputs as usize)
+    {
+        total.add(&Balance::of_output(&candidate.1));
+        inputs.push(candidate.clone());
+
+        if let Some((output, total)) = settle_collateral(total.clone(), required, address, pparams)?
+        {
+            return Ok(Some(Collateral {
+                inputs,
+                output,
+                total,
+            }));
+        }
+    }
+
+    Err(TxBuilderError::InsufficientCollateral)
+}
+
+pub(crate) fn build_balanced<R: Rng>(
+    staging: StagingTransaction,
+    utxos: &[(Input, Output)],
+    pparams: &ConwayProtParams,
+    strategy: CoinSelection,
+    rng: &mut R,
+) -> Result<BuiltTransaction, TxBuilderError> {
+    let change_address = staging
+        .change_address
+        .clone()
+        .ok_or(TxBuilderError::MissingChangeAddress)?;
+
+    let resolve = |input: &Input| utxos.iter().find(|(x, _)| x == input).map(|(_, x)| x);
+
+    let mut selected = staging
+        .inputs
+        .iter()
+        .flatten()
+        .map(|x| resolve(x).map(|o| (x.clone(), o.clone())))
+        .collect::<Option<Vec<_>>>()
+        .ok_or(TxBuilderError::UnresolvedInput)?;
+
+    let reserved = staging
+        .inputs
+        .iter()
+        .chain(staging.reference_inputs.iter())
+        .chain(staging.collateral_inputs.iter())
+        .flatten()
+        .collect::<Vec<_>>();
+
+    let mut pool = utxos
+        .iter()
+        .filter(|(x, _)| !reserved.contains(&x))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let mut required = Balance::default();
+
+    for output in staging.outputs.iter().flatten() {
+        if output.lovelace < min_utxo(output, pparams)? {
+            return Err(TxBuilderError::OutputBelowMinUtxo);
+        }
+
+        required.add(&Balance::of_output(output));
+    }
+
+    for (policy, assets) in staging.mint.iter().flat_map(|x| x.iter()) {
+        for (name, amount) in assets {
+            required.add_asset((policy.clone(), name.0.clone()), -(*amount as i128));
+        }
+    }
+
+    let (deposits, refunds) = deposits_and_refunds(&staging, pparams);
+    let withdrawals: u64 = staging.withdrawals.iter().flat_map(|x| x.values()).sum();
+
+    required.lovelace += deposits as i128 - refunds as i128 - withdrawals as i128;
+
+    let ref_input_scripts: u64 = staging
+        .reference_inputs
+        .iter()
+        .flatten()
+        .filter_map(resolve)
+        .filter_map(|x| x.script.as_ref())
+        .map(|x| x.bytes.0.len() as u64)
+        .sum();
+
+    let mut fee = 0;
+
+    loop {
+        let mut leftover = Balance::default();
+
+        for (_, output) in selected.iter() {
+            leftover.add(&Balance::of_output(output));
+        }
+
+        leftover.sub(&required);
+        leftover.lovelace -= fee as i128;
+
+        if let Some((asset, missing)) = leftover.first_deficit() {
+            select(&mut pool, &mut selected, asset, missing, strategy, rng)?;
+            continue;
+        }
+
+        let (change, dust) = if leftover.lovelace == 0 && leftover.assets.is_empty() {
+            (None, 0)
+        } else {
+            let has_assets = !leftover.assets.is_empty();
+            let change = leftover.into_output(change_address.0.clone())?;
+            let min_change = min_utxo(&change, pparams)?;
+
+            if change.lovelace >= min_change {
+                (Some(change), 0)
+            } else if !has_assets {
+                // too little to return, so it goes to the fee instead
+                (None, change.lovelace)
+            } else {
+                let missing = min_change - change.lovelace;
+                select(&mut pool, &mut selected, None, missing, strategy, rng)?;
+                continue;
+            }
+        };
+
+        let mut tx = staging.clone();
+
+        tx.inputs = Some(selected.iter().map(|(x, _)| x.clone()).collect());
+        tx.fee = Some(fee + dust);
+
+        if let Some(change) = change {
+            tx = tx.output(change);
+        }
+
+        let mut spent = selected.iter().map(|(_, x)| x).collect::<Vec<_>>();
+
+        let ref_scripts_size = ref_input_scripts
+            + spent
+                .iter()
+                .filter_map(|x| x.script.as_ref())
+                .map(|x| x.bytes.0.len() as u64)
+                .sum::<u64>();
+
+        let collateral = select_collateral(&tx, utxos, fee + dust, pparams, &change_address.0)?;
+
+        if let Some(collateral) = collateral.as_ref() {
+            tx.collateral_inputs = Some(collateral.inputs.iter().map(|(x, _)| x.clone()).collect());
+            tx.collateral_output = collateral.output.clone();
+            tx.total_collateral = Some(collateral.total);
+
+            spent.extend(collateral.inputs.iter().map(|(_, x)| x));
+        }
+
+        let signers = estimated_signers(&tx, &spent);
+        let built = tx.build_conway_raw()?;
+        let required_fee = min_fee(&built, signers, ref_scripts_size, pparams)?;
+
+        if required_fee <= fee {
+            return Ok(built);
+        }
+
+        fee = required_fee;
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::borrow::Cow;
+
+    use pallas_addresses::{Network, ShelleyAddress, ShelleyDelegationPart};
+    use pallas_crypto::{hash::Hasher, key::ed25519::SecretKey};
+    use pallas_primitives::{
+        conway::{
+            CostModels, DRepVotingThresholds, PoolVotingThresholds, TransactionInput,
+            TransactionOutput,
+        },
+        ExUnitPrices, ExUnits as PallasExUnits,
+    };
+    use pallas_traverse::{MultiEraInput, MultiEraOutput, MultiEraTx};
+    use pallas_validate::{
+        phase1::validate_txs,
+        utils::{
+            AccountState, CertState, DRepState, Environment, GovState, MultiEraProtocolParameters,
+            PostAlonzoError as E, UTxOs, ValidationError::PostAlonzo, ValidationResult,
+        },
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::{ExUnits, ScriptKind};
+
+    use super::*;
+
+    fn rational(numerator: u64, denominator: u64) -> RationalNumber {
+        RationalNumber {
+            numerator,
+            denominator,
+        }
+    }
+
+    pub(crate) fn mk_params() -> ConwayProtParams {
+        ConwayProtParams {
+            system_start: chrono::DateTime::parse_from_rfc3339("2017-09-23T21:44:51Z").unwrap(),
+            epoch_length: 432000,
+            slot_length: 1,
+            minfee_a: 44,
+            minfee_b: 155381,
+            max_block_body_size: 90112,
+            max_transaction_size: 16384,
+            max_block_header_size: 1100,
+            key_deposit: 2000000,
+            pool_deposit: 500000000,
+            desired_number_of_stake_pools: 500,
+            protocol_version: (9, 0),
+            min_pool_cost: 340000000,
+            ada_per_utxo_byte: 4310,
+            cost_models_for_script_languages: CostModels {
+                plutus_v1: None,
+                plutus_v2: None,
+                plutus_v3: None,
+                unknown: BTreeMap::default(),
+            },
+            execution_costs: ExUnitPrices {
+                mem_price: rational(577, 10000),
+                step_price: rational(721, 10000000),
+            },
+            max_tx_ex_units: PallasExUnits {
+                mem: 14000000,
+                steps: 10000000000,
+            },
+            max_block_ex_units: PallasExUnits {
+                mem: 62000000,
+                steps: 40000000000,
+            },
+            max_value_size: 5000,
+            collateral_percentage: 150,
+            max_collateral_inputs: 3,
+            expansion_rate: rational(3, 1000),
+            treasury_growth_rate: rational(2, 10),
+            maximum_epoch: 18,
+            pool_pledge_influence: rational(3, 10),
+            pool_voting_thresholds: PoolVotingThresholds {
+                motion_no_confidence: rational(51, 100),
+                committee_normal: rational(51, 100),
+                committee_no_confidence: rational(51, 100),
+                hard_fork_initiation: rational(51, 100),
+                security_voting_threshold: rational(51, 100),
+            },
+            drep_voting_thresholds: DRepVotingThresholds {
+                motion_no_confidence: rational(67, 100),
+                committee_normal: rational(67, 100),
+                committee_no_confidence: rational(6, 10),
+                update_constitution: rational(75, 100),
+                hard_fork_initiation: rational(6, 10),
+                pp_network_group: rational(67, 100),
+                pp_economic_group: rational(67, 100),
+                pp_technical_group: rational(67, 100),
+                pp_governance_group: rational(75, 100),
+                treasury_withdrawal: rational(67, 100),
+            },
+            min_committee_size: 7,
+            committee_term_limit: 146,
+            governance_action_validity_period: 6,
+            governance_action_deposit: 100000000000,
+            drep_deposit: 500000000,
+            drep_inactivity_period: 20,
+            minfee_refscript_cost_per_byte: rational(15, 1),
+        }
+    }
+
+    fn mk_address(key: &SecretKey) -> PallasAddress {
+        let hash = Hasher::<224>::hash(key.public_key().as_ref());
+
+        ShelleyAddress::new(
+            Network::Testnet,
+            ShelleyPaymentPart::key_hash(hash),
+            ShelleyDelegationPart::Null,
+        )
+        .into()
+    }
+
+    fn mk_utxos(address: &PallasAddress, values: &[(u64, u64)]) -> Vec<(Input, Output)> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, (lovelace, tokens))| {
+                let mut output = Output::new(address.clone(), *lovelace);
+
+                if *tokens > 0 {
+                    output = output
+                        .add_asset([9; 28].into(), b"token".to_vec(), *tokens)
+                        .unwrap();
+                }
+
+                (Input::new([i as u8; 32].into(), 0), output)
+            })
+            .collect()
+    }
+
+    fn validate(built: &BuiltTransaction, utxos: &[(Input, Output)]) -> ValidationResult {
+        validate_with(built, utxos, &mut CertState::default())
+    }
+
+    fn validate_with(
+        built: &BuiltTransaction,
+        utxos: &[(Input, Output)],
+        cert_state: &mut CertState,
+    ) -> ValidationResult {
+        let mut ledger_utxos: UTxOs = UTxOs::new();
+
+        for (input, output) in utxos {
+            let tx_in = TransactionInput {
+                transaction_id: input.tx_hash.0.into(),
+                index: input.txo_index,
+            };
+
+            let tx_out: TransactionOutput = output.build_babbage_raw().unwrap();
+
+            ledger_utxos.insert(
+                MultiEraInput::AlonzoCompatible(Box::new(Cow::Owned(tx_in))),
+                MultiEraOutput::Conway(Box::new(Cow::Owned(tx_out))),
+            );
+        }
+
+        let metx = MultiEraTx::decode(&built.tx_bytes.0).unwrap();
+
+        let env = Environment {
+            prot_params: MultiEraProtocolParameters::Conway(mk_params()),
+            prot_magic: 764824073,
+            block_slot: 134000000,
+            network_id: 0,
+            acnt: Some(AccountState::default()),
+        };
+
+        validate_txs(
+            &[metx],
+            &env,
+            &ledger_utxos,
+            cert_state,
+            &mut GovState::default(),
+        )
+    }
+
+    #[test]
+    fn largest_first_balances_and_validates() {
+        let key = SecretKey::from([7; 32]);
+        let address = mk_address(&key);
+        let utxos = mk_utxos(&address, &[(3_000_000, 0), (10_000_000, 0), (5_000_000, 0)]);
+
+        let built = StagingTransaction::new()
+            .output(Output::new(address.clone(), 8_000_000))
+            .change_address(address)
+            .build_conway_balanced(&utxos, &mk_params(), CoinSelection::LargestFirst)
+            .unwrap();
+
+        let tx = Tx::decode_fragment(&built.tx_bytes.0).unwrap();
+
+        assert_eq!(tx.transaction_body.inputs.len(), 1);
+        assert_eq!(tx.transaction_body.outputs.len(), 2);
+
+        validate(&built.sign(&key).unwrap(), &utxos).unwrap();
+    }
+
+    #[test]
+    fn random_improve_returns_assets_as_change() {
+        let key = SecretKey::from([7; 32]);
+        let address = mk_address(&key);
+        let utxos = mk_utxos(
+            &address,
+            &[
+                (2_000_000, 100),
+                (4_000_000, 0),
+                (6_000_000, 0),
+                (3_000_000, 0),
+            ],
+        );
+
+        let output = Output::new(address.clone(), 2_000_000)
+            .add_asset([9; 28].into(), b"token".to_vec(), 40)
+            .unwrap();
+
+        let staging = StagingTransaction::new()
+            .output(output)
+            .change_address(address);
+
+        let built = build_balanced(
+            staging,
+            &utxos,
+            &mk_params(),
+            CoinSelection::RandomImprove,
+            &mut StdRng::seed_from_u64(42),
+        )
+        .unwrap();
+
+        validate(&built.sign(&key).unwrap(), &utxos).unwrap();
+    }
+
+    #[test]
+    fn collateral_prefers_pure_ada() {
+        let key = SecretKey::from([7; 32]);
+        let address = mk_address(&key);
+        let utxos = mk_utxos(
+            &address,
+            &[(20_000_000, 5), (10_000_000, 0), (4_000_000, 0)],
+        );
+
+        let script = vec![0x46, 0x01, 0x00, 0x00, 0x22, 0x00, 0x11];
+        let policy = Hasher::<224>::hash_tagged(&script, 2);
+
+        let built = StagingTransaction::new()
+            .output(Output::new(address.clone(), 3_000_000))
+            .mint_asset(policy, b"minted".to_vec(), 1)
+            .unwrap()
+            .script(ScriptKind::PlutusV2, script)
+            .add_mint_redeemer(
+                policy,
+                vec![0x00],
+                Some(ExUnits {
+                    mem: 1000,
+                    steps: 1000,
+                }),
+            )
+            .change_address(address)
+            .build_conway_balanced(&utxos, &mk_params(), CoinSelection::LargestFirst)
+            .unwrap();
+
+        let tx = Tx::decode_fragment(&built.tx_bytes.0).unwrap();
+        let body = &tx.transaction_body;
+
+        // the largest UTxO holds tokens, so the largest pure-ADA one is used
+        let collateral = body.collateral.iter().flat_map(|x| x.iter());
+        assert_eq!(
+            collateral.cloned().collect::<Vec<_>>(),
+            vec![TransactionInput {
+                transaction_id: Hash::from([1; 32]),
+                index: 0,
+            }]
+        );
+
+        let total = body.total_collateral.unwrap();
+        assert!(total * 100 >= body.fee * 150);
+
+        let returned = match body.collateral_return.as_ref().unwrap() {
+            TransactionOutput::PostAlonzo(x) => x.value.clone(),
+            _ => unreachable!(),
+        };
+        assert_eq!(
+            returned,
+            pallas_primitives::conway::Value::Coin(10_000_000 - total)
+        );
+
+        // the script integrity hash is the last check of the ledger, which uses
+        // the mainnet cost models where the params here have none: every check
+        // of fees, collateral and balance before it must pass
+        assert!(matches!(
+            validate(&built.sign(&key).unwrap(), &utxos),
+            Err(PostAlonzo(E::ScriptIntegrityHash))
+        ));
+    }
+
+    #[test]
+    fn drep_update_pays_for_drep_witness() {
+        let key = SecretKey::from([7; 32]);
+        let drep_key = SecretKey::from([8; 32]);
+        let address = mk_address(&key);
+        let utxos = mk_utxos(&address, &[(10_000_000, 0)]);
+
+        let drep =
+            StakeCredential::AddrKeyhash(Hasher::<224>::hash(drep_key.public_key().as_ref()));
+
+        let built = StagingTransaction::new()
+            .output(Output::new(address.clone(), 3_000_000))
+            .certificate(Certificate::UpdateDRepCert(drep.clone(), None))
+            .change_address(address)
+            .build_conway_balanced(&utxos, &mk_params(), CoinSelection::LargestFirst)
+            .unwrap();
+
+        let mut cert_state = CertState::default();
+        cert_state.vstate.dreps.insert(
+            drep,
+            DRepState {
+                deposit: 500000000,
+                anchor: None,
+                expiry: 20,
+            },
+        );
+
+        let signed = built.sign(&key).unwrap().sign(&drep_key).unwrap();
+
+        validate_with(&signed, &utxos, &mut cert_state).unwrap();
+    }
+
+    #[test]
+    fn staged_collateral_respects_max_inputs() {
+        let key = SecretKey::from([7; 32]);
+        let address = mk_address(&key);
+        let utxos = mk_utxos(
+            &address,
+            &[
+                (10_000_000, 0),
+                (1_000_000, 0),
+                (1_000_000, 0),
+                (1_000_000, 0),
+                (1_000_000, 0),
+            ],
+        );
+
+        let staging = utxos[1..]
+            .iter()
+            .fold(StagingTransaction::new(), |tx, (input, _)| {
+                tx.collateral_input(input.clone())
+            })
+            .input(utxos[0].0.clone())
+            .output(Output::new(address.clone(), 3_000_000))
+            .language_view(ScriptKind::PlutusV3, vec![])
+            .add_spend_redeemer(
+                utxos[0].0.clone(),
+                vec![0x00],
+                Some(ExUnits {
+                    mem: 1000,
+                    steps: 1000,
+                }),
+            )
+            .change_address(address);
+
+        let result = staging.clone().build_conway_balanced(
+            &utxos,
+            &mk_params(),
+            CoinSelection::LargestFirst,
+        );
+
+        assert_eq!(result, Err(TxBuilderError::TooManyCollateralInputs));
+
+        let mut staging = staging;
+        staging.collateral_inputs.as_mut().unwrap().pop();
+
+        let built = staging
+            .build_conway_balanced(&utxos, &mk_params(), CoinSelection::LargestFirst)
+            .unwrap();
+
+        let tx = Tx::decode_fragment(&built.tx_bytes.0).unwrap();
+        assert_eq!(tx.transaction_body.collateral.as_ref().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn insufficient_funds() {
+        let key = SecretKey::from([7; 32]);
+        let address = mk_address(&key);
+        let utxos = mk_utxos(&address, &[(3_000_000, 0)]);
+
+        let result = StagingTransaction::new()
+            .output(Output::new(address.clone(), 8_000_000))
+            .change_address(address)
+            .build_conway_balanced(&utxos, &mk_params(), CoinSelection::LargestFirst);
+
+        assert_eq!(result, Err(TxBuilderError::InsufficientFunds));
+    }
+}
@@ -1,4 +1,7 @@
-use std::ops::Deref;
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::Deref,
+};
 
 use pallas_codec::utils::CborWrap;
 use pallas_crypto::hash::Hash;
@@ -7,17 +10,27 @@ use pallas_primitives::{
         DatumOption, ExUnits as PallasExUnits, NativeScript, NetworkId, NonZeroInt, PlutusData,
         PlutusScript, PostAlonzoTransactionOutput, Redeemer, RedeemerTag,
         ScriptRef as PallasScript, TransactionBody, TransactionInput, TransactionOutput, Tx, Value,
-        WitnessSet,
+        Voter, VotingProcedures, WitnessSet,
     },
     Fragment, NonEmptySet, PositiveCoin,
 };
 use pallas_traverse::ComputeHash;
+#[cfg(feature = "phase2")]
+use pallas_traverse::{Era, MultiEraTx};
+#[cfg(feature = "phase2")]
+use pallas_validate::{
+    phase2::{evaluate_tx, script_context::SlotConfig},
+    utils::{MultiEraProtocolParameters, UtxoMap},
+};
+
+use pallas_validate::utils::ConwayProtParams;
 
 use crate::{
+    balance::{self, CoinSelection},
     transaction::{
         model::{
-            BuilderEra, BuiltTransaction, DatumKind, ExUnits, Output, RedeemerPurpose, ScriptKind,
-            StagingTransaction,
+            BuilderEra, BuiltTransaction, DatumKind, ExUnits, Input, Output, RedeemerPurpose,
+            Redeemers, ScriptKind, StagingTransaction,
         },
         Bytes, Bytes32, TransactionStatus,
     },
@@ -27,6 +40,49 @@ use crate::{
 pub trait BuildConway {
     fn build_conway_raw(self) -> Result<BuiltTransaction, TxBuilderError>;
 
+    /// Build the transaction, estimating the budget of every redeemer that was
+    /// staged without `ExUnits`.
+    ///
+    /// Missing budgets are set to `placeholder` to build a draft, which is
+    /// handed to `evaluate` to measure the units consumed by each redeemer
+    /// pointer. The measured units, which should already include any safety
+    /// margin, replace the placeholders and the transaction is rebuilt so
+    /// that the script data hash commits to the final budgets.
+    fn build_conway_evaluated<F>(
+        self,
+        placeholder: ExUnits,
+        evaluate: F,
+    ) -> Result<BuiltTransaction, TxBuilderError>
+    where
+        F: FnOnce(&BuiltTransaction) -> Result<Vec<(RedeemerTag, u32, ExUnits)>, TxBuilderError>;
+
+    /// Build a balanced transaction, selecting inputs from `utxos` to cover
+    /// the outputs, deposits and fee, and returning the remaining value to
+    /// the change address.
+    ///
+    /// Staged inputs must be present in `utxos`, which are also used to
+    /// resolve reference inputs when accounting for reference script fees.
+    /// Transactions with redeemers also get collateral, taken from the
+    /// staged collateral inputs or else chosen from `utxos`, along with the
+    /// matching return output and total collateral.
+    fn build_conway_balanced(
+        self,
+        utxos: &[(Input, Output)],
+        pparams: &ConwayProtParams,
+        selection: CoinSelection,
+    ) -> Result<BuiltTransaction, TxBuilderError>;
+
+    /// Build the transaction, estimating missing redeemer budgets by running
+    /// its scripts through the phase-2 evaluator of `pallas-validate`, whose
+    /// reported units are already padded by 10%.
+    #[cfg(feature = "phase2")]
+    fn build_conway_phase2(
+        self,
+        utxos: &UtxoMap,
+        pparams: &MultiEraProtocolParameters,
+        slot_config: &SlotConfig,
+    ) -> Result<BuiltTransaction, TxBuilderError>;
+
     // fn build_babbage(staging_tx: StagingTransaction, resolver: (), params: ()) ->
     // Result<BuiltTransaction, TxBuilderError>;
 }
@@ -158,13 +214,51 @@ impl BuildConway for StagingTransaction {
             })
             .collect::<Result<Vec<_>, _>>()?;
 
-        let mut mint_policies = mint
-            .iter()
-            .flat_map(|x: &pallas_primitives::conway::Multiasset<NonZeroInt>| x.iter())
-            .map(|(p, _)| *p)
-            .collect::<Vec<_>>();
+        let certificates = NonEmptySet::from_vec(self.certificates.unwrap_or_default());
+
+        let withdrawals = self
+            .withdrawals
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(account, amount)| (account.into(), amount))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut voting_procedures: VotingProcedures = BTreeMap::new();
 
-        mint_policies.sort_unstable_by_key(|x| *x);
+        for (voter, gov_action_id, procedure) in self.voting_procedures.unwrap_or_default() {
+            voting_procedures
+                .entry(voter)
+                .or_default()
+                .insert(gov_action_id, procedure);
+        }
+
+        let proposal_procedures =
+            NonEmptySet::from_vec(self.proposal_procedures.unwrap_or_default());
+
+        let mut transaction_body = TransactionBody {
+            inputs: pallas_primitives::Set::from(inputs),
+            outputs,
+            ttl: self.invalid_from_slot,
+            validity_interval_start: self.valid_from_slot,
+            fee: self.fee.unwrap_or_default(),
+            certificates,
+            withdrawals: (!withdrawals.is_empty()).then_some(withdrawals),
+            auxiliary_data_hash: None, // TODO (accept user input)
+            mint,
+            script_data_hash: None,
+            collateral,
+            required_signers,
+            network_id,
+            collateral_return,
+            reference_inputs,
+            total_collateral: self.total_collateral,
+            voting_procedures: (!voting_procedures.is_empty()).then_some(voting_procedures),
+            proposal_procedures,
+            treasury_value: None, // TODO
+            donation: None,       // TODO
+        };
+
+        let targets = RedeemerTargets::from_body(&transaction_body);
 
         let mut redeemers = vec![];
 
@@ -176,50 +270,26 @@ impl BuildConway for StagingTransaction {
                         steps: *steps,
                     }
                 } else {
-                    todo!("ExUnits budget calculation not yet implement") // TODO
+                    return Err(TxBuilderError::MissingExUnits);
                 };
 
                 let data = PlutusData::decode_fragment(pd.as_ref())
                     .map_err(|_| TxBuilderError::MalformedDatum)?;
 
-                match purpose {
-                    RedeemerPurpose::Spend(ref txin) => {
-                        let index = inputs
-                            .iter()
-                            .position(|x| {
-                                (*x.transaction_id, x.index) == (txin.tx_hash.0, txin.txo_index)
-                            })
-                            .ok_or(TxBuilderError::RedeemerTargetMissing)?
-                            as u32;
-
-                        redeemers.push(Redeemer {
-                            tag: RedeemerTag::Spend,
-                            index,
-                            data,
-                            ex_units,
-                        })
-                    }
-                    RedeemerPurpose::Mint(pid) => {
-                        let index = mint_policies
-                            .iter()
-                            .position(|x| x.as_slice() == pid.0)
-                            .ok_or(TxBuilderError::RedeemerTargetMissing)?
-                            as u32;
-
-                        redeemers.push(Redeemer {
-                            tag: RedeemerTag::Mint,
-                            index,
-                            data,
-                            ex_units,
-                        })
-                    } // todo!("reward and cert redeemers not yet supported"), // TODO
-                }
+                let (tag, index) = targets.pointer(purpose)?;
+
+                redeemers.push(Redeemer {
+                    tag,
+                    index,
+                    data,
+                    ex_units,
+                })
             }
         };
 
         let witness_set_redeemers = pallas_primitives::conway::Redeemers::List(redeemers.clone());
 
-        let script_data_hash = self.language_view.map(|language_view| {
+        transaction_body.script_data_hash = self.language_view.map(|language_view| {
             let dta = pallas_primitives::conway::ScriptData {
                 redeemers: witness_set_redeemers.clone(),
                 datums: if !plutus_data.is_empty() {
@@ -234,29 +304,7 @@ impl BuildConway for StagingTransaction {
         });
 
         let mut pallas_tx: Tx = Tx {
-            transaction_body: TransactionBody {
-                inputs: pallas_primitives::Set::from(inputs),
-                outputs,
-                ttl: self.invalid_from_slot,
-                validity_interval_start: self.valid_from_slot,
-                fee: self.fee.unwrap_or_default(),
-                certificates: None,        // TODO
-                withdrawals: None,         // TODO
-                auxiliary_data_hash: None, // TODO (accept user input)
-                mint,
-                script_data_hash,
-                collateral,
-                required_signers,
-                network_id,
-                collateral_return,
-                reference_inputs,
-                total_collateral: None,    // TODO
-                voting_procedures: None,   // TODO
-                proposal_procedures: None, // TODO
-                treasury_value: None,      // TODO
-                donation: None,            // TODO
-            }
-            .into(),
+            transaction_body: transaction_body.into(),
             transaction_witness_set: WitnessSet {
                 vkeywitness: None,
                 native_script: NonEmptySet::from_vec(
@@ -297,11 +345,221 @@ impl BuildConway for StagingTransaction {
         })
     }
 
+    fn build_conway_evaluated<F>(
+        self,
+        placeholder: ExUnits,
+        evaluate: F,
+    ) -> Result<BuiltTransaction, TxBuilderError>
+    where
+        F: FnOnce(&BuiltTransaction) -> Result<Vec<(RedeemerTag, u32, ExUnits)>, TxBuilderError>,
+    {
+        let staged = match self.redeemers {
+            Some(ref rdmrs) if rdmrs.values().any(|(_, ex_units)| ex_units.is_none()) => {
+                rdmrs.clone()
+            }
+            _ => return self.build_conway_raw(),
+        };
+
+        let mut draft = self.clone();
+
+        draft.redeemers = Some(Redeemers::from_map(
+            staged
+                .iter()
+                .map(|(purpose, (pd, ex_units))| {
+                    let ex_units = ex_units.clone().unwrap_or_else(|| placeholder.clone());
+                    (purpose.clone(), (pd.clone(), Some(ex_units)))
+                })
+                .collect(),
+        ));
+
+        let draft = draft.build_conway_raw()?;
+        let report = evaluate(&draft)?;
+
+        let draft_tx =
+            Tx::decode_fragment(&draft.tx_bytes.0).map_err(|_| TxBuilderError::CorruptedTxBytes)?;
+
+        let targets = RedeemerTargets::from_body(&draft_tx.transaction_body);
+
+        let mut measured = HashMap::new();
+
+        for (purpose, (pd, ex_units)) in staged.iter() {
+            let ex_units = match ex_units {
+                Some(ex_units) => ex_units.clone(),
+                None => {
+                    let pointer = targets.pointer(purpose)?;
+
+                    let (_, _, units) = report
+                        .iter()
+                        .find(|(tag, index, _)| (*tag, *index) == pointer)
+                        .ok_or(TxBuilderError::MissingExUnits)?;
+
+                    units.clone()
+                }
+            };
+
+            measured.insert(purpose.clone(), (pd.clone(), Some(ex_units)));
+        }
+
+        let mut staging = self;
+        staging.redeemers = Some(Redeemers::from_map(measured));
+
+        staging.build_conway_raw()
+    }
+
+    fn build_conway_balanced(
+        self,
+        utxos: &[(Input, Output)],
+        pparams: &ConwayProtParams,
+        selection: CoinSelection,
+    ) -> Result<BuiltTransaction, TxBuilderError> {
+        balance::build_balanced(self, utxos, pparams, selection, &mut rand::thread_rng())
+    }
+
+    #[cfg(feature = "phase2")]
+    fn build_conway_phase2(
+        self,
+        utxos: &UtxoMap,
+        pparams: &MultiEraProtocolParameters,
+        slot_config: &SlotConfig,
+    ) -> Result<BuiltTransaction, TxBuilderError> {
+        let placeholder = match pparams {
+            MultiEraProtocolParameters::Conway(pp) => ExUnits {
+                mem: pp.max_tx_ex_units.mem,
+                steps: pp.max_tx_ex_units.steps,
+            },
+            _ => return Err(TxBuilderError::UnsupportedEra),
+        };
+
+        self.build_conway_evaluated(placeholder, |draft| {
+            let tx = MultiEraTx::decode_for_era(Era::Conway, &draft.tx_bytes.0)
+                .map_err(|_| TxBuilderError::CorruptedTxBytes)?;
+
+            let report = evaluate_tx(&tx, pparams, utxos, slot_config)
+                .map_err(|e| TxBuilderError::ScriptEvaluation(e.to_string()))?;
+
+            Ok(report
+                .into_iter()
+                .map(|x| {
+                    let units = ExUnits {
+                        mem: x.units.mem,
+                        steps: x.units.steps,
+                    };
+
+                    (x.tag, x.index, units)
+                })
+                .collect())
+        })
+    }
+
     // fn build_babbage(staging_tx: StagingTransaction) -> Result<BuiltTransaction,
     // TxBuilderError> {     todo!()
     // }
 }
 
+/// Items of a transaction body that redeemers can point to, each in the order
+/// the ledger uses to assign redeemer indexes
+struct RedeemerTargets {
+    inputs: Vec<TransactionInput>,
+    mint_policies: Vec<Hash<28>>,
+    certificates: usize,
+    reward_accounts: Vec<pallas_primitives::Bytes>,
+    voters: Vec<Voter>,
+    proposals: usize,
+}
+
+impl RedeemerTargets {
+    fn from_body(body: &TransactionBody) -> Self {
+        let mut mint_policies = body
+            .mint
+            .iter()
+            .flat_map(|x| x.iter())
+            .map(|(p, _)| *p)
+            .collect::<Vec<_>>();
+
+        mint_policies.sort_unstable();
+
+        let mut reward_accounts = body
+            .withdrawals
+            .iter()
+            .flat_map(|x| x.keys())
+            .cloned()
+            .collect::<Vec<_>>();
+
+        // the ledger orders reward accounts by network and then by credential,
+        // with script credentials before key hashes
+        reward_accounts.sort_by_key(|x| {
+            let header = x.first().copied().unwrap_or_default();
+            (header & 0x0f, header & 0x10 == 0, x.to_vec())
+        });
+
+        let mut voters = body
+            .voting_procedures
+            .iter()
+            .flat_map(|x| x.keys())
+            .cloned()
+            .collect::<Vec<_>>();
+
+        // the ledger orders voters by role, with script credentials before key
+        // hashes within each role
+        voters.sort_by_key(|x| match x {
+            Voter::ConstitutionalCommitteeScript(hash) => (0, *hash),
+            Voter::ConstitutionalCommitteeKey(hash) => (1, *hash),
+            Voter::DRepScript(hash) => (2, *hash),
+            Voter::DRepKey(hash) => (3, *hash),
+            Voter::StakePoolKey(hash) => (4, *hash),
+        });
+
+        Self {
+            inputs: body.inputs.iter().cloned().collect(),
+            mint_policies,
+            certificates: body.certificates.as_ref().map_or(0, |x| x.len()),
+            reward_accounts,
+            voters,
+            proposals: body.proposal_procedures.as_ref().map_or(0, |x| x.len()),
+        }
+    }
+
+    /// Resolve the tag and index a redeemer purpose points to
+    fn pointer(&self, purpose: &RedeemerPurpose) -> Result<(RedeemerTag, u32), TxBuilderError> {
+        let (tag, index) = match purpose {
+            RedeemerPurpose::Spend(ref txin) => (
+                RedeemerTag::Spend,
+                self.inputs
+                    .iter()
+                    .position(|x| (*x.transaction_id, x.index) == (txin.tx_hash.0, txin.txo_index)),
+            ),
+            RedeemerPurpose::Mint(pid) => (
+                RedeemerTag::Mint,
+                self.mint_policies
+                    .iter()
+                    .position(|x| x.as_slice() == pid.0),
+            ),
+            RedeemerPurpose::Reward(account) => (
+                RedeemerTag::Reward,
+                self.reward_accounts
+                    .iter()
+                    .position(|x| x.as_slice() == account.as_ref()),
+            ),
+            RedeemerPurpose::Cert(index) => (
+                RedeemerTag::Cert,
+                Some(*index).filter(|x| *x < self.certificates),
+            ),
+            RedeemerPurpose::Vote(voter) => (
+                RedeemerTag::Vote,
+                self.voters.iter().position(|x| x == voter),
+            ),
+            RedeemerPurpose::Propose(index) => (
+                RedeemerTag::Propose,
+                Some(*index).filter(|x| *x < self.proposals),
+            ),
+        };
+
+        let index = index.ok_or(TxBuilderError::RedeemerTargetMissing)?;
+
+        Ok((tag, index as u32))
+    }
+}
+
 impl Output {
     pub fn build_babbage_raw(&self) -> Result<TransactionOutput, TxBuilderError> {
         let assets = self
@@ -383,3 +641,312 @@ impl Output {
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use pallas_addresses::{Address, StakeAddress};
+    use pallas_primitives::conway::{
+        Anchor, Certificate, GovAction, GovActionId, ProposalProcedure,
+        Redeemers as PallasRedeemers, StakeCredential, Vote, VotingProcedure,
+    };
+
+    use crate::transaction::model::Input;
+
+    use super::*;
+
+    fn staging_with_spend_redeemer(ex_units: Option<ExUnits>) -> StagingTransaction {
+        let input = Input::new(Hash::from([1; 32]), 0);
+
+        StagingTransaction::new()
+            .input(Input::new(Hash::from([0; 32]), 0))
+            .input(input.clone())
+            .language_view(ScriptKind::PlutusV3, vec![1, 2, 3])
+            .add_spend_redeemer(input, vec![0x00], ex_units)
+    }
+
+    fn built_redeemers(built: &BuiltTransaction) -> (Vec<Redeemer>, Option<Hash<32>>) {
+        let tx = Tx::decode_fragment(&built.tx_bytes.0).unwrap();
+
+        let redeemers = match tx.transaction_witness_set.redeemer.as_deref() {
+            Some(PallasRedeemers::List(x)) => x.to_vec(),
+            _ => panic!("expected a list of redeemers"),
+        };
+
+        (redeemers, tx.transaction_body.script_data_hash)
+    }
+
+    fn stake_address(bytes: &[u8]) -> StakeAddress {
+        match Address::from_bytes(bytes).unwrap() {
+            Address::Stake(x) => x,
+            _ => panic!("expected a stake address"),
+        }
+    }
+
+    #[test]
+    fn raw_build_requires_ex_units() {
+        assert_eq!(
+            staging_with_spend_redeemer(None).build_conway_raw(),
+            Err(TxBuilderError::MissingExUnits)
+        );
+    }
+
+    #[test]
+    fn evaluated_build_fills_measured_budget() {
+        let placeholder = ExUnits {
+            mem: 14_000_000,
+            steps: 10_000_000_000,
+        };
+
+        let built = staging_with_spend_redeemer(None)
+            .build_conway_evaluated(placeholder, |draft| {
+                let (redeemers, _) = built_redeemers(draft);
+                assert_eq!(redeemers[0].ex_units.mem, 14_000_000);
+
+                Ok(vec![(
+                    RedeemerTag::Spend,
+                    1,
+                    ExUnits {
+                        mem: 1_000,
+                        steps: 2_000,
+                    },
+                )])
+            })
+            .unwrap();
+
+        let (redeemers, script_data_hash) = built_redeemers(&built);
+
+        assert_eq!(
+            (redeemers[0].tag, redeemers[0].index),
+            (RedeemerTag::Spend, 1)
+        );
+        assert_eq!(
+            redeemers[0].ex_units,
+            PallasExUnits {
+                mem: 1_000,
+                steps: 2_000
+            }
+        );
+
+        let expected = staging_with_spend_redeemer(Some(ExUnits {
+            mem: 1_000,
+            steps: 2_000,
+        }))
+        .build_conway_raw()
+        .unwrap();
+
+        assert_eq!(script_data_hash, built_redeemers(&expected).1);
+        assert_eq!(built.tx_hash, expected.tx_hash);
+    }
+
+    #[test]
+    fn redeemers_point_into_conway_body() {
+        let key_account = stake_address(&[[0xe1].as_slice(), &[1; 28]].concat());
+        let script_account = stake_address(&[[0xf1].as_slice(), &[2; 28]].concat());
+        let drep = Voter::DRepScript([3; 28].into());
+        let committee = Voter::ConstitutionalCommitteeScript([4; 28].into());
+
+        let gov_action_id = GovActionId {
+            transaction_id: [5; 32].into(),
+            action_index: 0,
+        };
+
+        let procedure = VotingProcedure {
+            vote: Vote::Yes,
+            anchor: None,
+        };
+
+        let ex_units = Some(ExUnits { mem: 1, steps: 1 });
+
+        let built = StagingTransaction::new()
+            .input(Input::new(Hash::from([0; 32]), 0))
+            .withdrawal(key_account, 0)
+            .withdrawal(script_account.clone(), 0)
+            .certificate(Certificate::StakeRegistration(
+                StakeCredential::AddrKeyhash([6; 28].into()),
+            ))
+            .certificate(Certificate::StakeDeregistration(
+                StakeCredential::ScriptHash([7; 28].into()),
+            ))
+            .vote(drep.clone(), gov_action_id.clone(), procedure.clone())
+            .vote(committee, gov_action_id, procedure)
+            .add_reward_redeemer(script_account, vec![0x00], ex_units.clone())
+            .add_cert_redeemer(1, vec![0x00], ex_units.clone())
+            .add_vote_redeemer(drep, vec![0x00], ex_units.clone())
+            .build_conway_raw()
+            .unwrap();
+
+        let (redeemers, _) = built_redeemers(&built);
+
+        let mut pointers = redeemers
+            .iter()
+            .map(|x| (x.tag, x.index))
+            .collect::<Vec<_>>();
+
+        pointers.sort();
+
+        assert_eq!(
+            pointers,
+            vec![
+                (RedeemerTag::Cert, 1),
+                (RedeemerTag::Reward, 0),
+                (RedeemerTag::Vote, 1)
+            ]
+        );
+
+        let result = StagingTransaction::new()
+            .input(Input::new(Hash::from([0; 32]), 0))
+            .add_propose_redeemer(0, vec![0x00], ex_units)
+            .build_conway_raw();
+
+        assert_eq!(result, Err(TxBuilderError::RedeemerTargetMissing));
+    }
+
+    #[test]
+    fn evaluated_build_reports_unevaluated_redeemers() {
+        let placeholder = ExUnits { mem: 1, steps: 1 };
+
+        let result =
+            staging_with_spend_redeemer(None).build_conway_evaluated(placeholder, |_| Ok(vec![]));
+
+        assert_eq!(result, Err(TxBuilderError::MissingExUnits));
+    }
+
+    #[test]
+    fn removing_certificate_moves_later_redeemers() {
+        let cert =
+            |k| Certificate::StakeDeregistration(StakeCredential::ScriptHash([k; 28].into()));
+        let ex_units = Some(ExUnits { mem: 1, steps: 1 });
+
+        let staging = StagingTransaction::new()
+            .certificate(cert(1))
+            .certificate(cert(2))
+            .certificate(cert(3))
+            .add_cert_redeemer(1, vec![0x01], ex_units.clone())
+            .add_cert_redeemer(2, vec![0x02], ex_units);
+
+        assert_eq!(
+            staging.clone().remove_certificate(3),
+            Err(TxBuilderError::IndexOutOfRange)
+        );
+
+        let staging = staging.remove_certificate(1).unwrap();
+        let redeemers = staging.redeemers.as_deref().unwrap();
+
+        assert_eq!(staging.certificates, Some(vec![cert(1), cert(3)]));
+        assert_eq!(redeemers.len(), 1);
+        assert_eq!(redeemers[&RedeemerPurpose::Cert(1)].0, Bytes(vec![0x02]));
+    }
+
+    #[test]
+    fn removing_proposal_moves_later_redeemers() {
+        let proposal = |k| ProposalProcedure {
+            deposit: 1337,
+            reward_account: [[0xe1].as_slice(), &[k; 28]].concat().into(),
+            gov_action: GovAction::Information,
+            anchor: Anchor {
+                url: "https://example.com".into(),
+                content_hash: [k; 32].into(),
+            },
+        };
+        let ex_units = Some(ExUnits { mem: 1, steps: 1 });
+
+        let staging = StagingTransaction::new()
+            .proposal(proposal(1))
+            .proposal(proposal(2))
+            .add_propose_redeemer(0, vec![0x00], ex_units.clone())
+            .add_propose_redeemer(1, vec![0x01], ex_units);
+
+        assert_eq!(
+            StagingTransaction::new().remove_proposal(0),
+            Err(TxBuilderError::IndexOutOfRange)
+        );
+
+        let staging = staging.remove_proposal(0).unwrap();
+        let redeemers = staging.redeemers.as_deref().unwrap();
+
+        assert_eq!(staging.proposal_procedures, Some(vec![proposal(2)]));
+        assert_eq!(redeemers.len(), 1);
+        assert_eq!(redeemers[&RedeemerPurpose::Propose(0)].0, Bytes(vec![0x01]));
+    }
+
+    /// `(program 1.1.0 (lam ctx (con unit ())))`
+    #[cfg(feature = "phase2")]
+    const ALWAYS_SUCCEEDS: &str = "450101002499";
+
+    /// `(program 1.1.0 (lam ctx (error)))`
+    #[cfg(feature = "phase2")]
+    const ALWAYS_FAILS: &str = "450101002601";
+
+    #[cfg(feature = "phase2")]
+    fn build_script_spend(script: &str) -> Result<BuiltTransaction, TxBuilderError> {
+        use pallas_addresses::{
+            Network, ShelleyAddress, ShelleyDelegationPart, ShelleyPaymentPart,
+        };
+        use pallas_crypto::hash::Hasher;
+        use pallas_validate::utils::{EraCbor, TxoRef};
+
+        let script = hex::decode(script).unwrap();
+        let script_hash = Hasher::<224>::hash_tagged(&script, 3);
+
+        let address: pallas_addresses::Address = ShelleyAddress::new(
+            Network::Testnet,
+            ShelleyPaymentPart::script_hash(script_hash),
+            ShelleyDelegationPart::Null,
+        )
+        .into();
+
+        let input = Input::new(Hash::from([1; 32]), 0);
+        let locked =
+            Output::new(address.clone(), 5_000_000).set_inline_datum(vec![0xd8, 0x79, 0x80]);
+
+        let utxos: UtxoMap = [(
+            TxoRef(input.tx_hash.0.into(), input.txo_index as u32),
+            EraCbor(
+                Era::Conway,
+                locked
+                    .build_babbage_raw()
+                    .unwrap()
+                    .encode_fragment()
+                    .unwrap(),
+            ),
+        )]
+        .into_iter()
+        .collect();
+
+        let pparams = MultiEraProtocolParameters::Conway(crate::balance::tests::mk_params());
+
+        StagingTransaction::new()
+            .input(input.clone())
+            .output(Output::new(address, 4_800_000))
+            .fee(200_000)
+            .script(ScriptKind::PlutusV3, script)
+            .language_view(ScriptKind::PlutusV3, vec![])
+            .add_spend_redeemer(input, vec![0x00], None)
+            .build_conway_phase2(&utxos, &pparams, &SlotConfig::default())
+    }
+
+    #[test]
+    #[cfg(feature = "phase2")]
+    fn phase2_build_measures_passing_script() {
+        let built = build_script_spend(ALWAYS_SUCCEEDS).unwrap();
+        let (redeemers, script_data_hash) = built_redeemers(&built);
+
+        assert_eq!(
+            (redeemers[0].tag, redeemers[0].index),
+            (RedeemerTag::Spend, 0)
+        );
+        assert!(redeemers[0].ex_units.mem > 0);
+        assert!(redeemers[0].ex_units.steps > 0);
+        assert!(redeemers[0].ex_units.mem < 14_000_000);
+        assert!(script_data_hash.is_some());
+    }
+
+    #[test]
+    #[cfg(feature = "phase2")]
+    fn phase2_build_rejects_failing_script() {
+        assert!(matches!(
+            build_script_spend(ALWAYS_FAILS),
+            Err(TxBuilderError::ScriptEvaluation(_))
+        ));
+    }
+}
@@ -1,6 +1,8 @@
+mod balance;
 mod conway;
 mod transaction;
 
+pub use balance::CoinSelection;
 pub use conway::BuildConway;
 pub use transaction::{
     model::{BuiltTransaction, ExUnits, Input, Output, ScriptKind, StagingTransaction},
@@ -34,7 +36,34 @@ pub enum TxBuilderError {
     /// Asset name is too long, it must be 32 bytes or less
     #[error("Asset name must be 32 bytes or less")]
     AssetNameTooLong,
+    /// Certificate or proposal index is past the end of the staged items
+    #[error("Index is out of range of the staged items")]
+    IndexOutOfRange,
     /// Unsupported era
     #[error("Unsupported era")]
     UnsupportedEra,
+    /// A redeemer has no execution budget and none could be estimated
+    #[error("Redeemer has no ExUnits budget")]
+    MissingExUnits,
+    /// Phase-2 evaluation of the transaction's scripts failed
+    #[error("Script evaluation failed: {0}")]
+    ScriptEvaluation(String),
+    /// Balancing requires a change address to be set
+    #[error("No change address to balance the transaction with")]
+    MissingChangeAddress,
+    /// A staged input was not found among the provided UTxOs
+    #[error("Input not found among the provided UTxOs")]
+    UnresolvedInput,
+    /// The provided UTxOs cannot cover the value the transaction spends
+    #[error("Insufficient funds to balance the transaction")]
+    InsufficientFunds,
+    /// No suitable UTxOs are available to cover the required collateral
+    #[error("Insufficient collateral for the transaction")]
+    InsufficientCollateral,
+    /// More collateral inputs are staged than the protocol parameters allow
+    #[error("Too many collateral inputs")]
+    TooManyCollateralInputs,
+    /// An output holds less lovelace than the minimum required for its size
+    #[error("Output lovelace is below the minimum UTxO value")]
+    OutputBelowMinUtxo,
 }
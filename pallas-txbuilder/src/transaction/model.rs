@@ -1,4 +1,4 @@
-use pallas_addresses::Address as PallasAddress;
+use pallas_addresses::{Address as PallasAddress, StakeAddress};
 use pallas_crypto::{
     hash::{Hash, Hasher},
     key::ed25519,
@@ -31,6 +31,7 @@ pub struct StagingTransaction {
     pub network_id: Option<u8>,
     pub collateral_inputs: Option<Vec<Input>>,
     pub collateral_output: Option<Output>,
+    pub total_collateral: Option<u64>,
     pub disclosed_signers: Option<Vec<PubKeyHash>>,
     pub scripts: Option<HashMap<ScriptHash, Script>>,
     pub datums: Option<HashMap<DatumHash, DatumBytes>>,
@@ -39,8 +40,11 @@ pub struct StagingTransaction {
     pub signature_amount_override: Option<u8>,
     pub change_address: Option<Address>,
     pub language_view: Option<pallas_primitives::conway::LanguageView>,
-    // pub certificates: TODO
-    // pub withdrawals: TODO
+    pub certificates: Option<Vec<conway::Certificate>>,
+    pub withdrawals: Option<HashMap<Bytes, u64>>,
+    pub voting_procedures:
+        Option<Vec<(conway::Voter, conway::GovActionId, conway::VotingProcedure)>>,
+    pub proposal_procedures: Option<Vec<conway::ProposalProcedure>>,
     // pub updates: TODO
     // pub auxiliary_data: TODO
     // pub phase_2_valid: TODO
@@ -212,6 +216,16 @@ impl StagingTransaction {
         self
     }
 
+    pub fn total_collateral(mut self, amount: u64) -> Self {
+        self.total_collateral = Some(amount);
+        self
+    }
+
+    pub fn clear_total_collateral(mut self) -> Self {
+        self.total_collateral = None;
+        self
+    }
+
     pub fn disclosed_signer(mut self, pub_key_hash: Hash<28>) -> Self {
         let mut disclosed_signers = self.disclosed_signers.unwrap_or_default();
         disclosed_signers.push(Hash28(*pub_key_hash));
@@ -352,6 +366,219 @@ impl StagingTransaction {
         self
     }
 
+    pub fn add_reward_redeemer(
+        mut self,
+        reward_account: StakeAddress,
+        plutus_data: Vec<u8>,
+        ex_units: Option<ExUnits>,
+    ) -> Self {
+        let mut rdmrs = self.redeemers.map(|x| x.0).unwrap_or_default();
+
+        rdmrs.insert(
+            RedeemerPurpose::Reward(reward_account.to_vec().into()),
+            (plutus_data.into(), ex_units),
+        );
+
+        self.redeemers = Some(Redeemers(rdmrs));
+
+        self
+    }
+
+    pub fn remove_reward_redeemer(mut self, reward_account: StakeAddress) -> Self {
+        let mut rdmrs = self.redeemers.map(|x| x.0).unwrap_or_default();
+
+        rdmrs.remove(&RedeemerPurpose::Reward(reward_account.to_vec().into()));
+
+        self.redeemers = Some(Redeemers(rdmrs));
+
+        self
+    }
+
+    /// Add a redeemer for the certificate at `index` in the staged
+    /// certificates
+    pub fn add_cert_redeemer(
+        mut self,
+        index: usize,
+        plutus_data: Vec<u8>,
+        ex_units: Option<ExUnits>,
+    ) -> Self {
+        let mut rdmrs = self.redeemers.map(|x| x.0).unwrap_or_default();
+
+        rdmrs.insert(RedeemerPurpose::Cert(index), (plutus_data.into(), ex_units));
+
+        self.redeemers = Some(Redeemers(rdmrs));
+
+        self
+    }
+
+    pub fn remove_cert_redeemer(mut self, index: usize) -> Self {
+        let mut rdmrs = self.redeemers.map(|x| x.0).unwrap_or_default();
+
+        rdmrs.remove(&RedeemerPurpose::Cert(index));
+
+        self.redeemers = Some(Redeemers(rdmrs));
+
+        self
+    }
+
+    pub fn add_vote_redeemer(
+        mut self,
+        voter: conway::Voter,
+        plutus_data: Vec<u8>,
+        ex_units: Option<ExUnits>,
+    ) -> Self {
+        let mut rdmrs = self.redeemers.map(|x| x.0).unwrap_or_default();
+
+        rdmrs.insert(RedeemerPurpose::Vote(voter), (plutus_data.into(), ex_units));
+
+        self.redeemers = Some(Redeemers(rdmrs));
+
+        self
+    }
+
+    pub fn remove_vote_redeemer(mut self, voter: conway::Voter) -> Self {
+        let mut rdmrs = self.redeemers.map(|x| x.0).unwrap_or_default();
+
+        rdmrs.remove(&RedeemerPurpose::Vote(voter));
+
+        self.redeemers = Some(Redeemers(rdmrs));
+
+        self
+    }
+
+    /// Add a redeemer for the proposal at `index` in the staged proposal
+    /// procedures
+    pub fn add_propose_redeemer(
+        mut self,
+        index: usize,
+        plutus_data: Vec<u8>,
+        ex_units: Option<ExUnits>,
+    ) -> Self {
+        let mut rdmrs = self.redeemers.map(|x| x.0).unwrap_or_default();
+
+        rdmrs.insert(
+            RedeemerPurpose::Propose(index),
+            (plutus_data.into(), ex_units),
+        );
+
+        self.redeemers = Some(Redeemers(rdmrs));
+
+        self
+    }
+
+    pub fn remove_propose_redeemer(mut self, index: usize) -> Self {
+        let mut rdmrs = self.redeemers.map(|x| x.0).unwrap_or_default();
+
+        rdmrs.remove(&RedeemerPurpose::Propose(index));
+
+        self.redeemers = Some(Redeemers(rdmrs));
+
+        self
+    }
+
+    pub fn certificate(mut self, certificate: conway::Certificate) -> Self {
+        let mut certs = self.certificates.unwrap_or_default();
+        certs.push(certificate);
+        self.certificates = Some(certs);
+        self
+    }
+
+    /// Remove the certificate at `index`, dropping its redeemer and moving
+    /// the redeemers of later certificates along with them
+    pub fn remove_certificate(mut self, index: usize) -> Result<Self, TxBuilderError> {
+        let mut certs = self.certificates.unwrap_or_default();
+
+        if index >= certs.len() {
+            return Err(TxBuilderError::IndexOutOfRange);
+        }
+
+        certs.remove(index);
+        self.certificates = Some(certs);
+
+        self.remap_redeemers(|purpose| match purpose {
+            RedeemerPurpose::Cert(i) if i == index => None,
+            RedeemerPurpose::Cert(i) if i > index => Some(RedeemerPurpose::Cert(i - 1)),
+            x => Some(x),
+        });
+
+        Ok(self)
+    }
+
+    pub fn withdrawal(mut self, reward_account: StakeAddress, amount: u64) -> Self {
+        let mut wdrls = self.withdrawals.unwrap_or_default();
+        wdrls.insert(reward_account.to_vec().into(), amount);
+        self.withdrawals = Some(wdrls);
+        self
+    }
+
+    pub fn remove_withdrawal(mut self, reward_account: StakeAddress) -> Self {
+        let mut wdrls = self.withdrawals.unwrap_or_default();
+        wdrls.remove(&Bytes(reward_account.to_vec()));
+        self.withdrawals = Some(wdrls);
+        self
+    }
+
+    /// Cast a vote on a governance action, replacing any previous vote by the
+    /// same voter on that action
+    pub fn vote(
+        mut self,
+        voter: conway::Voter,
+        gov_action_id: conway::GovActionId,
+        procedure: conway::VotingProcedure,
+    ) -> Self {
+        let mut votes = self.voting_procedures.unwrap_or_default();
+        votes.retain(|(v, a, _)| (v, a) != (&voter, &gov_action_id));
+        votes.push((voter, gov_action_id, procedure));
+        self.voting_procedures = Some(votes);
+        self
+    }
+
+    pub fn remove_vote(mut self, voter: conway::Voter, gov_action_id: conway::GovActionId) -> Self {
+        let mut votes = self.voting_procedures.unwrap_or_default();
+        votes.retain(|(v, a, _)| (v, a) != (&voter, &gov_action_id));
+        self.voting_procedures = Some(votes);
+        self
+    }
+
+    pub fn proposal(mut self, procedure: conway::ProposalProcedure) -> Self {
+        let mut proposals = self.proposal_procedures.unwrap_or_default();
+        proposals.push(procedure);
+        self.proposal_procedures = Some(proposals);
+        self
+    }
+
+    /// Remove the proposal at `index`, dropping its redeemer and moving the
+    /// redeemers of later proposals along with them
+    pub fn remove_proposal(mut self, index: usize) -> Result<Self, TxBuilderError> {
+        let mut proposals = self.proposal_procedures.unwrap_or_default();
+
+        if index >= proposals.len() {
+            return Err(TxBuilderError::IndexOutOfRange);
+        }
+
+        proposals.remove(index);
+        self.proposal_procedures = Some(proposals);
+
+        self.remap_redeemers(|purpose| match purpose {
+            RedeemerPurpose::Propose(i) if i == index => None,
+            RedeemerPurpose::Propose(i) if i > index => Some(RedeemerPurpose::Propose(i - 1)),
+            x => Some(x),
+        });
+
+        Ok(self)
+    }
+
+    fn remap_redeemers(&mut self, f: impl Fn(RedeemerPurpose) -> Option<RedeemerPurpose>) {
+        if let Some(Redeemers(rdmrs)) = self.redeemers.take() {
+            let rdmrs = rdmrs
+                .into_iter()
+                .filter_map(|(purpose, x)| Some((f(purpose)?, x)))
+                .collect();
+
+            self.redeemers = Some(Redeemers(rdmrs));
+        }
+    }
+
     pub fn signature_amount_override(mut self, amount: u8) -> Self {
         self.signature_amount_override = Some(amount);
         self
@@ -550,8 +777,13 @@ pub struct Datum {
 pub enum RedeemerPurpose {
     Spend(Input),
     Mint(PolicyId),
-    // Reward TODO
-    // Cert TODO
+    /// Withdrawal from the reward account with the given bytes
+    Reward(Bytes),
+    /// Certificate at the given position in the staged certificates
+    Cert(usize),
+    Vote(conway::Voter),
+    /// Proposal at the given position in the staged proposal procedures
+    Propose(usize),
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
@@ -2,9 +2,10 @@ use core::fmt;
 use std::{collections::HashMap, ops::Deref, str::FromStr};
 
 use pallas_addresses::Address as PallasAddress;
+use pallas_primitives::{conway::Voter, Fragment};
 use serde::{
     de::{self, Visitor},
-    ser::SerializeMap,
+    ser::{self, SerializeMap},
     Deserialize, Deserializer, Serialize, Serializer,
 };
 
@@ -248,6 +249,13 @@ impl Serialize for RedeemerPurpose {
                 format!("spend:{}#{}", hex::encode(tx_hash.0), txo_index)
             }
             RedeemerPurpose::Mint(hash) => format!("mint:{}", hex::encode(hash.0)),
+            RedeemerPurpose::Reward(account) => format!("reward:{}", hex::encode(&account.0)),
+            RedeemerPurpose::Cert(index) => format!("cert:{}", index),
+            RedeemerPurpose::Vote(voter) => format!(
+                "vote:{}",
+                hex::encode(voter.encode_fragment().map_err(ser::Error::custom)?)
+            ),
+            RedeemerPurpose::Propose(index) => format!("propose:{}", index),
         };
 
         serializer.serialize_str(&str)
@@ -269,7 +277,10 @@ impl Visitor<'_> for RedeemerPurposeVisitor {
     type Value = RedeemerPurpose;
 
     fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        formatter.write_str("'spend:{hex_txid}#{index}' or 'mint:{hex_policyid}'")
+        formatter.write_str(
+            "'spend:{hex_txid}#{index}', 'mint:{hex_policyid}', 'reward:{hex_account}', \
+             'cert:{index}', 'vote:{hex_voter_cbor}' or 'propose:{index}'",
+        )
     }
 
     fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
@@ -308,6 +319,34 @@ impl Visitor<'_> for RedeemerPurposeVisitor {
 
                 Ok(RedeemerPurpose::Mint(hash))
             }
+            "reward" => {
+                let account = hex::decode(item)
+                    .map_err(|_| E::custom("invalid reward redeemer item account hex"))?;
+
+                Ok(RedeemerPurpose::Reward(Bytes(account)))
+            }
+            "cert" => {
+                let index = item
+                    .parse()
+                    .map_err(|_| E::custom("invalid cert redeemer item index"))?;
+
+                Ok(RedeemerPurpose::Cert(index))
+            }
+            "vote" => {
+                let bytes = hex::decode(item)
+                    .map_err(|_| E::custom("invalid vote redeemer item voter hex"))?;
+                let voter = Voter::decode_fragment(&bytes)
+                    .map_err(|_| E::custom("invalid vote redeemer item voter"))?;
+
+                Ok(RedeemerPurpose::Vote(voter))
+            }
+            "propose" => {
+                let index = item
+                    .parse()
+                    .map_err(|_| E::custom("invalid propose redeemer item index"))?;
+
+                Ok(RedeemerPurpose::Propose(index))
+            }
             _ => Err(E::custom("invalid redeemer tag")),
         }
     }
@@ -395,7 +434,14 @@ mod tests {
     use std::str::FromStr;
 
     use pallas_addresses::Address as PallasAddress;
-    use pallas_primitives::{babbage::PlutusData, Fragment, MaybeIndefArray};
+    use pallas_primitives::{
+        babbage::PlutusData,
+        conway::{
+            Anchor, Certificate, GovAction, GovActionId, ProposalProcedure, StakeCredential, Vote,
+            VotingProcedure,
+        },
+        Fragment, MaybeIndefArray,
+    };
 
     use crate::transaction::{model::*, Bytes64, DatumBytes, DatumHash, Hash28, TransactionStatus};
 
@@ -462,6 +508,7 @@ mod tests {
                 }
             ]),
             collateral_output: Some(Output { address: Address(PallasAddress::from_str("addr1g9ekml92qyvzrjmawxkh64r2w5xr6mg9ngfmxh2khsmdrcudevsft64mf887333adamant").unwrap()), lovelace: 1337, assets: None, datum: None, script: None }),
+            total_collateral: Some(1337),
             disclosed_signers: Some(vec![Hash28([0; 28])]),
             scripts: Some(
                 vec![
@@ -475,11 +522,32 @@ mod tests {
             redeemers: Some(Redeemers::from_map(vec![
                 (RedeemerPurpose::Spend(Input { tx_hash: Bytes32([4; 32]), txo_index: 1 }), (Bytes(PlutusData::Array(MaybeIndefArray::Def(vec![])).encode_fragment().unwrap()), Some(ExUnits { mem: 1337, steps: 7331 }))),
                 (RedeemerPurpose::Mint(Hash28([5; 28])), (Bytes(PlutusData::Array(MaybeIndefArray::Def(vec![])).encode_fragment().unwrap()), None)),
+                (RedeemerPurpose::Reward(Bytes([[0xf1].as_slice(), &[6; 28]].concat())), (Bytes(PlutusData::Array(MaybeIndefArray::Def(vec![])).encode_fragment().unwrap()), None)),
+                (RedeemerPurpose::Cert(0), (Bytes(PlutusData::Array(MaybeIndefArray::Def(vec![])).encode_fragment().unwrap()), None)),
+                (RedeemerPurpose::Vote(Voter::DRepScript([7; 28].into())), (Bytes(PlutusData::Array(MaybeIndefArray::Def(vec![])).encode_fragment().unwrap()), None)),
+                (RedeemerPurpose::Propose(0), (Bytes(PlutusData::Array(MaybeIndefArray::Def(vec![])).encode_fragment().unwrap()), None)),
             ].into_iter().collect::<HashMap<_, _>>())),
             signature_amount_override: Some(5),
             change_address: Some(Address(PallasAddress::from_str("addr1g9ekml92qyvzrjmawxkh64r2w5xr6mg9ngfmxh2khsmdrcudevsft64mf887333adamant").unwrap())),
             script_data_hash: Some(Bytes32([0; 32])),
             language_view: Some(pallas_primitives::conway::LanguageView(1, vec![1, 2, 3])),
+            certificates: Some(vec![Certificate::StakeRegistration(StakeCredential::ScriptHash([6; 28].into()))]),
+            withdrawals: Some(vec![(Bytes([[0xf1].as_slice(), &[6; 28]].concat()), 1337)].into_iter().collect::<HashMap<_, _>>()),
+            voting_procedures: Some(vec![
+                (
+                    Voter::DRepScript([7; 28].into()),
+                    GovActionId { transaction_id: [8; 32].into(), action_index: 0 },
+                    VotingProcedure { vote: Vote::Yes, anchor: None },
+                )
+            ]),
+            proposal_procedures: Some(vec![
+                ProposalProcedure {
+                    deposit: 1337,
+                    reward_account: [[0xe1].as_slice(), &[9; 28]].concat().into(),
+                    gov_action: GovAction::Information,
+                    anchor: Anchor { url: "https://example.com".into(), content_hash: [10; 32].into() },
+                }
+            ]),
         };
 
         let serialised_tx = serde_json::to_string(&tx).unwrap();
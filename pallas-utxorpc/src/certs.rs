@@ -4,6 +4,34 @@ use utxorpc_spec::utxorpc::v1alpha::cardano as u5c;
 
 use crate::{LedgerContext, Mapper};
 
+/// Name of the `Certificate` field holding the given variant, as used by the
+/// paths of a field mask
+fn cert_field(x: &u5c::certificate::Certificate) -> &'static str {
+    use u5c::certificate::Certificate::*;
+
+    match x {
+        StakeRegistration(_) => "stake_registration",
+        StakeDeregistration(_) => "stake_deregistration",
+        StakeDelegation(_) => "stake_delegation",
+        PoolRegistration(_) => "pool_registration",
+        PoolRetirement(_) => "pool_retirement",
+        GenesisKeyDelegation(_) => "genesis_key_delegation",
+        MirCert(_) => "mir_cert",
+        RegCert(_) => "reg_cert",
+        UnregCert(_) => "unreg_cert",
+        VoteDelegCert(_) => "vote_deleg_cert",
+        StakeVoteDelegCert(_) => "stake_vote_deleg_cert",
+        StakeRegDelegCert(_) => "stake_reg_deleg_cert",
+        VoteRegDelegCert(_) => "vote_reg_deleg_cert",
+        StakeVoteRegDelegCert(_) => "stake_vote_reg_deleg_cert",
+        AuthCommitteeHotCert(_) => "auth_committee_hot_cert",
+        ResignCommitteeColdCert(_) => "resign_committee_cold_cert",
+        RegDrepCert(_) => "reg_drep_cert",
+        UnregDrepCert(_) => "unreg_drep_cert",
+        UpdateDrepCert(_) => "update_drep_cert",
+    }
+}
+
 impl<C: LedgerContext> Mapper<C> {
     pub fn map_alonzo_compatible_cert(
         &self,
@@ -94,10 +122,11 @@ impl<C: LedgerContext> Mapper<C> {
         };
 
         u5c::Certificate {
-            certificate: inner.into(),
-            redeemer: tx
-                .find_certificate_redeemer(order)
-                .map(|r| self.map_redeemer(&r)),
+            certificate: Some(inner).filter(|x| self.selects(cert_field(x))),
+            redeemer: self.select_or_default("redeemer", || {
+                tx.find_certificate_redeemer(order)
+                    .map(|r| self.map_redeemer(&r))
+            }),
         }
     }
 
@@ -257,10 +286,11 @@ impl<C: LedgerContext> Mapper<C> {
         };
 
         u5c::Certificate {
-            certificate: inner.into(),
-            redeemer: tx
-                .find_certificate_redeemer(order)
-                .map(|r| self.map_redeemer(&r)),
+            certificate: Some(inner).filter(|x| self.selects(cert_field(x))),
+            redeemer: self.select_or_default("redeemer", || {
+                tx.find_certificate_redeemer(order)
+                    .map(|r| self.map_redeemer(&r))
+            }),
         }
     }
 
@@ -14,6 +14,7 @@ use utxorpc_spec::utxorpc::v1alpha::cardano as u5c;
 
 mod certs;
 mod params;
+pub mod reverse;
 
 pub type TxHash = Hash<32>;
 pub type TxoIndex = u32;
@@ -37,24 +38,77 @@ pub trait LedgerContext: Clone {
 #[derive(Default, Clone)]
 pub struct Mapper<C: LedgerContext> {
     ledger: Option<C>,
-    _mask: FieldMask,
+    mask: FieldMask,
 }
 
 impl<C: LedgerContext> Mapper<C> {
     pub fn new(ledger: C) -> Self {
         Self {
             ledger: Some(ledger),
-            _mask: FieldMask { paths: vec![] },
+            mask: FieldMask { paths: vec![] },
         }
     }
 
     /// Creates a clone of this mapper using a custom field mask
+    ///
+    /// Paths of the mask are relative to the message returned by the `map_*`
+    /// method being called (eg: `body.tx.hash` for `map_block`, `hash` for
+    /// `map_tx`). Fields outside of the mask are left with their default
+    /// value, and the UTxOs of the inputs are only resolved if their
+    /// `as_output` field is selected. An empty mask selects every field.
     pub fn masked(&self, mask: FieldMask) -> Self {
         Self {
             ledger: self.ledger.clone(),
-            _mask: mask,
+            mask,
         }
     }
+
+    /// Whether the field at `path` is selected by the mask, either directly,
+    /// through one of its parents or through one of its children
+    fn selects(&self, path: &str) -> bool {
+        self.mask.paths.is_empty()
+            || self.mask.paths.iter().any(|x| {
+                x == path
+                    || x.strip_prefix(path).is_some_and(|x| x.starts_with('.'))
+                    || path
+                        .strip_prefix(x.as_str())
+                        .is_some_and(|x| x.starts_with('.'))
+            })
+    }
+
+    /// Evaluates `f` if the field at `path` is selected by the mask, otherwise
+    /// returns the default value of the field
+    fn select_or_default<T: Default>(&self, path: &str, f: impl FnOnce() -> T) -> T {
+        match self.selects(path) {
+            true => f(),
+            false => T::default(),
+        }
+    }
+
+    /// Creates a clone of this mapper for the message at `path`, with the
+    /// mask made relative to that message
+    fn nested(&self, path: &str) -> Self {
+        let selects_all = self.mask.paths.is_empty()
+            || self.mask.paths.iter().any(|x| {
+                x == path
+                    || path
+                        .strip_prefix(x.as_str())
+                        .is_some_and(|x| x.starts_with('.'))
+            });
+
+        let paths = match selects_all {
+            true => vec![],
+            false => self
+                .mask
+                .paths
+                .iter()
+                .filter_map(|x| x.strip_prefix(path)?.strip_prefix('.'))
+                .map(String::from)
+                .collect(),
+        };
+
+        self.masked(FieldMask { paths })
+    }
 }
 
 impl<C: LedgerContext> Mapper<C> {
@@ -89,16 +143,34 @@ impl<C: LedgerContext> Mapper<C> {
         tx: &trv::MultiEraTx,
     ) -> Option<u5c::TxOutput> {
         let as_txref = (*input.hash(), input.index() as u32);
+        let mapper = self.nested("as_output");
 
         resolved
             .as_ref()
             .and_then(|x| x.get(&as_txref))
             .and_then(|(era, cbor)| {
                 let o = trv::MultiEraOutput::decode(*era, cbor.as_slice()).ok()?;
-                Some(self.map_tx_output(&o, Some(tx)))
+                Some(mapper.map_tx_output(&o, Some(tx)))
             })
     }
 
+    fn map_input_fields(
+        &self,
+        input: &trv::MultiEraInput,
+        resolved: &Option<UtxoMap>,
+        tx: &trv::MultiEraTx,
+        redeemer: impl FnOnce() -> Option<u5c::Redeemer>,
+    ) -> u5c::TxInput {
+        u5c::TxInput {
+            tx_hash: self.select_or_default("tx_hash", || input.hash().to_vec().into()),
+            output_index: self.select_or_default("output_index", || input.index() as u32),
+            as_output: self.select_or_default("as_output", || {
+                self.decode_resolved_utxo(resolved, input, tx)
+            }),
+            redeemer: self.select_or_default("redeemer", redeemer),
+        }
+    }
+
     pub fn map_tx_input(
         &self,
         input: &trv::MultiEraInput,
@@ -107,12 +179,9 @@ impl<C: LedgerContext> Mapper<C> {
         order: u32,
         resolved: &Option<UtxoMap>,
     ) -> u5c::TxInput {
-        u5c::TxInput {
-            tx_hash: input.hash().to_vec().into(),
-            output_index: input.index() as u32,
-            as_output: self.decode_resolved_utxo(resolved, input, tx),
-            redeemer: tx.find_spend_redeemer(order).map(|x| self.map_redeemer(&x)),
-        }
+        self.map_input_fields(input, resolved, tx, || {
+            tx.find_spend_redeemer(order).map(|x| self.map_redeemer(&x))
+        })
     }
 
     pub fn map_tx_reference_input(
@@ -121,12 +190,7 @@ impl<C: LedgerContext> Mapper<C> {
         resolved: &Option<UtxoMap>,
         tx: &trv::MultiEraTx,
     ) -> u5c::TxInput {
-        u5c::TxInput {
-            tx_hash: input.hash().to_vec().into(),
-            output_index: input.index() as u32,
-            as_output: self.decode_resolved_utxo(resolved, input, tx),
-            redeemer: None,
-        }
+        self.map_input_fields(input, resolved, tx, || None)
     }
 
     pub fn map_tx_collateral(
@@ -135,12 +199,7 @@ impl<C: LedgerContext> Mapper<C> {
         resolved: &Option<UtxoMap>,
         tx: &trv::MultiEraTx,
     ) -> u5c::TxInput {
-        u5c::TxInput {
-            tx_hash: input.hash().to_vec().into(),
-            output_index: input.index() as u32,
-            as_output: self.decode_resolved_utxo(resolved, input, tx),
-            redeemer: None,
-        }
+        self.map_input_fields(input, resolved, tx, || None)
     }
 
     pub fn map_tx_datum(
@@ -191,19 +250,24 @@ impl<C: LedgerContext> Mapper<C> {
         tx: Option<&trv::MultiEraTx>,
     ) -> u5c::TxOutput {
         u5c::TxOutput {
-            address: x.address().map(|a| a.to_vec()).unwrap_or_default().into(),
-            coin: x.value().coin(),
+            address: self.select_or_default("address", || {
+                x.address().map(|a| a.to_vec()).unwrap_or_default().into()
+            }),
+            coin: self.select_or_default("coin", || x.value().coin()),
             // TODO: this is wrong, we're crating a new item for each asset even if they share
             // the same policy id. We need to adjust Pallas' interface to make this mapping more
             // ergonomic.
-            assets: x
-                .value()
-                .assets()
-                .iter()
-                .map(|x| self.map_policy_assets(x))
-                .collect(),
-            datum: self.map_tx_datum(x, tx).into(),
-            script: x.script_ref().map(|x| self.map_any_script(&x)),
+            assets: self.select_or_default("assets", || {
+                let mapper = self.nested("assets");
+                x.value()
+                    .assets()
+                    .iter()
+                    .map(|x| mapper.map_policy_assets(x))
+                    .collect()
+            }),
+            datum: self.select_or_default("datum", || self.map_tx_datum(x, tx).into()),
+            script: self
+                .select_or_default("script", || x.script_ref().map(|x| self.map_any_script(&x))),
         }
     }
 
@@ -253,26 +317,31 @@ impl<C: LedgerContext> Mapper<C> {
         order: u32,
     ) -> u5c::Withdrawal {
         u5c::Withdrawal {
-            reward_account: Vec::from(x.0).into(),
-            coin: x.1,
-            redeemer: tx
-                .find_withdrawal_redeemer(order)
-                .map(|x| self.map_redeemer(&x)),
+            reward_account: self.select_or_default("reward_account", || Vec::from(x.0).into()),
+            coin: self.select_or_default("coin", || x.1),
+            redeemer: self.select_or_default("redeemer", || {
+                tx.find_withdrawal_redeemer(order)
+                    .map(|x| self.map_redeemer(&x))
+            }),
         }
     }
 
     pub fn map_asset(&self, x: &trv::MultiEraAsset) -> u5c::Asset {
         u5c::Asset {
-            name: x.name().to_vec().into(),
-            output_coin: x.output_coin().unwrap_or_default(),
-            mint_coin: x.mint_coin().unwrap_or_default(),
+            name: self.select_or_default("name", || x.name().to_vec().into()),
+            output_coin: self
+                .select_or_default("output_coin", || x.output_coin().unwrap_or_default()),
+            mint_coin: self.select_or_default("mint_coin", || x.mint_coin().unwrap_or_default()),
         }
     }
 
     pub fn map_policy_assets(&self, x: &trv::MultiEraPolicyAssets) -> u5c::Multiasset {
         u5c::Multiasset {
-            policy_id: x.policy().to_vec().into(),
-            assets: x.assets().iter().map(|x| self.map_asset(x)).collect(),
+            policy_id: self.select_or_default("policy_id", || x.policy().to_vec().into()),
+            assets: self.select_or_default("assets", || {
+                let mapper = self.nested("assets");
+                x.assets().iter().map(|x| mapper.map_asset(x)).collect()
+            }),
             redeemer: None,
         }
     }
@@ -295,7 +364,7 @@ impl<C: LedgerContext> Mapper<C> {
                 })
             }
             babbage::NativeScript::ScriptAny(x) => {
-                u5c::native_script::NativeScript::ScriptAll(u5c::NativeScriptList {
+                u5c::native_script::NativeScript::ScriptAny(u5c::NativeScriptList {
                     items: x.iter().map(|x| Self::map_native_script(x)).collect(),
                 })
             }
@@ -522,14 +591,18 @@ impl<C: LedgerContext> Mapper<C> {
 
     pub fn map_gov_proposal(&self, x: &trv::MultiEraProposal) -> u5c::GovernanceActionProposal {
         u5c::GovernanceActionProposal {
-            deposit: x.deposit(),
-            reward_account: x.reward_account().to_vec().into(),
-            gov_action: x
-                .as_conway()
-                .map(|x| self.map_conway_gov_action(&x.gov_action)),
-            anchor: Some(u5c::Anchor {
-                url: x.anchor().url.clone(),
-                content_hash: x.anchor().content_hash.to_vec().into(),
+            deposit: self.select_or_default("deposit", || x.deposit()),
+            reward_account: self
+                .select_or_default("reward_account", || x.reward_account().to_vec().into()),
+            gov_action: self.select_or_default("gov_action", || {
+                x.as_conway()
+                    .map(|x| self.map_conway_gov_action(&x.gov_action))
+            }),
+            anchor: self.select_or_default("anchor", || {
+                Some(u5c::Anchor {
+                    url: x.anchor().url.clone(),
+                    content_hash: x.anchor().content_hash.to_vec().into(),
+                })
             }),
         }
     }
@@ -589,20 +662,25 @@ impl<C: LedgerContext> Mapper<C> {
         ns.chain(p1).collect()
     }
 
+    /// Inputs whose UTxO needs to be resolved, only those whose `as_output`
+    /// field is selected by the mask are included.
     fn find_related_inputs(&self, tx: &trv::MultiEraTx) -> Vec<TxoRef> {
         let inputs = tx
             .inputs()
             .into_iter()
+            .filter(|_| self.selects("inputs.as_output"))
             .map(|x| (*x.hash(), x.index() as u32));
 
         let collateral = tx
             .collateral()
             .into_iter()
+            .filter(|_| self.selects("collateral.collateral.as_output"))
             .map(|x| (*x.hash(), x.index() as u32));
 
         let reference_inputs = tx
             .reference_inputs()
             .into_iter()
+            .filter(|_| self.selects("reference_inputs.as_output"))
             .map(|x| (*x.hash(), x.index() as u32));
 
         inputs.chain(collateral).chain(reference_inputs).collect()
@@ -611,116 +689,170 @@ impl<C: LedgerContext> Mapper<C> {
     pub fn map_tx(&self, tx: &trv::MultiEraTx) -> u5c::Tx {
         let resolved = self.ledger.as_ref().and_then(|ctx| {
             let to_resolve = self.find_related_inputs(tx);
-            ctx.get_utxos(to_resolve.as_slice())
+            match to_resolve.is_empty() {
+                true => None,
+                false => ctx.get_utxos(to_resolve.as_slice()),
+            }
         });
 
         u5c::Tx {
-            hash: tx.hash().to_vec().into(),
-            inputs: tx
-                .inputs_sorted_set()
-                .iter()
-                .enumerate()
-                .map(|(order, i)| self.map_tx_input(i, tx, order as u32, &resolved))
-                .collect(),
-            outputs: tx
-                .outputs()
-                .iter()
-                .map(|x| self.map_tx_output(x, Some(tx)))
-                .collect(),
-            certificates: tx
-                .certs()
-                .iter()
-                .enumerate()
-                .filter_map(|(order, x)| self.map_cert(x, tx, order as u32))
-                .collect(),
-            proposals: tx
-                .gov_proposals()
-                .iter()
-                .map(|x| self.map_gov_proposal(x))
-                .collect(),
-            withdrawals: tx
-                .withdrawals_sorted_set()
-                .iter()
-                .enumerate()
-                .map(|(order, x)| self.map_withdrawals(x, tx, order as u32))
-                .collect(),
-            mint: tx
-                .mints_sorted_set()
-                .iter()
-                .enumerate()
-                .map(|(order, x)| {
-                    let mut ma = self.map_policy_assets(x);
+            hash: self.select_or_default("hash", || tx.hash().to_vec().into()),
+            inputs: self.select_or_default("inputs", || {
+                let mapper = self.nested("inputs");
+                tx.inputs_sorted_set()
+                    .iter()
+                    .enumerate()
+                    .map(|(order, i)| mapper.map_tx_input(i, tx, order as u32, &resolved))
+                    .collect()
+            }),
+            outputs: self.select_or_default("outputs", || {
+                let mapper = self.nested("outputs");
+                tx.outputs()
+                    .iter()
+                    .map(|x| mapper.map_tx_output(x, Some(tx)))
+                    .collect()
+            }),
+            certificates: self.select_or_default("certificates", || {
+                let mapper = self.nested("certificates");
+                tx.certs()
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(order, x)| mapper.map_cert(x, tx, order as u32))
+                    .collect()
+            }),
+            proposals: self.select_or_default("proposals", || {
+                let mapper = self.nested("proposals");
+                tx.gov_proposals()
+                    .iter()
+                    .map(|x| mapper.map_gov_proposal(x))
+                    .collect()
+            }),
+            withdrawals: self.select_or_default("withdrawals", || {
+                let mapper = self.nested("withdrawals");
+                tx.withdrawals_sorted_set()
+                    .iter()
+                    .enumerate()
+                    .map(|(order, x)| mapper.map_withdrawals(x, tx, order as u32))
+                    .collect()
+            }),
+            mint: self.select_or_default("mint", || {
+                let mapper = self.nested("mint");
+                tx.mints_sorted_set()
+                    .iter()
+                    .enumerate()
+                    .map(|(order, x)| {
+                        let mut ma = mapper.map_policy_assets(x);
 
-                    ma.redeemer = tx
-                        .find_mint_redeemer(order as u32)
-                        .map(|r| self.map_redeemer(&r));
+                        ma.redeemer = mapper.select_or_default("redeemer", || {
+                            tx.find_mint_redeemer(order as u32)
+                                .map(|r| mapper.map_redeemer(&r))
+                        });
 
-                    ma
-                })
-                .collect(),
-            reference_inputs: tx
-                .reference_inputs()
-                .iter()
-                .map(|x| self.map_tx_reference_input(x, &resolved, tx))
-                .collect(),
-            witnesses: u5c::WitnessSet {
-                vkeywitness: tx
-                    .vkey_witnesses()
+                        ma
+                    })
+                    .collect()
+            }),
+            reference_inputs: self.select_or_default("reference_inputs", || {
+                let mapper = self.nested("reference_inputs");
+                tx.reference_inputs()
+                    .iter()
+                    .map(|x| mapper.map_tx_reference_input(x, &resolved, tx))
+                    .collect()
+            }),
+            witnesses: self.select_or_default("witnesses", || {
+                self.nested("witnesses").map_witnesses(tx).into()
+            }),
+            collateral: self.select_or_default("collateral", || {
+                self.nested("collateral")
+                    .map_collateral(tx, &resolved)
+                    .into()
+            }),
+            fee: self.select_or_default("fee", || tx.fee().unwrap_or_default()),
+            validity: self.select_or_default("validity", || {
+                u5c::TxValidity {
+                    start: self.select_or_default("validity.start", || {
+                        tx.validity_start().unwrap_or_default()
+                    }),
+                    ttl: self.select_or_default("validity.ttl", || tx.ttl().unwrap_or_default()),
+                }
+                .into()
+            }),
+            successful: self.select_or_default("successful", || tx.is_valid()),
+            auxiliary: self.select_or_default("auxiliary", || {
+                u5c::AuxData {
+                    metadata: self.select_or_default("auxiliary.metadata", || {
+                        tx.metadata()
+                            .collect::<Vec<_>>()
+                            .into_iter()
+                            .map(|(l, d)| self.map_metadata(l, d))
+                            .collect()
+                    }),
+                    scripts: self.select_or_default("auxiliary.scripts", || {
+                        self.collect_all_aux_scripts(tx)
+                    }),
+                }
+                .into()
+            }),
+        }
+    }
+
+    fn map_witnesses(&self, tx: &trv::MultiEraTx) -> u5c::WitnessSet {
+        u5c::WitnessSet {
+            vkeywitness: self.select_or_default("vkeywitness", || {
+                tx.vkey_witnesses()
                     .iter()
                     .map(|x| self.map_vkey_witness(x))
-                    .collect(),
-                script: self.collect_all_scripts(tx),
-                plutus_datums: tx
-                    .plutus_data()
+                    .collect()
+            }),
+            script: self.select_or_default("script", || self.collect_all_scripts(tx)),
+            plutus_datums: self.select_or_default("plutus_datums", || {
+                tx.plutus_data()
                     .iter()
                     .map(|x| self.map_plutus_datum(x.deref()))
-                    .collect(),
-            }
-            .into(),
-            collateral: u5c::Collateral {
-                collateral: tx
-                    .collateral()
+                    .collect()
+            }),
+        }
+    }
+
+    fn map_collateral(&self, tx: &trv::MultiEraTx, resolved: &Option<UtxoMap>) -> u5c::Collateral {
+        u5c::Collateral {
+            collateral: self.select_or_default("collateral", || {
+                let mapper = self.nested("collateral");
+                tx.collateral()
                     .iter()
-                    .map(|x| self.map_tx_collateral(x, &resolved, tx))
-                    .collect(),
-                collateral_return: tx
-                    .collateral_return()
-                    .map(|x| self.map_tx_output(&x, Some(tx))),
-                total_collateral: tx.total_collateral().unwrap_or_default(),
-            }
-            .into(),
-            fee: tx.fee().unwrap_or_default(),
-            validity: u5c::TxValidity {
-                start: tx.validity_start().unwrap_or_default(),
-                ttl: tx.ttl().unwrap_or_default(),
-            }
-            .into(),
-            successful: tx.is_valid(),
-            auxiliary: u5c::AuxData {
-                metadata: tx
-                    .metadata()
-                    .collect::<Vec<_>>()
-                    .into_iter()
-                    .map(|(l, d)| self.map_metadata(l, d))
-                    .collect(),
-                scripts: self.collect_all_aux_scripts(tx),
-            }
-            .into(),
+                    .map(|x| mapper.map_tx_collateral(x, resolved, tx))
+                    .collect()
+            }),
+            collateral_return: self.select_or_default("collateral_return", || {
+                let mapper = self.nested("collateral_return");
+                tx.collateral_return()
+                    .map(|x| mapper.map_tx_output(&x, Some(tx)))
+            }),
+            total_collateral: self.select_or_default("total_collateral", || {
+                tx.total_collateral().unwrap_or_default()
+            }),
         }
     }
 
     pub fn map_block(&self, block: &trv::MultiEraBlock) -> u5c::Block {
         u5c::Block {
-            header: u5c::BlockHeader {
-                slot: block.slot(),
-                hash: block.hash().to_vec().into(),
-                height: block.number(),
-            }
-            .into(),
-            body: u5c::BlockBody {
-                tx: block.txs().iter().map(|x| self.map_tx(x)).collect(),
-            }
-            .into(),
+            header: self.select_or_default("header", || {
+                u5c::BlockHeader {
+                    slot: self.select_or_default("header.slot", || block.slot()),
+                    hash: self.select_or_default("header.hash", || block.hash().to_vec().into()),
+                    height: self.select_or_default("header.height", || block.number()),
+                }
+                .into()
+            }),
+            body: self.select_or_default("body", || {
+                u5c::BlockBody {
+                    tx: self.select_or_default("body.tx", || {
+                        let mapper = self.nested("body.tx");
+                        block.txs().iter().map(|x| mapper.map_tx(x)).collect()
+                    }),
+                }
+                .into()
+            }),
         }
     }
 
@@ -731,12 +863,12 @@ impl<C: LedgerContext> Mapper<C> {
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
 
     #[derive(Clone)]
-    struct NoLedger;
+    pub(crate) struct NoLedger;
 
     impl LedgerContext for NoLedger {
         fn get_utxos(&self, _refs: &[TxoRef]) -> Option<UtxoMap> {
@@ -769,4 +901,246 @@ mod tests {
             assert_eq!(expected, current)
         }
     }
+
+    #[derive(Clone, Default)]
+    struct CountingLedger(std::rc::Rc<std::cell::RefCell<Vec<TxoRef>>>);
+
+    impl LedgerContext for CountingLedger {
+        fn get_utxos(&self, refs: &[TxoRef]) -> Option<UtxoMap> {
+            self.0.borrow_mut().extend_from_slice(refs);
+            None
+        }
+    }
+
+    fn mask(paths: &[&str]) -> FieldMask {
+        FieldMask {
+            paths: paths.iter().map(|x| x.to_string()).collect(),
+        }
+    }
+
+    pub(crate) fn test_block() -> Vec<u8> {
+        hex::decode(include_str!("../../test_data/u5c1.block")).unwrap()
+    }
+
+    /// A block with certificates, mints and withdrawals
+    fn test_block_with_certs() -> Vec<u8> {
+        hex::decode(include_str!("../../test_data/alonzo21.block")).unwrap()
+    }
+
+    #[test]
+    fn masked_block_only_maps_selected_fields() {
+        let cbor = test_block();
+        let block = pallas_traverse::MultiEraBlock::decode(&cbor).unwrap();
+
+        let mapper = Mapper::new(NoLedger);
+        let full = mapper.map_block(&block);
+        let masked = mapper.masked(mask(&["body.tx.hash"])).map_block(&block);
+
+        assert_eq!(masked.header, None);
+
+        let full_txs = full.body.unwrap().tx;
+        let masked_txs = masked.body.unwrap().tx;
+        assert!(!full_txs.is_empty());
+        assert_eq!(full_txs.len(), masked_txs.len());
+
+        for (full, masked) in full_txs.into_iter().zip(masked_txs) {
+            let expected = u5c::Tx {
+                hash: full.hash,
+                ..Default::default()
+            };
+
+            assert_eq!(expected, masked);
+        }
+    }
+
+    #[test]
+    fn masked_header_fields() {
+        let cbor = test_block();
+        let block = pallas_traverse::MultiEraBlock::decode(&cbor).unwrap();
+
+        let masked = Mapper::new(NoLedger)
+            .masked(mask(&["header.slot", "header.height"]))
+            .map_block(&block);
+
+        let expected = u5c::BlockHeader {
+            slot: block.slot(),
+            hash: Default::default(),
+            height: block.number(),
+        };
+
+        assert_eq!(masked.header, Some(expected));
+        assert_eq!(masked.body, None);
+    }
+
+    #[test]
+    fn masked_nested_fields() {
+        let cbor = test_block();
+        let block = pallas_traverse::MultiEraBlock::decode(&cbor).unwrap();
+
+        let mapper = Mapper::new(NoLedger);
+        let full = mapper.map_block(&block).body.unwrap().tx;
+        let masked = mapper
+            .masked(mask(&["body.tx.outputs.coin", "body.tx.witnesses"]))
+            .map_block(&block)
+            .body
+            .unwrap()
+            .tx;
+
+        for (full, masked) in full.into_iter().zip(masked) {
+            let expected = u5c::Tx {
+                outputs: full
+                    .outputs
+                    .iter()
+                    .map(|x| u5c::TxOutput {
+                        coin: x.coin,
+                        ..Default::default()
+                    })
+                    .collect(),
+                witnesses: full.witnesses,
+                ..Default::default()
+            };
+
+            assert_eq!(expected, masked);
+        }
+    }
+
+    #[test]
+    fn masked_nested_collections() {
+        let cbor = test_block_with_certs();
+        let block = pallas_traverse::MultiEraBlock::decode(&cbor).unwrap();
+
+        let mapper = Mapper::new(NoLedger);
+        let full = mapper.map_block(&block).body.unwrap().tx;
+        let masked = mapper
+            .masked(mask(&[
+                "body.tx.certificates.redeemer",
+                "body.tx.mint.policy_id",
+                "body.tx.mint.assets.mint_coin",
+                "body.tx.withdrawals.coin",
+                "body.tx.proposals.deposit",
+            ]))
+            .map_block(&block)
+            .body
+            .unwrap()
+            .tx;
+
+        assert!(full.iter().any(|x| !x.certificates.is_empty()));
+        assert!(full.iter().any(|x| !x.mint.is_empty()));
+        assert!(full.iter().any(|x| !x.withdrawals.is_empty()));
+
+        for (full, masked) in full.into_iter().zip(masked) {
+            let expected = u5c::Tx {
+                certificates: full
+                    .certificates
+                    .iter()
+                    .map(|x| u5c::Certificate {
+                        redeemer: x.redeemer.clone(),
+                        ..Default::default()
+                    })
+                    .collect(),
+                mint: full
+                    .mint
+                    .iter()
+                    .map(|x| u5c::Multiasset {
+                        policy_id: x.policy_id.clone(),
+                        assets: x
+                            .assets
+                            .iter()
+                            .map(|x| u5c::Asset {
+                                mint_coin: x.mint_coin,
+                                ..Default::default()
+                            })
+                            .collect(),
+                        ..Default::default()
+                    })
+                    .collect(),
+                withdrawals: full
+                    .withdrawals
+                    .iter()
+                    .map(|x| u5c::Withdrawal {
+                        coin: x.coin,
+                        ..Default::default()
+                    })
+                    .collect(),
+                proposals: full
+                    .proposals
+                    .iter()
+                    .map(|x| u5c::GovernanceActionProposal {
+                        deposit: x.deposit,
+                        ..Default::default()
+                    })
+                    .collect(),
+                ..Default::default()
+            };
+
+            assert_eq!(expected, masked);
+        }
+    }
+
+    #[test]
+    fn masked_certificate_kinds() {
+        let cbor = test_block_with_certs();
+        let block = pallas_traverse::MultiEraBlock::decode(&cbor).unwrap();
+
+        let mapper = Mapper::new(NoLedger);
+        let full = mapper.map_block(&block).body.unwrap().tx;
+        let masked = mapper
+            .masked(mask(&["body.tx.certificates.stake_registration"]))
+            .map_block(&block)
+            .body
+            .unwrap()
+            .tx;
+
+        let full: Vec<_> = full.into_iter().flat_map(|x| x.certificates).collect();
+        let masked: Vec<_> = masked.into_iter().flat_map(|x| x.certificates).collect();
+
+        let is_registration = |x: &u5c::Certificate| {
+            matches!(
+                x.certificate,
+                Some(u5c::certificate::Certificate::StakeRegistration(_))
+            )
+        };
+
+        assert!(full.iter().any(is_registration));
+        assert!(!full.iter().all(is_registration));
+        assert_eq!(full.len(), masked.len());
+
+        for (full, masked) in full.iter().zip(&masked) {
+            let expected = u5c::Certificate {
+                certificate: full.certificate.clone().filter(|_| is_registration(full)),
+                redeemer: None,
+            };
+
+            assert_eq!(expected, *masked);
+        }
+    }
+
+    #[test]
+    fn utxos_resolved_only_when_selected() {
+        let cbor = test_block();
+        let block = pallas_traverse::MultiEraBlock::decode(&cbor).unwrap();
+
+        let ledger = CountingLedger::default();
+        let mapper = Mapper::new(ledger.clone());
+
+        mapper
+            .masked(mask(&["body.tx.hash", "body.tx.inputs.tx_hash"]))
+            .map_block(&block);
+        assert!(ledger.0.borrow().is_empty());
+
+        mapper
+            .masked(mask(&["body.tx.inputs.as_output.address"]))
+            .map_block(&block);
+        let expected: usize = block.txs().iter().map(|x| x.inputs().len()).sum();
+        assert_eq!(ledger.0.borrow().len(), expected);
+
+        ledger.0.borrow_mut().clear();
+        mapper.map_block(&block);
+        let expected: usize = block
+            .txs()
+            .iter()
+            .map(|x| x.inputs().len() + x.collateral().len() + x.reference_inputs().len())
+            .sum();
+        assert_eq!(ledger.0.borrow().len(), expected);
+    }
 }
@@ -0,0 +1,391 @@
+//! Mapping of u5c messages back into Conway primitives
+//!
+//! This is the inverse of the mapping done by [`Mapper`](crate::Mapper), for
+//! the messages we receive from clients (outputs, datums and scripts). Since
+//! u5c messages are built by third parties, every function here is fallible.
+//! The resulting primitives can be encoded as CBOR through `minicbor`.
+
+use pallas_codec::{
+    minicbor,
+    utils::{CborWrap, Int, KeepRaw, KeyValuePairs, MaybeIndefArray, PositiveCoin},
+};
+use pallas_crypto::hash::Hash;
+use pallas_primitives::{conway, BoundedBytes};
+use thiserror::Error;
+use utxorpc_spec::utxorpc::v1alpha::cardano as u5c;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("missing required field `{0}`")]
+    MissingField(&'static str),
+
+    #[error("invalid length for `{0}`")]
+    InvalidLength(&'static str),
+
+    #[error("asset quantity in an output must be greater than zero")]
+    ZeroAssetQuantity,
+
+    #[error("invalid constructor tag {0} for plutus data")]
+    InvalidConstrTag(u32),
+
+    #[error("invalid cbor: {0}")]
+    InvalidCbor(#[from] minicbor::decode::Error),
+}
+
+fn hash<const BYTES: usize>(x: &[u8], field: &'static str) -> Result<Hash<BYTES>, Error> {
+    let bytes: [u8; BYTES] = x.try_into().map_err(|_| Error::InvalidLength(field))?;
+    Ok(Hash::new(bytes))
+}
+
+// Plutus encodes non-empty lists as indefinite arrays, we do the same so that
+// re-encoded data matches the one produced by the node whenever possible.
+fn plutus_list(items: Vec<conway::PlutusData>) -> MaybeIndefArray<conway::PlutusData> {
+    match items.is_empty() {
+        true => MaybeIndefArray::Def(items),
+        false => MaybeIndefArray::Indef(items),
+    }
+}
+
+pub fn plutus_bigint(x: &u5c::BigInt) -> Result<conway::BigInt, Error> {
+    let out = match x.big_int.as_ref().ok_or(Error::MissingField("big_int"))? {
+        u5c::big_int::BigInt::Int(x) => conway::BigInt::Int(Int::from(*x)),
+        u5c::big_int::BigInt::BigUInt(x) => conway::BigInt::BigUInt(BoundedBytes::from(x.to_vec())),
+        u5c::big_int::BigInt::BigNInt(x) => conway::BigInt::BigNInt(BoundedBytes::from(x.to_vec())),
+    };
+
+    Ok(out)
+}
+
+pub fn plutus_data(x: &u5c::PlutusData) -> Result<conway::PlutusData, Error> {
+    let out = match x
+        .plutus_data
+        .as_ref()
+        .ok_or(Error::MissingField("plutus_data"))?
+    {
+        u5c::plutus_data::PlutusData::Constr(x) => {
+            // only the tags with a compact encoding (121-127, 1280-1400) and
+            // the general constructor tag (102) can be encoded as cbor
+            if !matches!(x.tag, 102 | 121..=127 | 1280..=1400) {
+                return Err(Error::InvalidConstrTag(x.tag));
+            }
+
+            conway::PlutusData::Constr(conway::Constr {
+                tag: x.tag as u64,
+                any_constructor: (x.tag == 102).then_some(x.any_constructor),
+                fields: plutus_list(x.fields.iter().map(plutus_data).collect::<Result<_, _>>()?),
+            })
+        }
+        u5c::plutus_data::PlutusData::Map(x) => {
+            let pairs = x
+                .pairs
+                .iter()
+                .map(|x| {
+                    let key = x.key.as_ref().ok_or(Error::MissingField("key"))?;
+                    let value = x.value.as_ref().ok_or(Error::MissingField("value"))?;
+                    Ok((plutus_data(key)?, plutus_data(value)?))
+                })
+                .collect::<Result<_, Error>>()?;
+
+            conway::PlutusData::Map(KeyValuePairs::Def(pairs))
+        }
+        u5c::plutus_data::PlutusData::BigInt(x) => conway::PlutusData::BigInt(plutus_bigint(x)?),
+        u5c::plutus_data::PlutusData::BoundedBytes(x) => {
+            conway::PlutusData::BoundedBytes(BoundedBytes::from(x.to_vec()))
+        }
+        u5c::plutus_data::PlutusData::Array(x) => conway::PlutusData::Array(plutus_list(
+            x.items.iter().map(plutus_data).collect::<Result<_, _>>()?,
+        )),
+    };
+
+    Ok(out)
+}
+
+pub fn native_script(x: &u5c::NativeScript) -> Result<conway::NativeScript, Error> {
+    let scripts = |xs: &[u5c::NativeScript]| -> Result<Vec<_>, Error> {
+        xs.iter().map(native_script).collect()
+    };
+
+    let out = match x
+        .native_script
+        .as_ref()
+        .ok_or(Error::MissingField("native_script"))?
+    {
+        u5c::native_script::NativeScript::ScriptPubkey(x) => {
+            conway::NativeScript::ScriptPubkey(hash(x, "script_pubkey")?)
+        }
+        u5c::native_script::NativeScript::ScriptAll(x) => {
+            conway::NativeScript::ScriptAll(scripts(&x.items)?)
+        }
+        u5c::native_script::NativeScript::ScriptAny(x) => {
+            conway::NativeScript::ScriptAny(scripts(&x.items)?)
+        }
+        u5c::native_script::NativeScript::ScriptNOfK(x) => {
+            conway::NativeScript::ScriptNOfK(x.k, scripts(&x.scripts)?)
+        }
+        u5c::native_script::NativeScript::InvalidBefore(x) => {
+            conway::NativeScript::InvalidBefore(*x)
+        }
+        u5c::native_script::NativeScript::InvalidHereafter(x) => {
+            conway::NativeScript::InvalidHereafter(*x)
+        }
+    };
+
+    Ok(out)
+}
+
+pub fn script(x: &u5c::Script) -> Result<conway::ScriptRef<'static>, Error> {
+    let out = match x.script.as_ref().ok_or(Error::MissingField("script"))? {
+        u5c::script::Script::Native(x) => conway::ScriptRef::NativeScript(native_script(x)?.into()),
+        u5c::script::Script::PlutusV1(x) => {
+            conway::ScriptRef::PlutusV1Script(conway::PlutusScript(x.to_vec().into()))
+        }
+        u5c::script::Script::PlutusV2(x) => {
+            conway::ScriptRef::PlutusV2Script(conway::PlutusScript(x.to_vec().into()))
+        }
+        u5c::script::Script::PlutusV3(x) => {
+            conway::ScriptRef::PlutusV3Script(conway::PlutusScript(x.to_vec().into()))
+        }
+    };
+
+    Ok(out)
+}
+
+/// Maps a datum into its datum option, if any. The datum is inlined when the
+/// original cbor is present (which is kept as-is) or when only the payload is
+/// given. Otherwise, the datum is referenced by its hash.
+pub fn datum(x: &u5c::Datum) -> Result<Option<conway::DatumOption<'static>>, Error> {
+    if !x.original_cbor.is_empty() {
+        let data: KeepRaw<conway::PlutusData> = minicbor::decode(&x.original_cbor)?;
+        return Ok(Some(conway::DatumOption::Data(CborWrap(data.to_owned()))));
+    }
+
+    if !x.hash.is_empty() {
+        return Ok(Some(conway::DatumOption::Hash(hash(&x.hash, "hash")?)));
+    }
+
+    match &x.payload {
+        Some(payload) => {
+            let data = KeepRaw::from(plutus_data(payload)?);
+            Ok(Some(conway::DatumOption::Data(CborWrap(data))))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Maps the coin and assets of an output into a value. Assets of the same
+/// policy may be spread across several entries, policies without assets are
+/// skipped since the ledger rejects empty asset maps.
+pub fn value(coin: u64, assets: &[u5c::Multiasset]) -> Result<conway::Value, Error> {
+    let mut multiasset = conway::Multiasset::<PositiveCoin>::new();
+
+    for policy in assets {
+        let policy_id = hash(&policy.policy_id, "policy_id")?;
+
+        for asset in policy.assets.iter() {
+            let quantity =
+                PositiveCoin::try_from(asset.output_coin).map_err(|_| Error::ZeroAssetQuantity)?;
+
+            multiasset
+                .entry(policy_id)
+                .or_default()
+                .insert(asset.name.to_vec().into(), quantity);
+        }
+    }
+
+    match multiasset.is_empty() {
+        true => Ok(conway::Value::Coin(coin)),
+        false => Ok(conway::Value::Multiasset(coin, multiasset)),
+    }
+}
+
+/// Maps an output into a post-alonzo output. The era of the original output
+/// is not part of the message, so legacy outputs are not reconstructed.
+pub fn tx_output(x: &u5c::TxOutput) -> Result<conway::TransactionOutput<'static>, Error> {
+    if x.address.is_empty() {
+        return Err(Error::MissingField("address"));
+    }
+
+    let datum_option = match &x.datum {
+        Some(x) => datum(x)?.map(KeepRaw::from),
+        None => None,
+    };
+
+    let output = conway::PostAlonzoTransactionOutput {
+        address: x.address.to_vec().into(),
+        value: value(x.coin, &x.assets)?,
+        datum_option,
+        script_ref: x.script.as_ref().map(script).transpose()?.map(CborWrap),
+    };
+
+    Ok(conway::TransactionOutput::PostAlonzo(output.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{test_block, NoLedger};
+    use crate::Mapper;
+    use pallas_traverse as trv;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn tx_output_roundtrip() {
+        let cbor = test_block();
+        let block = trv::MultiEraBlock::decode(&cbor).unwrap();
+        let mapper = Mapper::new(NoLedger);
+
+        for tx in block.txs() {
+            for output in tx.outputs() {
+                let expected = mapper.map_tx_output(&output, Some(&tx));
+                let reversed = tx_output(&expected).unwrap();
+
+                let encoded = minicbor::to_vec(&reversed).unwrap();
+                let decoded = trv::MultiEraOutput::decode(trv::Era::Conway, &encoded).unwrap();
+
+                assert_eq!(expected, mapper.map_tx_output(&decoded, Some(&tx)));
+            }
+        }
+    }
+
+    #[test]
+    fn plutus_data_roundtrip() {
+        let cbor = test_block();
+        let block = trv::MultiEraBlock::decode(&cbor).unwrap();
+        let mapper = Mapper::new(NoLedger);
+
+        let txs = block.txs();
+        let datums: Vec<_> = txs.iter().flat_map(|x| x.plutus_data()).collect();
+        assert!(!datums.is_empty());
+
+        for datum in datums {
+            let expected = mapper.map_plutus_datum(datum);
+            let reversed = plutus_data(&expected).unwrap();
+
+            assert_eq!(expected, mapper.map_plutus_datum(&reversed));
+        }
+    }
+
+    #[test]
+    fn native_script_roundtrip() {
+        let script = conway::NativeScript::ScriptAll(vec![
+            conway::NativeScript::ScriptPubkey(Hash::new([7; 28])),
+            conway::NativeScript::ScriptAny(vec![
+                conway::NativeScript::InvalidBefore(100),
+                conway::NativeScript::InvalidHereafter(200),
+            ]),
+            conway::NativeScript::ScriptNOfK(1, vec![]),
+        ]);
+
+        let mapped = Mapper::<NoLedger>::map_native_script(&script);
+
+        assert_eq!(script, native_script(&mapped).unwrap());
+    }
+
+    #[test]
+    fn inline_datum_keeps_original_cbor() {
+        let original = hex::decode("d8799f4100ff").unwrap();
+        let data: conway::PlutusData = minicbor::decode(&original).unwrap();
+
+        let datum = u5c::Datum {
+            hash: vec![].into(),
+            payload: Mapper::new(NoLedger).map_plutus_datum(&data).into(),
+            original_cbor: original.clone().into(),
+        };
+
+        match super::datum(&datum).unwrap() {
+            Some(conway::DatumOption::Data(x)) => assert_eq!(x.0.raw_cbor(), original),
+            _ => panic!("expected inline datum"),
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_messages() {
+        let output = u5c::TxOutput {
+            address: vec![1; 29].into(),
+            coin: 1_000_000,
+            assets: vec![u5c::Multiasset {
+                policy_id: vec![1; 27].into(),
+                assets: vec![],
+                redeemer: None,
+            }],
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            tx_output(&output),
+            Err(Error::InvalidLength("policy_id"))
+        ));
+
+        let output = u5c::TxOutput {
+            assets: vec![u5c::Multiasset {
+                policy_id: vec![1; 28].into(),
+                assets: vec![u5c::Asset {
+                    name: vec![].into(),
+                    output_coin: 0,
+                    mint_coin: 0,
+                }],
+                redeemer: None,
+            }],
+            ..output
+        };
+
+        assert!(matches!(tx_output(&output), Err(Error::ZeroAssetQuantity)));
+
+        assert!(matches!(
+            plutus_data(&u5c::PlutusData::default()),
+            Err(Error::MissingField("plutus_data"))
+        ));
+
+        assert!(matches!(
+            tx_output(&u5c::TxOutput::default()),
+            Err(Error::MissingField("address"))
+        ));
+
+        for tag in [0, 101, 120, 128, 1279, 1401] {
+            let data = u5c::PlutusData {
+                plutus_data: u5c::plutus_data::PlutusData::Constr(u5c::Constr {
+                    tag,
+                    ..Default::default()
+                })
+                .into(),
+            };
+
+            assert!(matches!(
+                plutus_data(&data),
+                Err(Error::InvalidConstrTag(x)) if x == tag
+            ));
+        }
+    }
+
+    #[test]
+    fn value_skips_empty_policies() {
+        let empty = u5c::Multiasset {
+            policy_id: vec![1; 28].into(),
+            assets: vec![],
+            redeemer: None,
+        };
+
+        assert_eq!(
+            value(1_000_000, std::slice::from_ref(&empty)).unwrap(),
+            conway::Value::Coin(1_000_000)
+        );
+
+        let other = u5c::Multiasset {
+            policy_id: vec![2; 28].into(),
+            assets: vec![u5c::Asset {
+                name: vec![3].into(),
+                output_coin: 5,
+                mint_coin: 0,
+            }],
+            redeemer: None,
+        };
+
+        match value(1_000_000, &[empty, other]).unwrap() {
+            conway::Value::Multiasset(1_000_000, x) => {
+                assert_eq!(x.len(), 1);
+                assert!(x.contains_key(&Hash::new([2; 28])));
+            }
+            x => panic!("unexpected value {x:?}"),
+        }
+    }
+}
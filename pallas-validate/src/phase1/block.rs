@@ -0,0 +1,196 @@
+//! Block-level checks that do not depend on the contents of the UTxO set.
+
+use pallas_codec::{minicbor, utils::KeepRaw};
+use pallas_crypto::hash::{Hash, Hasher};
+use pallas_primitives::alonzo::{ExUnits, TransactionIndex};
+use pallas_traverse::{Era, MultiEraBlock, MultiEraHeader, MultiEraTx};
+
+use crate::utils::{
+    BlockError::*,
+    MultiEraProtocolParameters,
+    ValidationError::{self, Block},
+    ValidationResult,
+};
+
+/// Block body rule: BBODY, together with the header checks that depend on the
+/// protocol parameters
+pub fn validate_block_body(
+    block: &MultiEraBlock,
+    prot_pps: &MultiEraProtocolParameters,
+) -> ValidationResult {
+    match (prot_pps, block) {
+        (MultiEraProtocolParameters::Byron(bpp), MultiEraBlock::Byron(_))
+        | (MultiEraProtocolParameters::Byron(bpp), MultiEraBlock::EpochBoundary(_)) => {
+            if block.size() as u64 > bpp.max_block_size {
+                return Err(Block(MaxBlockSizeExceeded));
+            }
+            Ok(())
+        }
+        (MultiEraProtocolParameters::Byron(_), _)
+        | (_, MultiEraBlock::Byron(_))
+        | (_, MultiEraBlock::EpochBoundary(_)) => Err(Block(BlockAndProtParamsDiffer)),
+        _ => {
+            check_header_size(block, prot_pps)?;
+            check_header_prot_version(block, prot_pps)?;
+            check_body(block, prot_pps)?;
+            check_block_ex_units(&block.txs(), prot_pps)
+        }
+    }
+}
+
+/// Size limits and protocol version for blocks of the Shelley family of eras,
+/// along with the block ExUnits limit from Alonzo onwards
+fn shelley_limits(prot_pps: &MultiEraProtocolParameters) -> (u32, u32, u64, Option<&ExUnits>) {
+    match prot_pps {
+        MultiEraProtocolParameters::Shelley(spp) => (
+            spp.max_block_header_size,
+            spp.max_block_body_size,
+            spp.protocol_version.0,
+            None,
+        ),
+        MultiEraProtocolParameters::Alonzo(app) => (
+            app.max_block_header_size,
+            app.max_block_body_size,
+            app.protocol_version.0,
+            Some(&app.max_block_ex_units),
+        ),
+        MultiEraProtocolParameters::Babbage(bpp) => (
+            bpp.max_block_header_size,
+            bpp.max_block_body_size,
+            bpp.protocol_version.0,
+            Some(&bpp.max_block_ex_units),
+        ),
+        MultiEraProtocolParameters::Conway(cpp) => (
+            cpp.max_block_header_size,
+            cpp.max_block_body_size,
+            cpp.protocol_version.0,
+            Some(&cpp.max_block_ex_units),
+        ),
+        MultiEraProtocolParameters::Byron(_) => unreachable!("byron has no shelley limits"),
+    }
+}
+
+fn check_header_size(
+    block: &MultiEraBlock,
+    prot_pps: &MultiEraProtocolParameters,
+) -> ValidationResult {
+    let (max_header_size, ..) = shelley_limits(prot_pps);
+    if block.header().cbor().len() > max_header_size as usize {
+        return Err(Block(MaxBlockHeaderSizeExceeded));
+    }
+    Ok(())
+}
+
+// A header may announce at most the next major protocol version.
+fn check_header_prot_version(
+    block: &MultiEraBlock,
+    prot_pps: &MultiEraProtocolParameters,
+) -> ValidationResult {
+    let (_, _, pps_major, _) = shelley_limits(prot_pps);
+    let header_major: u64 = match block.header() {
+        MultiEraHeader::ShelleyCompatible(x) => x.header_body.protocol_major,
+        MultiEraHeader::BabbageCompatible(x) => x.header_body.protocol_version.0,
+        _ => return Err(Block(BlockAndProtParamsDiffer)),
+    };
+    if header_major > pps_major + 1 {
+        return Err(Block(HeaderProtVerTooHigh));
+    }
+    Ok(())
+}
+
+fn check_body(block: &MultiEraBlock, prot_pps: &MultiEraProtocolParameters) -> ValidationResult {
+    let (_, max_body_size, ..) = shelley_limits(prot_pps);
+    let (declared_size, declared_hash): (u64, Hash<32>) = match block.header() {
+        MultiEraHeader::ShelleyCompatible(x) => {
+            (x.header_body.block_body_size, x.header_body.block_body_hash)
+        }
+        MultiEraHeader::BabbageCompatible(x) => {
+            (x.header_body.block_body_size, x.header_body.block_body_hash)
+        }
+        _ => return Err(Block(BlockAndProtParamsDiffer)),
+    };
+    let segments: Vec<Vec<u8>> = body_segments(block)?;
+    let size: u64 = segments.iter().map(|x| x.len() as u64).sum();
+    if size > max_body_size as u64 {
+        return Err(Block(MaxBlockBodySizeExceeded));
+    }
+    if size != declared_size {
+        return Err(Block(WrongBlockBodySize));
+    }
+    if compute_block_body_hash(&segments) != declared_hash {
+        return Err(Block(WrongBlockBodyHash));
+    }
+    Ok(())
+}
+
+fn check_block_ex_units(
+    txs: &[MultiEraTx],
+    prot_pps: &MultiEraProtocolParameters,
+) -> ValidationResult {
+    let (.., max_ex_units) = shelley_limits(prot_pps);
+    if let Some(max_ex_units) = max_ex_units {
+        let (mut mem, mut steps): (u64, u64) = (0, 0);
+        for redeemer in txs.iter().flat_map(|tx| tx.redeemers()) {
+            let ex_units: ExUnits = redeemer.ex_units();
+            mem += ex_units.mem;
+            steps += ex_units.steps;
+        }
+        if mem > max_ex_units.mem || steps > max_ex_units.steps {
+            return Err(Block(BlockExUnitsExceeded));
+        }
+    }
+    Ok(())
+}
+
+/// The serialised sections of a Shelley-family block body, in the order in
+/// which they are hashed: tx bodies, witness sets, auxiliary data and, from
+/// Alonzo onwards, the indices of the invalid txs. Sections decoded from a
+/// block keep their original bytes, which are hashed as they are.
+fn body_segments(block: &MultiEraBlock) -> Result<Vec<Vec<u8>>, ValidationError> {
+    fn segment<T: minicbor::Encode<()>>(x: &KeepRaw<T>) -> Result<Vec<u8>, ValidationError> {
+        minicbor::to_vec(x).map_err(|_| Block(UnencodableBlockBody))
+    }
+
+    // blocks that omit the list of invalid txs hash it as an empty one
+    fn invalid_txs(x: &Option<KeepRaw<Vec<TransactionIndex>>>) -> Result<Vec<u8>, ValidationError> {
+        match x {
+            Some(x) => segment(x),
+            None => segment(&KeepRaw::from(Vec::<TransactionIndex>::new())),
+        }
+    }
+
+    match block {
+        MultiEraBlock::AlonzoCompatible(x, era) => {
+            let mut segments = vec![
+                segment(&x.transaction_bodies)?,
+                segment(&x.transaction_witness_sets)?,
+                segment(&x.auxiliary_data_set)?,
+            ];
+            if *era == Era::Alonzo {
+                segments.push(invalid_txs(&x.invalid_transactions)?);
+            }
+            Ok(segments)
+        }
+        MultiEraBlock::Babbage(x) => Ok(vec![
+            segment(&x.transaction_bodies)?,
+            segment(&x.transaction_witness_sets)?,
+            segment(&x.auxiliary_data_set)?,
+            invalid_txs(&x.invalid_transactions)?,
+        ]),
+        MultiEraBlock::Conway(x) => Ok(vec![
+            segment(&x.transaction_bodies)?,
+            segment(&x.transaction_witness_sets)?,
+            segment(&x.auxiliary_data_set)?,
+            invalid_txs(&x.invalid_transactions)?,
+        ]),
+        _ => Ok(vec![]),
+    }
+}
+
+fn compute_block_body_hash(segments: &[Vec<u8>]) -> Hash<32> {
+    let mut hasher = Hasher::<256>::new();
+    for segment in segments {
+        hasher.input(Hasher::<256>::hash(segment).as_ref());
+    }
+    hasher.finalize()
+}
@@ -6,13 +6,14 @@ use crate::utils::{
     conway_add_values, conway_get_val_size_in_words, conway_lovelace_diff_or_fail,
     conway_values_are_equal, get_conway_tx_size, get_lovelace_from_conway_val, get_payment_part,
     get_shelley_address, is_byron_address, mk_alonzo_vk_wits_check_list, verify_signature,
-    ConwayProtParams,
+    CertState, CommitteeAuthorization, ConwayProtParams, DRepState, DState, GovActionState,
+    GovState, PState, PoolParam,
     PostAlonzoError::*,
     UTxOs,
     ValidationError::{self, *},
     ValidationResult,
 };
-use pallas_addresses::{ScriptHash, ShelleyAddress, ShelleyPaymentPart};
+use pallas_addresses::{Address, ScriptHash, ShelleyAddress, ShelleyPaymentPart, StakePayload};
 use pallas_codec::{
     minicbor::{encode, Encoder},
     utils::{Bytes, KeepRaw},
@@ -20,17 +21,27 @@ use pallas_codec::{
 use pallas_primitives::{
     babbage,
     conway::{
-        DatumOption, Language, Mint, NativeScript, Redeemers, RedeemersKey, RequiredSigners,
-        ScriptRef, TransactionBody, TransactionOutput, Tx, VKeyWitness, Value, WitnessSet,
+        Certificate::{self, *},
+        CommitteeColdCredential, DRep, DRepCredential, DatumOption, GovAction, GovActionId,
+        Language, Mint, NativeScript, ProposalProcedure, ProtocolParamUpdate, Redeemers,
+        RedeemersKey, RequiredSigners, ScriptRef, TransactionBody, TransactionOutput, Tx,
+        VKeyWitness, Value, Voter, VotingProcedures, WitnessSet,
     },
-    AddrKeyhash, Hash, PlutusData, PlutusScript, PolicyId, PositiveCoin, TransactionInput,
+    AddrKeyhash, Coin, Epoch, Hash, PlutusData, PlutusScript, PolicyId, PoolKeyhash, PositiveCoin,
+    RewardAccount, StakeCredential, TransactionInput,
+};
+use pallas_traverse::{wellknown::GenesisValues, MultiEraInput, MultiEraOutput, OriginalHash};
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::Deref,
 };
-use pallas_traverse::{MultiEraInput, MultiEraOutput, OriginalHash};
-use std::ops::Deref;
 
+#[allow(clippy::too_many_arguments)]
 pub fn validate_conway_tx(
     mtx: &Tx,
     utxos: &UTxOs,
+    cert_state: &mut CertState,
+    gov_state: &mut GovState,
     prot_pps: &ConwayProtParams,
     block_slot: &u64,
     network_magic: &u32,
@@ -38,11 +49,34 @@ pub fn validate_conway_tx(
 ) -> ValidationResult {
     let tx_body: &TransactionBody = &mtx.transaction_body.clone();
     let size: u32 = get_conway_tx_size(mtx).ok_or(PostAlonzo(UnknownTxSize))?;
+    let cepoch: Epoch = current_epoch(block_slot, network_magic);
     check_ins_not_empty(tx_body)?;
     check_all_ins_in_utxos(tx_body, utxos)?;
     check_tx_validity_interval(tx_body, block_slot)?;
     check_fee(tx_body, &size, mtx, utxos, prot_pps)?;
-    check_preservation_of_value(tx_body, utxos)?;
+    let deposits: &mut Coin = &mut 0; // total of deposits paid by certificates and proposals
+    let refunds: &mut Coin = &mut 0; // total of deposits refunded by certificates
+    check_withdrawals(tx_body, &mut cert_state.dstate)?;
+    check_certificates(
+        &tx_body.certificates,
+        cert_state,
+        gov_state,
+        deposits,
+        refunds,
+        prot_pps,
+        &cepoch,
+    )?;
+    check_governance(
+        tx_body,
+        &mtx.transaction_body.original_hash(),
+        cert_state,
+        gov_state,
+        deposits,
+        prot_pps,
+        &cepoch,
+        network_id,
+    )?;
+    check_preservation_of_value(tx_body, utxos, deposits, refunds)?;
     check_min_lovelace(tx_body, prot_pps)?;
     check_output_val_size(tx_body, prot_pps)?;
     check_network_id(tx_body, network_id)?;
@@ -359,13 +393,29 @@ fn val_from_multi_era_output(multi_era_output: &MultiEraOutput) -> Value {
     }
 }
 
-// The preservation of value property holds.
-fn check_preservation_of_value(tx_body: &TransactionBody, utxos: &UTxOs) -> ValidationResult {
+// The preservation of value property holds, taking into account the deposits
+// paid and refunded by certificates, withdrawals and treasury donations.
+fn check_preservation_of_value(
+    tx_body: &TransactionBody,
+    utxos: &UTxOs,
+    deposits: &Coin,
+    refunds: &Coin,
+) -> ValidationResult {
     let mut input: Value = get_consumed(tx_body, utxos)?;
+    let withdrawals: Coin = match &tx_body.withdrawals {
+        Some(withdrawals) => withdrawals.values().sum(),
+        None => 0,
+    };
+    input = conway_add_values(
+        &input,
+        &Value::Coin(withdrawals + *refunds),
+        &PostAlonzo(NegativeValue),
+    )?;
     let produced: Value = get_produced(tx_body)?;
+    let donation: Coin = tx_body.donation.map(u64::from).unwrap_or_default();
     let output: Value = conway_add_values(
         &produced,
-        &Value::Coin(tx_body.fee),
+        &Value::Coin(tx_body.fee + *deposits + donation),
         &PostAlonzo(NegativeValue),
     )?;
     if let Some(m) = &tx_body.mint {
@@ -459,6 +509,580 @@ fn get_produced(tx_body: &TransactionBody) -> Result<Value, ValidationError> {
     Ok(res)
 }
 
+// Each withdrawal drains the whole reward balance of a registered stake
+// credential. Withdrawals are applied before the certificates, so that a
+// transaction can withdraw the rewards of a credential and deregister it.
+fn check_withdrawals(tx_body: &TransactionBody, ds: &mut DState) -> ValidationResult {
+    if let Some(withdrawals) = &tx_body.withdrawals {
+        for (reward_account, amount) in withdrawals.iter() {
+            let stc: StakeCredential = reward_account_credential(reward_account)?;
+            match ds.rewards.get_mut(&stc) {
+                Some(balance) if *balance == *amount => *balance = 0,
+                _ => return Err(PostAlonzo(WithdrawalsNotInRewards)),
+            }
+        }
+    }
+    Ok(())
+}
+
+// Checks all certificates in order against the certificate state, updating it
+// and accumulating the deposits paid and refunded by the transaction.
+fn check_certificates(
+    cert_opt: &Option<pallas_primitives::NonEmptySet<Certificate>>,
+    cert_state: &mut CertState,
+    gov_state: &GovState,
+    deposits: &mut Coin,
+    refunds: &mut Coin,
+    prot_pps: &ConwayProtParams,
+    cepoch: &Epoch,
+) -> ValidationResult {
+    if let Some(certs) = cert_opt {
+        for cert in certs.iter() {
+            check_certificate(
+                cert, cert_state, gov_state, deposits, refunds, prot_pps, cepoch,
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn current_epoch(block_slot: &u64, network_magic: &u32) -> Epoch {
+    let genesis: GenesisValues =
+        GenesisValues::from_magic(*network_magic as u64).unwrap_or_else(GenesisValues::mainnet);
+    genesis.absolute_slot_to_relative(*block_slot).0
+}
+
+fn check_certificate(
+    cert: &Certificate,
+    cert_state: &mut CertState,
+    gov_state: &GovState,
+    deposits: &mut Coin,
+    refunds: &mut Coin,
+    prot_pps: &ConwayProtParams,
+    cepoch: &Epoch,
+) -> ValidationResult {
+    let CertState {
+        pstate,
+        dstate,
+        vstate,
+        ..
+    } = cert_state;
+    match cert {
+        // DELEG rule
+        StakeRegistration(stc) => {
+            check_stake_registration(stc, &prot_pps.key_deposit, prot_pps, dstate)?;
+            *deposits += prot_pps.key_deposit;
+        }
+        Reg(stc, deposit) => {
+            check_stake_registration(stc, deposit, prot_pps, dstate)?;
+            *deposits += *deposit;
+        }
+        StakeDeregistration(stc) => {
+            *refunds += check_stake_deregistration(stc, None, prot_pps, dstate)?;
+        }
+        UnReg(stc, refund) => {
+            *refunds += check_stake_deregistration(stc, Some(refund), prot_pps, dstate)?;
+        }
+        StakeDelegation(stc, pool) => {
+            check_stake_delegation(stc, pool, dstate, pstate)?;
+        }
+        VoteDeleg(stc, drep) => {
+            check_vote_delegation(stc, drep, dstate, &vstate.dreps)?;
+        }
+        StakeVoteDeleg(stc, pool, drep) => {
+            check_stake_delegation(stc, pool, dstate, pstate)?;
+            check_vote_delegation(stc, drep, dstate, &vstate.dreps)?;
+        }
+        StakeRegDeleg(stc, pool, deposit) => {
+            check_stake_registration(stc, deposit, prot_pps, dstate)?;
+            *deposits += *deposit;
+            check_stake_delegation(stc, pool, dstate, pstate)?;
+        }
+        VoteRegDeleg(stc, drep, deposit) => {
+            check_stake_registration(stc, deposit, prot_pps, dstate)?;
+            *deposits += *deposit;
+            check_vote_delegation(stc, drep, dstate, &vstate.dreps)?;
+        }
+        StakeVoteRegDeleg(stc, pool, drep, deposit) => {
+            check_stake_registration(stc, deposit, prot_pps, dstate)?;
+            *deposits += *deposit;
+            check_stake_delegation(stc, pool, dstate, pstate)?;
+            check_vote_delegation(stc, drep, dstate, &vstate.dreps)?;
+        }
+        // POOL rule
+        PoolRegistration {
+            operator,
+            vrf_keyhash,
+            pledge,
+            cost,
+            margin,
+            reward_account,
+            pool_owners,
+            relays,
+            pool_metadata,
+        } => {
+            let pool_param = PoolParam {
+                vrf_keyhash: *vrf_keyhash,
+                pledge: *pledge,
+                cost: *cost,
+                margin: margin.clone(),
+                reward_account: reward_account.clone(),
+                pool_owners: pool_owners.iter().cloned().collect(),
+                relays: relays.clone(),
+                pool_metadata: pool_metadata.clone(),
+            };
+            if check_pool_reg_or_update(operator, &pool_param, prot_pps, pstate)? {
+                *deposits += prot_pps.pool_deposit;
+            }
+        }
+        PoolRetirement(pool, repoch) => {
+            check_pool_retirement(pool, repoch, cepoch, &prot_pps.maximum_epoch, pstate)?;
+        }
+        // GOVCERT rule
+        RegDRepCert(drc, deposit, anchor) => {
+            if vstate.dreps.contains_key(drc) {
+                return Err(PostAlonzo(DRepAlreadyRegistered));
+            }
+            if *deposit != prot_pps.drep_deposit {
+                return Err(PostAlonzo(IncorrectDRepDeposit));
+            }
+            vstate.dreps.insert(
+                drc.clone(),
+                DRepState {
+                    deposit: *deposit,
+                    anchor: anchor.clone(),
+                    expiry: *cepoch + prot_pps.drep_inactivity_period,
+                },
+            );
+            *deposits += *deposit;
+        }
+        UnRegDRepCert(drc, refund) => {
+            match vstate.dreps.get(drc) {
+                None => return Err(PostAlonzo(DRepNotRegistered)),
+                Some(drep_state) if drep_state.deposit != *refund => {
+                    return Err(PostAlonzo(IncorrectDRepRefund))
+                }
+                Some(_) => (),
+            }
+            vstate.dreps.remove(drc);
+            let drep: DRep = drep_from_credential(drc);
+            dstate.vote_delegations.retain(|_, d| *d != drep);
+            *refunds += *refund;
+        }
+        UpdateDRepCert(drc, anchor) => match vstate.dreps.get_mut(drc) {
+            None => return Err(PostAlonzo(DRepNotRegistered)),
+            Some(drep_state) => {
+                drep_state.anchor = anchor.clone();
+                drep_state.expiry = *cepoch + prot_pps.drep_inactivity_period;
+            }
+        },
+        AuthCommitteeHot(cold, hot) => {
+            check_committee_authorization(
+                cold,
+                CommitteeAuthorization::HotCredential(hot.clone()),
+                gov_state,
+                &mut vstate.committee_state,
+            )?;
+        }
+        ResignCommitteeCold(cold, anchor) => {
+            check_committee_authorization(
+                cold,
+                CommitteeAuthorization::Resigned(anchor.clone()),
+                gov_state,
+                &mut vstate.committee_state,
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn check_stake_registration(
+    stc: &StakeCredential,
+    deposit: &Coin,
+    prot_pps: &ConwayProtParams,
+    ds: &mut DState,
+) -> ValidationResult {
+    if ds.rewards.contains_key(stc) {
+        return Err(PostAlonzo(StakeKeyAlreadyRegistered));
+    }
+    if *deposit != prot_pps.key_deposit {
+        return Err(PostAlonzo(IncorrectKeyDeposit));
+    }
+    ds.rewards.insert(stc.clone(), 0);
+    ds.deposits.insert(stc.clone(), *deposit);
+    Ok(())
+}
+
+// Returns the refunded deposit, which must coincide with the one supplied in
+// the certificate (if any). Credentials registered without a tracked deposit
+// are refunded the current key deposit, as the ledger does for legacy
+// registrations.
+fn check_stake_deregistration(
+    stc: &StakeCredential,
+    refund: Option<&Coin>,
+    prot_pps: &ConwayProtParams,
+    ds: &mut DState,
+) -> Result<Coin, ValidationError> {
+    match ds.rewards.get(stc) {
+        None => Err(PostAlonzo(StakeKeyNotRegistered)),
+        Some(0) => {
+            let deposit: Coin = ds
+                .deposits
+                .get(stc)
+                .copied()
+                .unwrap_or(prot_pps.key_deposit);
+            if refund.is_some_and(|refund| *refund != deposit) {
+                return Err(PostAlonzo(IncorrectKeyRefund));
+            }
+            ds.rewards.remove(stc);
+            ds.deposits.remove(stc);
+            ds.delegations.remove(stc);
+            ds.vote_delegations.remove(stc);
+            ds.ptrs.retain(|_, v| v != stc);
+            Ok(deposit)
+        }
+        Some(_) => Err(PostAlonzo(RewardsNotNull)),
+    }
+}
+
+fn check_stake_delegation(
+    stc: &StakeCredential,
+    pool: &PoolKeyhash,
+    ds: &mut DState,
+    ps: &PState,
+) -> ValidationResult {
+    if !ds.rewards.contains_key(stc) {
+        Err(PostAlonzo(StakeKeyNotRegistered))
+    } else if !ps.pool_params.contains_key(pool) {
+        Err(PostAlonzo(PoolNotRegistered))
+    } else {
+        ds.delegations.insert(stc.clone(), *pool);
+        Ok(())
+    }
+}
+
+// Delegation to the predefined `Abstain` and `NoConfidence` DReps is always
+// allowed, while credential-based DReps must be registered.
+fn check_vote_delegation(
+    stc: &StakeCredential,
+    drep: &DRep,
+    ds: &mut DState,
+    dreps: &HashMap<DRepCredential, DRepState>,
+) -> ValidationResult {
+    if !ds.rewards.contains_key(stc) {
+        return Err(PostAlonzo(StakeKeyNotRegistered));
+    }
+    let registered: bool = match drep {
+        DRep::Key(hash) => dreps.contains_key(&StakeCredential::AddrKeyhash(*hash)),
+        DRep::Script(hash) => dreps.contains_key(&StakeCredential::ScriptHash(*hash)),
+        DRep::Abstain | DRep::NoConfidence => true,
+    };
+    if !registered {
+        return Err(PostAlonzo(DRepNotRegistered));
+    }
+    ds.vote_delegations.insert(stc.clone(), drep.clone());
+    Ok(())
+}
+
+fn drep_from_credential(drc: &DRepCredential) -> DRep {
+    match drc {
+        StakeCredential::AddrKeyhash(hash) => DRep::Key(*hash),
+        StakeCredential::ScriptHash(hash) => DRep::Script(*hash),
+    }
+}
+
+// Returns whether a new pool is being registered (as opposed to updated), in
+// which case a deposit is required.
+fn check_pool_reg_or_update(
+    pool: &PoolKeyhash,
+    pool_param: &PoolParam,
+    prot_pps: &ConwayProtParams,
+    ps: &mut PState,
+) -> Result<bool, ValidationError> {
+    if pool_param.cost < prot_pps.min_pool_cost {
+        Err(PostAlonzo(PoolCostBelowMin))
+    } else if ps.pool_params.contains_key(pool) {
+        ps.fut_pool_params.insert(*pool, pool_param.clone());
+        ps.retiring.remove(pool);
+        Ok(false)
+    } else {
+        ps.pool_params.insert(*pool, pool_param.clone());
+        Ok(true)
+    }
+}
+
+fn check_pool_retirement(
+    pool: &PoolKeyhash,
+    repoch: &Epoch,
+    cepoch: &Epoch,
+    emax: &Epoch,
+    ps: &mut PState,
+) -> ValidationResult {
+    if !ps.pool_params.contains_key(pool) {
+        Err(PostAlonzo(PoolNotRegistered))
+    } else if *cepoch < *repoch && *repoch <= *cepoch + *emax {
+        ps.retiring.insert(*pool, *repoch);
+        Ok(())
+    } else {
+        Err(PostAlonzo(PoolRetirementEpoch))
+    }
+}
+
+// Only current committee members, or members proposed by a pending committee
+// update, may authorize a hot key or resign. Those who have resigned cannot do
+// either again.
+fn check_committee_authorization(
+    cold: &CommitteeColdCredential,
+    authorization: CommitteeAuthorization,
+    gov_state: &GovState,
+    committee_state: &mut HashMap<CommitteeColdCredential, CommitteeAuthorization>,
+) -> ValidationResult {
+    if !is_current_or_proposed_member(cold, gov_state) {
+        return Err(PostAlonzo(CommitteeIsUnknown));
+    }
+    if let Some(CommitteeAuthorization::Resigned(_)) = committee_state.get(cold) {
+        return Err(PostAlonzo(CommitteeHasPreviouslyResigned));
+    }
+    committee_state.insert(cold.clone(), authorization);
+    Ok(())
+}
+
+fn is_current_or_proposed_member(cold: &CommitteeColdCredential, gov_state: &GovState) -> bool {
+    let is_current: bool = gov_state
+        .committee
+        .as_ref()
+        .is_some_and(|committee| committee.members.contains_key(cold));
+    let is_proposed = || {
+        gov_state.proposals.values().any(|action| {
+            matches!(
+                &action.proposal.gov_action,
+                GovAction::UpdateCommittee(_, _, added, _) if added.contains_key(cold)
+            )
+        })
+    };
+    is_current || is_proposed()
+}
+
+// Checks the voting and proposal procedures of the transaction against the
+// governance state (GOV rule), recording new votes and proposals in it.
+#[allow(clippy::too_many_arguments)]
+fn check_governance(
+    tx_body: &TransactionBody,
+    tx_hash: &Hash<32>,
+    cert_state: &mut CertState,
+    gov_state: &mut GovState,
+    deposits: &mut Coin,
+    prot_pps: &ConwayProtParams,
+    cepoch: &Epoch,
+    network_id: &u8,
+) -> ValidationResult {
+    if let Some(voting_procedures) = &tx_body.voting_procedures {
+        check_votes(voting_procedures, cert_state, gov_state, prot_pps, cepoch)?;
+    }
+    if let Some(proposals) = &tx_body.proposal_procedures {
+        for (ix, proposal) in proposals.iter().enumerate() {
+            check_proposal(proposal, gov_state, prot_pps, cepoch, network_id)?;
+            *deposits += proposal.deposit;
+            gov_state.proposals.insert(
+                GovActionId {
+                    transaction_id: *tx_hash,
+                    action_index: ix as u32,
+                },
+                GovActionState {
+                    proposal: proposal.clone(),
+                    proposed_in: *cepoch,
+                    expires_after: *cepoch + prot_pps.governance_action_validity_period,
+                    votes: BTreeMap::new(),
+                },
+            );
+        }
+    }
+    Ok(())
+}
+
+// Every vote must come from a registered voter, refer to a known and unexpired
+// governance action, and be cast by a role allowed to vote on that action.
+// Votes by DReps renew their expiry.
+fn check_votes(
+    voting_procedures: &VotingProcedures,
+    cert_state: &mut CertState,
+    gov_state: &mut GovState,
+    prot_pps: &ConwayProtParams,
+    cepoch: &Epoch,
+) -> ValidationResult {
+    for (voter, votes) in voting_procedures.iter() {
+        check_voter_is_registered(voter, cert_state)?;
+        for (action_id, procedure) in votes.iter() {
+            let action: &mut GovActionState = gov_state
+                .proposals
+                .get_mut(action_id)
+                .ok_or(PostAlonzo(GovActionsDoNotExist))?;
+            if action.expires_after < *cepoch {
+                return Err(PostAlonzo(VotingOnExpiredGovAction));
+            }
+            if !voter_is_allowed(voter, &action.proposal.gov_action) {
+                return Err(PostAlonzo(DisallowedVoter));
+            }
+            action.votes.insert(voter.clone(), procedure.vote.clone());
+        }
+        if let Some(drc) = drep_credential(voter) {
+            if let Some(drep_state) = cert_state.vstate.dreps.get_mut(&drc) {
+                drep_state.expiry = *cepoch + prot_pps.drep_inactivity_period;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn check_voter_is_registered(voter: &Voter, cert_state: &CertState) -> ValidationResult {
+    let registered: bool = match voter {
+        Voter::ConstitutionalCommitteeKey(hash) => {
+            is_authorized_hot_key(&StakeCredential::AddrKeyhash(*hash), cert_state)
+        }
+        Voter::ConstitutionalCommitteeScript(hash) => {
+            is_authorized_hot_key(&StakeCredential::ScriptHash(*hash), cert_state)
+        }
+        Voter::DRepKey(_) | Voter::DRepScript(_) => match drep_credential(voter) {
+            Some(drc) => cert_state.vstate.dreps.contains_key(&drc),
+            None => false,
+        },
+        Voter::StakePoolKey(hash) => cert_state.pstate.pool_params.contains_key(hash),
+    };
+    if registered {
+        Ok(())
+    } else {
+        Err(PostAlonzo(UnknownVoter))
+    }
+}
+
+fn is_authorized_hot_key(hot: &StakeCredential, cert_state: &CertState) -> bool {
+    cert_state
+        .vstate
+        .committee_state
+        .values()
+        .any(|auth| *auth == CommitteeAuthorization::HotCredential(hot.clone()))
+}
+
+fn drep_credential(voter: &Voter) -> Option<DRepCredential> {
+    match voter {
+        Voter::DRepKey(hash) => Some(StakeCredential::AddrKeyhash(*hash)),
+        Voter::DRepScript(hash) => Some(StakeCredential::ScriptHash(*hash)),
+        _ => None,
+    }
+}
+
+// Voting permissions of each role, as per the Conway specification:
+// the constitutional committee cannot vote on no-confidence motions nor on
+// committee updates, and stake pools can only vote on those, on hard forks,
+// on info actions and on parameter changes affecting the security group.
+fn voter_is_allowed(voter: &Voter, gov_action: &GovAction) -> bool {
+    match voter {
+        Voter::ConstitutionalCommitteeKey(_) | Voter::ConstitutionalCommitteeScript(_) => {
+            !matches!(
+                gov_action,
+                GovAction::NoConfidence(..) | GovAction::UpdateCommittee(..)
+            )
+        }
+        Voter::DRepKey(_) | Voter::DRepScript(_) => true,
+        Voter::StakePoolKey(_) => match gov_action {
+            GovAction::ParameterChange(_, update, _) => is_security_relevant(update),
+            GovAction::HardForkInitiation(..)
+            | GovAction::NoConfidence(..)
+            | GovAction::UpdateCommittee(..)
+            | GovAction::Information => true,
+            GovAction::TreasuryWithdrawals(..) | GovAction::NewConstitution(..) => false,
+        },
+    }
+}
+
+fn is_security_relevant(update: &ProtocolParamUpdate) -> bool {
+    update.max_block_body_size.is_some()
+        || update.max_transaction_size.is_some()
+        || update.max_block_header_size.is_some()
+        || update.max_value_size.is_some()
+        || update.max_block_ex_units.is_some()
+        || update.minfee_a.is_some()
+        || update.minfee_b.is_some()
+        || update.ada_per_utxo_byte.is_some()
+        || update.governance_action_deposit.is_some()
+        || update.minfee_refscript_cost_per_byte.is_some()
+}
+
+fn check_proposal(
+    proposal: &ProposalProcedure,
+    gov_state: &GovState,
+    prot_pps: &ConwayProtParams,
+    cepoch: &Epoch,
+    network_id: &u8,
+) -> ValidationResult {
+    if proposal.deposit != prot_pps.governance_action_deposit {
+        return Err(PostAlonzo(ProposalDepositIncorrect));
+    }
+    if reward_account_network(&proposal.reward_account)? != *network_id {
+        return Err(PostAlonzo(ProposalNetworkIdMismatch));
+    }
+    let guardrails: Option<&ScriptHash> = gov_state
+        .constitution
+        .as_ref()
+        .and_then(|constitution| constitution.guardrail_script.as_ref());
+    match &proposal.gov_action {
+        GovAction::ParameterChange(_, _, policy) => check_policy_hash(policy, guardrails),
+        GovAction::TreasuryWithdrawals(withdrawals, policy) => {
+            for account in withdrawals.keys() {
+                if reward_account_network(account)? != *network_id {
+                    return Err(PostAlonzo(TreasuryWithdrawalsNetworkIdMismatch));
+                }
+            }
+            if withdrawals.values().sum::<Coin>() == 0 {
+                return Err(PostAlonzo(ZeroTreasuryWithdrawals));
+            }
+            check_policy_hash(policy, guardrails)
+        }
+        GovAction::UpdateCommittee(_, removed, added, _) => {
+            if removed.iter().any(|cold| added.contains_key(cold)) {
+                return Err(PostAlonzo(ConflictingCommitteeUpdate));
+            }
+            if added.values().any(|expiry| *expiry <= *cepoch) {
+                return Err(PostAlonzo(ExpirationEpochTooSmall));
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+// Proposals that may be subject to the guardrails script must carry exactly
+// the script hash of the enacted constitution.
+fn check_policy_hash(
+    policy: &Option<ScriptHash>,
+    guardrails: Option<&ScriptHash>,
+) -> ValidationResult {
+    if policy.as_ref() == guardrails {
+        Ok(())
+    } else {
+        Err(PostAlonzo(InvalidPolicyHash))
+    }
+}
+
+fn reward_account_credential(
+    reward_account: &RewardAccount,
+) -> Result<StakeCredential, ValidationError> {
+    match Address::from_bytes(reward_account) {
+        Ok(Address::Stake(stake_address)) => match stake_address.payload() {
+            StakePayload::Stake(hash) => Ok(StakeCredential::AddrKeyhash(*hash)),
+            StakePayload::Script(hash) => Ok(StakeCredential::ScriptHash(*hash)),
+        },
+        _ => Err(PostAlonzo(AddressDecoding)),
+    }
+}
+
+fn reward_account_network(reward_account: &RewardAccount) -> Result<u8, ValidationError> {
+    match Address::from_bytes(reward_account) {
+        Ok(Address::Stake(stake_address)) => Ok(stake_address.network().value()),
+        _ => Err(PostAlonzo(AddressDecoding)),
+    }
+}
+
 fn check_min_lovelace(tx_body: &TransactionBody, prot_pps: &ConwayProtParams) -> ValidationResult {
     for output in tx_body.outputs.iter() {
         let val: &Value = match output {
@@ -0,0 +1,167 @@
+//! Praos header checks, for headers of the Babbage and Conway eras.
+
+use std::convert::TryFrom;
+
+use pallas_codec::minicbor;
+use pallas_crypto::{
+    hash::{Hash, Hasher},
+    key::{ed25519, kes, vrf},
+};
+use pallas_math::math::{ExpOrdering, FixedDecimal, FixedPrecision};
+use pallas_primitives::{babbage::HeaderBody, RationalNumber};
+use pallas_traverse::MultiEraHeader;
+
+use crate::utils::{
+    HeaderError::*, OcertCounters, PoolStake, PraosEnvironment, ValidationError,
+    ValidationError::Header, ValidationResult,
+};
+
+/// Header rule of Praos: operational certificate and KES signature (OCERT),
+/// followed by the VRF proof and leader eligibility of the issuer.
+///
+/// On success, the operational certificate counter of the issuer is updated
+/// in `ocert_counters`. The counters are expected to be seeded with the pools
+/// of the stake distribution the first time they issue a block.
+pub fn validate_header(
+    header: &MultiEraHeader,
+    env: &PraosEnvironment,
+    ocert_counters: &mut OcertCounters,
+) -> ValidationResult {
+    let header_body: &HeaderBody = match header {
+        MultiEraHeader::BabbageCompatible(x) => &x.header_body,
+        _ => return Err(Header(UnsupportedHeader)),
+    };
+    let issuer_vkey: ed25519::PublicKey =
+        ed25519::PublicKey::try_from(header_body.issuer_vkey.as_slice())
+            .map_err(|_| Header(MalformedHeader))?;
+    let pool_id: Hash<28> = Hasher::<224>::hash(issuer_vkey.as_ref());
+    let counter: u64 = check_ocert(header, header_body, &issuer_vkey, env)?;
+    let last_counter: u64 = match ocert_counters.get(&pool_id) {
+        Some(last_counter) => *last_counter,
+        None if env.pool_distr.contains_key(&pool_id) => 0,
+        None => return Err(Header(NoOpCertCounter)),
+    };
+    if counter < last_counter {
+        return Err(Header(OpCertCounterTooSmall));
+    }
+    if counter > last_counter + 1 {
+        return Err(Header(OpCertCounterOverIncremented));
+    }
+    let pool_stake: &PoolStake = env.pool_distr.get(&pool_id).ok_or(Header(VrfKeyUnknown))?;
+    check_vrf(header, header_body, pool_stake, env)?;
+    ocert_counters.insert(pool_id, counter);
+    Ok(())
+}
+
+// Checks the KES period and signatures of the operational certificate and of
+// the header, and returns the counter of the certificate.
+fn check_ocert(
+    header: &MultiEraHeader,
+    header_body: &HeaderBody,
+    issuer_vkey: &ed25519::PublicKey,
+    env: &PraosEnvironment,
+) -> Result<u64, ValidationError> {
+    let ocert = &header_body.operational_cert;
+    let kes_period: u64 = header_body.slot / env.slots_per_kes_period;
+    if kes_period < ocert.operational_cert_kes_period {
+        return Err(Header(KesBeforeStart));
+    }
+    if kes_period >= ocert.operational_cert_kes_period + env.max_kes_evolutions {
+        return Err(Header(KesAfterEnd));
+    }
+    let mut ocert_signable: Vec<u8> = ocert.operational_cert_hot_vkey.to_vec();
+    ocert_signable.extend(ocert.operational_cert_sequence_number.to_be_bytes());
+    ocert_signable.extend(ocert.operational_cert_kes_period.to_be_bytes());
+    let ocert_signature = ed25519::Signature::try_from(ocert.operational_cert_sigma.as_slice())
+        .map_err(|_| Header(MalformedHeader))?;
+    if !issuer_vkey.verify(&ocert_signable, &ocert_signature) {
+        return Err(Header(InvalidOpCertSignature));
+    }
+    let hot_vkey = kes::PublicKey::try_from(ocert.operational_cert_hot_vkey.as_slice())
+        .map_err(|_| Header(MalformedHeader))?;
+    let body_signature = match header {
+        MultiEraHeader::BabbageCompatible(x) => {
+            kes::Signature::try_from(x.body_signature.as_slice())
+                .map_err(|_| Header(MalformedHeader))?
+        }
+        _ => return Err(Header(UnsupportedHeader)),
+    };
+    let period = u32::try_from(kes_period - ocert.operational_cert_kes_period)
+        .map_err(|_| Header(KesAfterEnd))?;
+    hot_vkey
+        .verify(period, header_body_cbor(header.cbor())?, &body_signature)
+        .map_err(|_| Header(InvalidKesSignature))?;
+    Ok(ocert.operational_cert_sequence_number)
+}
+
+fn check_vrf(
+    header: &MultiEraHeader,
+    header_body: &HeaderBody,
+    pool_stake: &PoolStake,
+    env: &PraosEnvironment,
+) -> ValidationResult {
+    if Hasher::<256>::hash(&header_body.vrf_vkey) != pool_stake.vrf_keyhash {
+        return Err(Header(VrfKeyWrongHash));
+    }
+    let vrf_vkey = vrf::PublicKey::try_from(header_body.vrf_vkey.as_slice())
+        .map_err(|_| Header(MalformedHeader))?;
+    let vrf_proof = vrf::Proof::try_from(header_body.vrf_result.1.as_slice())
+        .map_err(|_| Header(MalformedHeader))?;
+    let mut vrf_input = Hasher::<256>::new();
+    vrf_input.input(&header_body.slot.to_be_bytes());
+    vrf_input.input(env.epoch_nonce.as_ref());
+    let vrf_output = vrf_vkey
+        .verify(vrf_input.finalize(), &vrf_proof)
+        .map_err(|_| Header(VrfBadProof))?;
+    if vrf_output.as_ref() != header_body.vrf_result.0.as_slice() {
+        return Err(Header(VrfBadProof));
+    }
+    let leader_vrf_output: Vec<u8> = header
+        .leader_vrf_output()
+        .map_err(|_| Header(MalformedHeader))?;
+    if !is_slot_leader(
+        &leader_vrf_output,
+        &pool_stake.relative_stake,
+        &env.active_slot_coeff,
+    ) {
+        return Err(Header(VrfLeaderValueTooBig));
+    }
+    Ok(())
+}
+
+/// Leader check of Praos: the leader value `p = leader_vrf_output / 2^256`
+/// must satisfy `p < 1 - (1 - f)^sigma`, where `sigma` is the relative stake
+/// of the pool and `f` the active slot coefficient. This is checked as
+/// `1 / (1 - p) < exp(-sigma * ln(1 - f))` with a bounded Taylor expansion of
+/// the exponential.
+pub fn is_slot_leader(
+    leader_vrf_output: &[u8],
+    relative_stake: &RationalNumber,
+    active_slot_coeff: &RationalNumber,
+) -> bool {
+    if active_slot_coeff.numerator == active_slot_coeff.denominator {
+        return true;
+    }
+    let one = FixedDecimal::from(1u64);
+    let mut cert_nat_max: [u8; 33] = [0; 33];
+    cert_nat_max[0] = 1;
+    let cert_nat_max = FixedDecimal::from(&cert_nat_max[..]);
+    let cert_nat = FixedDecimal::from(leader_vrf_output);
+    let p = &cert_nat / &cert_nat_max;
+    let q = &one / &(&one - &p);
+    let sigma = &FixedDecimal::from(relative_stake.numerator)
+        / &FixedDecimal::from(relative_stake.denominator);
+    let f = &FixedDecimal::from(active_slot_coeff.numerator)
+        / &FixedDecimal::from(active_slot_coeff.denominator);
+    let alpha = -&(&sigma * &(&one - &f).ln());
+    alpha.exp_cmp(1000, 3, &q).estimation == ExpOrdering::LT
+}
+
+// The signed part of a header is its body, as serialised in the header.
+fn header_body_cbor(header_cbor: &[u8]) -> Result<&[u8], ValidationError> {
+    let mut decoder = minicbor::Decoder::new(header_cbor);
+    decoder.array().map_err(|_| Header(MalformedHeader))?;
+    let start: usize = decoder.position();
+    decoder.skip().map_err(|_| Header(MalformedHeader))?;
+    Ok(&header_cbor[start..decoder.position()])
+}
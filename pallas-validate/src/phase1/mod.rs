@@ -2,38 +2,143 @@
 
 pub mod alonzo;
 pub mod babbage;
+pub mod block;
 pub mod byron;
 pub mod conway;
+pub mod header;
 pub mod shelley_ma;
 
+use std::borrow::Cow;
+
 use alonzo::validate_alonzo_tx;
 use babbage::validate_babbage_tx;
+use block::validate_block_body;
 use byron::validate_byron_tx;
 use conway::validate_conway_tx;
-use pallas_primitives::alonzo::TransactionIndex;
-use pallas_traverse::{Era, MultiEraTx};
+use pallas_codec::utils::CborWrap;
+use pallas_primitives::{
+    alonzo::{TransactionIndex, TransactionInput},
+    byron::TxIn,
+};
+use pallas_traverse::{Era, MultiEraBlock, MultiEraInput, MultiEraOutput, MultiEraTx};
 use shelley_ma::validate_shelley_ma_tx;
 
 use crate::utils::{
-    CertState, Environment, MultiEraProtocolParameters, UTxOs,
+    BlockError, CertState, Environment, EraCbor, GovState, MultiEraProtocolParameters, TxoRef,
+    UTxOs, UtxoDelta, ValidationError,
     ValidationError::{
         EnvMissingAccountState, PParamsByronDoesntNeedAccountState, TxAndProtParamsDiffer,
     },
     ValidationResult,
 };
 
+/// Block inference rule: BBODY followed by LEDGERS
+///
+/// Unlike [`validate_txs`], each tx is validated against the UTxO set as left
+/// by the txs that precede it in the block, so outputs created earlier in the
+/// block can be spent by later txs. Txs flagged as invalid (`is_valid =
+/// false`) consume their collateral and produce their collateral return
+/// instead of their regular inputs and outputs. The slot of the environment
+/// must be that of the block.
+///
+/// On success, returns the changes the block makes to `utxos`, which can be
+/// reverted with [`UtxoDelta::undo`] should the block be rolled back.
+pub fn validate_block(
+    block: &MultiEraBlock,
+    env: &Environment,
+    utxos: &UTxOs,
+    cert_state: &mut CertState,
+    gov_state: &mut GovState,
+) -> Result<UtxoDelta, ValidationError> {
+    if block.slot() != *env.block_slot() {
+        return Err(ValidationError::Block(BlockError::WrongBlockSlot));
+    }
+    validate_block_body(block, env.prot_params())?;
+    let txs: Vec<MultiEraTx> = block.txs();
+    let mut block_utxos: UTxOs = utxos
+        .iter()
+        .map(|(input, output)| (input.clone(), reborrow_output(output)))
+        .collect();
+    let mut delta: UtxoDelta = UtxoDelta::default();
+    let mut delta_state: CertState = cert_state.clone();
+    let mut delta_gov_state: GovState = gov_state.clone();
+    for (txix, metx) in txs.iter().enumerate() {
+        validate_tx(
+            metx,
+            txix.try_into().unwrap(),
+            env,
+            &block_utxos,
+            &mut delta_state,
+            &mut delta_gov_state,
+        )?;
+        let mut tx_delta: UtxoDelta = UtxoDelta::default();
+        for input in metx.consumes() {
+            if let Some(output) = block_utxos.remove(&input) {
+                tx_delta
+                    .consumed
+                    .insert(TxoRef::from(&input), EraCbor::from(output));
+            }
+        }
+        for (index, output) in metx.produces() {
+            let input: MultiEraInput = produced_input(metx, index);
+            tx_delta
+                .produced
+                .insert(TxoRef::from(&input), EraCbor::from(output.clone()));
+            block_utxos.insert(input, output);
+        }
+        delta.merge(tx_delta);
+    }
+    *cert_state = delta_state;
+    *gov_state = delta_gov_state;
+    Ok(delta)
+}
+
+// Outputs are invariant over their lifetime, so the entries of the UTxO set
+// are borrowed again in order to be mixed with the outputs of the block.
+fn reborrow_output<'a>(output: &'a MultiEraOutput) -> MultiEraOutput<'a> {
+    match output {
+        MultiEraOutput::AlonzoCompatible(x, era) => MultiEraOutput::from_alonzo_compatible(x, *era),
+        MultiEraOutput::Babbage(x) => MultiEraOutput::from_babbage(x),
+        MultiEraOutput::Conway(x) => MultiEraOutput::from_conway(x),
+        MultiEraOutput::Byron(x) => MultiEraOutput::from_byron(x),
+        _ => unreachable!("unknown output variant"),
+    }
+}
+
+fn produced_input(metx: &MultiEraTx, index: usize) -> MultiEraInput<'static> {
+    match metx {
+        MultiEraTx::Byron(_) => MultiEraInput::Byron(Box::new(Cow::Owned(TxIn::Variant0(
+            CborWrap((metx.hash(), index as u32)),
+        )))),
+        _ => MultiEraInput::AlonzoCompatible(Box::new(Cow::Owned(TransactionInput {
+            transaction_id: metx.hash(),
+            index: index as u64,
+        }))),
+    }
+}
+
 /// Ledger sequence rule: LEDGERS
 pub fn validate_txs(
     metxs: &[MultiEraTx],
     env: &Environment,
     utxos: &UTxOs,
     cert_state: &mut CertState,
+    gov_state: &mut GovState,
 ) -> ValidationResult {
     let mut delta_state: CertState = cert_state.clone();
+    let mut delta_gov_state: GovState = gov_state.clone();
     for (txix, metx) in metxs.iter().enumerate() {
-        validate_tx(metx, txix.try_into().unwrap(), env, utxos, &mut delta_state)?;
+        validate_tx(
+            metx,
+            txix.try_into().unwrap(),
+            env,
+            utxos,
+            &mut delta_state,
+            &mut delta_gov_state,
+        )?;
     }
     *cert_state = delta_state;
+    *gov_state = delta_gov_state;
     Ok(())
 }
 
@@ -44,6 +149,7 @@ pub fn validate_tx(
     env: &Environment,
     utxos: &UTxOs,
     cert_state: &mut CertState,
+    gov_state: &mut GovState,
 ) -> ValidationResult {
     let pp_acnt = (env.prot_params(), env.acnt());
     match pp_acnt {
@@ -89,6 +195,8 @@ pub fn validate_tx(
             MultiEraTx::Conway(mtx) => validate_conway_tx(
                 mtx,
                 utxos,
+                cert_state,
+                gov_state,
                 cpp,
                 env.block_slot(),
                 env.prot_magic(),
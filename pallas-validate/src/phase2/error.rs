@@ -49,6 +49,8 @@ pub enum Error {
         Vec<String>,
     ),
 
+    #[error("script evaluation failed\n{:>13} {}", "Error", err)]
+    ScriptFailure { err: String, logs: Vec<String> },
     #[error("native script can't be executed in phase-two")]
     NativeScriptPhaseTwo,
     #[error("can't eval without redeemers")]
@@ -152,7 +152,8 @@ fn execute_script(
         .as_ref()
         .map(|d| plutus_data_to_pragma_term(&arena, d));
 
-    let program = uplc_turbo::flat::decode(&arena, &script_bytes[2..])?;
+    let flat_bytes = pallas_codec::minicbor::Decoder::new(script_bytes).bytes()?;
+    let program = uplc_turbo::flat::decode(&arena, flat_bytes)?;
 
     let program = match script_context {
         ScriptContext::V1V2 { .. } => if let Some(datum_term) = datum_term {
@@ -168,6 +169,13 @@ fn execute_script(
 
     let result = program.eval(&arena);
 
+    if let Err(err) = result.term {
+        return Err(Error::ScriptFailure {
+            err: err.to_string(),
+            logs: result.info.logs,
+        });
+    }
+
     Ok(TxEvalResult {
         tag: redeemer.tag,
         index: redeemer.index,
@@ -1,6 +1,7 @@
 //! Base types used for validating transactions in each era.
 
 pub mod environment;
+pub mod genesis;
 pub mod validation;
 
 pub use environment::*;
@@ -13,15 +14,19 @@ use pallas_crypto::key::ed25519::{PublicKey, Signature};
 use pallas_primitives::{
     alonzo::{Multiasset, NativeScript, Tx as AlonzoTx, VKeyWitness, Value},
     babbage::Tx as BabbageTx,
-    conway::{Multiasset as ConwayMultiasset, Tx as ConwayTx, Value as ConwayValue},
+    conway::{
+        Anchor, CommitteeColdCredential, CommitteeHotCredential, Constitution, DRep,
+        DRepCredential, GovActionId, Multiasset as ConwayMultiasset, ProposalProcedure,
+        Tx as ConwayTx, Value as ConwayValue, Vote, Voter,
+    },
     AddrKeyhash, AssetName, Coin, Epoch, GenesisDelegateHash, Genesishash, Hash, NetworkId,
     NonZeroInt, PlutusScript, PolicyId, PoolKeyhash, PoolMetadata, PositiveCoin, Relay,
     RewardAccount, StakeCredential, TransactionIndex, UnitInterval, VrfKeyhash,
 };
 
-use pallas_traverse::{time::Slot, Era, MultiEraInput, MultiEraOutput, MultiEraUpdate};
+use pallas_traverse::{time::Slot, Era, MultiEraInput, MultiEraOutput, MultiEraTx, MultiEraUpdate};
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::ops::Deref;
 pub use validation::*;
 
@@ -92,6 +97,71 @@ pub type UtxoMap = HashMap<TxoRef, EraCbor>;
 
 pub type UtxoSet = HashSet<TxoRef>;
 
+/// Changes to the UTxO set caused by applying a sequence of txs.
+///
+/// `consumed` holds the spent outputs that existed before the sequence was
+/// applied, so that the change can be reverted. `produced` holds the new
+/// outputs that are still unspent once the sequence has been applied.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct UtxoDelta {
+    pub consumed: UtxoMap,
+    pub produced: UtxoMap,
+}
+
+impl UtxoDelta {
+    /// Changes made by `tx` to `utxos`.
+    ///
+    /// A valid tx consumes its inputs and produces its outputs. A tx flagged as
+    /// invalid (`is_valid = false`) consumes its collateral instead and
+    /// produces only its collateral return, if any, at the index following its
+    /// outputs. Consumed outputs missing from `utxos` are left out.
+    pub fn from_tx(tx: &MultiEraTx, utxos: &UtxoMap) -> Self {
+        let consumed: UtxoMap = tx
+            .consumes()
+            .iter()
+            .map(TxoRef::from)
+            .filter_map(|txo_ref| {
+                let output: EraCbor = utxos.get(&txo_ref)?.clone();
+                Some((txo_ref, output))
+            })
+            .collect();
+        let produced: UtxoMap = tx
+            .produces()
+            .into_iter()
+            .map(|(index, output)| (TxoRef(tx.hash(), index as TxoIdx), EraCbor::from(output)))
+            .collect();
+        Self { consumed, produced }
+    }
+
+    /// Appends the changes of `next`, which must have been computed against the
+    /// UTxO set as left by `self`. Outputs both produced and consumed along the
+    /// way cancel out.
+    pub fn merge(&mut self, next: UtxoDelta) {
+        for (txo_ref, output) in next.consumed {
+            if self.produced.remove(&txo_ref).is_none() {
+                self.consumed.insert(txo_ref, output);
+            }
+        }
+        self.produced.extend(next.produced);
+    }
+
+    /// Rolls `utxos` forward over these changes.
+    pub fn apply(&self, utxos: &mut UtxoMap) {
+        for txo_ref in self.consumed.keys() {
+            utxos.remove(txo_ref);
+        }
+        utxos.extend(self.produced.clone());
+    }
+
+    /// Rolls `utxos` back over these changes, reverting [`UtxoDelta::apply`].
+    pub fn undo(&self, utxos: &mut UtxoMap) {
+        for txo_ref in self.produced.keys() {
+            utxos.remove(txo_ref);
+        }
+        utxos.extend(self.consumed.clone());
+    }
+}
+
 pub type UTxOs<'b> = HashMap<MultiEraInput<'b>, MultiEraOutput<'b>>;
 
 pub fn get_alonzo_comp_tx_size(mtx: &AlonzoTx) -> u32 {
@@ -778,6 +848,9 @@ pub struct DState {
     pub fut_gen_delegs: FutGenesisDelegation,
     pub gen_delegs: GenesisDelegation,
     pub inst_rewards: InstantaneousRewards,
+    // Conway: deposit paid when each stake credential was registered
+    pub deposits: HashMap<StakeCredential, Coin>,
+    pub vote_delegations: HashMap<StakeCredential, DRep>,
 }
 
 // Essentially part of the `PoolRegistration` component of `Certificate` at
@@ -801,6 +874,26 @@ pub struct PState {
     pub retiring: HashMap<PoolKeyhash, Epoch>,
 }
 
+#[derive(Clone, Debug)]
+pub struct DRepState {
+    pub deposit: Coin,
+    pub anchor: Option<Anchor>,
+    pub expiry: Epoch,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CommitteeAuthorization {
+    HotCredential(CommitteeHotCredential),
+    Resigned(Option<Anchor>),
+}
+
+// Voting state, introduced in Conway.
+#[derive(Default, Clone)] // for testing
+pub struct VState {
+    pub dreps: HashMap<DRepCredential, DRepState>,
+    pub committee_state: HashMap<CommitteeColdCredential, CommitteeAuthorization>,
+}
+
 // Originally `DPState` in ShelleyMA specs, then updated to
 // `CertState` in Haskell sources at Intersect (#3369).
 #[non_exhaustive]
@@ -808,4 +901,30 @@ pub struct PState {
 pub struct CertState {
     pub pstate: PState,
     pub dstate: DState,
+    pub vstate: VState,
+}
+
+#[derive(Clone, Debug)]
+pub struct GovActionState {
+    pub proposal: ProposalProcedure,
+    pub proposed_in: Epoch,
+    pub expires_after: Epoch,
+    pub votes: BTreeMap<Voter, Vote>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Committee {
+    // Cold credentials of the members, together with the epoch in which their
+    // term expires
+    pub members: HashMap<CommitteeColdCredential, Epoch>,
+    pub threshold: UnitInterval,
+}
+
+// Governance state, introduced in Conway. DRep expiries are kept in `VState`,
+// since they are also updated by certificates.
+#[derive(Default, Clone)] // for testing
+pub struct GovState {
+    pub proposals: BTreeMap<GovActionId, GovActionState>,
+    pub constitution: Option<Constitution>,
+    pub committee: Option<Committee>,
 }
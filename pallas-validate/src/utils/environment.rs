@@ -7,7 +7,9 @@ use pallas_primitives::{
     },
     babbage::CostModels as BabbageCostModels,
     conway::{CostModels as ConwayCostModels, Epoch},
+    Hash, PoolKeyhash,
 };
+use std::collections::HashMap;
 
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone)]
@@ -294,3 +296,24 @@ impl Environment {
         &self.acnt
     }
 }
+
+/// Relative stake of a pool in the stake distribution used for leader
+/// election, along with the hash of the VRF key registered by the pool.
+#[derive(Debug, Clone)]
+pub struct PoolStake {
+    pub relative_stake: RationalNumber,
+    pub vrf_keyhash: Hash<32>,
+}
+
+/// Consensus parameters and epoch state required for validating Praos headers.
+#[derive(Debug)]
+pub struct PraosEnvironment {
+    pub epoch_nonce: Hash<32>,
+    pub active_slot_coeff: RationalNumber,
+    pub slots_per_kes_period: u64,
+    pub max_kes_evolutions: u64,
+    pub pool_distr: HashMap<PoolKeyhash, PoolStake>,
+}
+
+/// Latest operational certificate counter seen for each pool.
+pub type OcertCounters = HashMap<PoolKeyhash, u64>;
@@ -0,0 +1,202 @@
+//! Initial UTxO set and certificate state built from the genesis files.
+
+use std::{
+    collections::HashMap,
+    net::{Ipv4Addr, Ipv6Addr},
+    str::FromStr,
+};
+
+use pallas_codec::{minicbor, utils::Bytes};
+use pallas_configs::{byron, shelley};
+use pallas_crypto::hash::Hash;
+use pallas_primitives::{
+    alonzo::{TransactionOutput, Value},
+    PoolMetadata, Relay, StakeCredential,
+};
+use pallas_traverse::Era;
+use thiserror::Error;
+
+use super::{CertState, EraCbor, PoolParam, TxoRef, UtxoMap};
+
+#[derive(Debug, Clone, Error)]
+pub enum GenesisError {
+    #[error("invalid {0} in genesis file: {1}")]
+    InvalidValue(&'static str, String),
+}
+
+fn hash<const BYTES: usize>(what: &'static str, value: &str) -> Result<Hash<BYTES>, GenesisError> {
+    Hash::from_str(value).map_err(|_| GenesisError::InvalidValue(what, value.to_owned()))
+}
+
+fn bytes(what: &'static str, value: &str) -> Result<Bytes, GenesisError> {
+    hex::decode(value)
+        .map(Bytes::from)
+        .map_err(|_| GenesisError::InvalidValue(what, value.to_owned()))
+}
+
+fn ip<T: FromStr>(what: &'static str, value: &str) -> Result<T, GenesisError> {
+    value
+        .parse()
+        .map_err(|_| GenesisError::InvalidValue(what, value.to_owned()))
+}
+
+/// UTxOs of the Byron genesis: the AVVM redemption addresses and the non-AVVM
+/// balances, each one spendable from a pseudo tx named after its address.
+pub fn byron_utxos(genesis: &byron::GenesisFile) -> UtxoMap {
+    byron::genesis_utxos(genesis)
+        .into_iter()
+        .map(|(tx_hash, address, amount)| {
+            let cbor = minicbor::to_vec((address, amount)).expect("infallible");
+            (TxoRef(tx_hash, 0), EraCbor(Era::Byron, cbor))
+        })
+        .collect()
+}
+
+/// UTxOs of the `initialFunds` section of the Shelley genesis, each one
+/// spendable from a pseudo tx derived from the payment credential of its
+/// address.
+pub fn shelley_utxos(genesis: &shelley::GenesisFile) -> Result<UtxoMap, GenesisError> {
+    let utxos = shelley::shelley_utxos(genesis).map_err(|err| match err {
+        shelley::InitialFundsError::InvalidAddress(x)
+        | shelley::InitialFundsError::UnsupportedAddress(x) => {
+            GenesisError::InvalidValue("initial funds address", x)
+        }
+    })?;
+
+    let utxos = utxos
+        .into_iter()
+        .map(|(tx_hash, address, amount)| {
+            let output = TransactionOutput {
+                address: address.to_vec().into(),
+                amount: Value::Coin(amount),
+                datum_hash: None,
+            };
+            let cbor = minicbor::to_vec(output).expect("infallible");
+
+            (TxoRef(tx_hash, 0), EraCbor(Era::Shelley, cbor))
+        })
+        .collect();
+
+    Ok(utxos)
+}
+
+fn relay(relay: &shelley::Relay) -> Result<Relay, GenesisError> {
+    match relay {
+        shelley::Relay::SingleHostAddr(addr) => {
+            let ipv4 = match &addr.ipv4 {
+                Some(ipv4) => Some(ip::<Ipv4Addr>("IPv4", ipv4)?.octets().to_vec().into()),
+                None => None,
+            };
+
+            // the ledger serializes IPv6 addresses as four little-endian words
+            let ipv6 = match &addr.ipv6 {
+                Some(ipv6) => {
+                    let octets = ip::<Ipv6Addr>("IPv6", ipv6)?.octets();
+                    let bytes: Vec<u8> = octets
+                        .chunks(4)
+                        .flat_map(|word| word.iter().rev().copied())
+                        .collect();
+                    Some(bytes.into())
+                }
+                None => None,
+            };
+
+            Ok(Relay::SingleHostAddr(addr.port, ipv4, ipv6))
+        }
+        shelley::Relay::SingleHostName(name) => {
+            Ok(Relay::SingleHostName(name.port, name.dns_name.clone()))
+        }
+        shelley::Relay::MultiHostName(name) => Ok(Relay::MultiHostName(name.dns_name.clone())),
+    }
+}
+
+fn pool_param(pool: &shelley::Pool) -> Result<PoolParam, GenesisError> {
+    let (header, credential) = match &pool.reward_account.credential {
+        shelley::Credential::KeyHash(hash) => (0xe0, hash),
+        shelley::Credential::ScriptHash(hash) => (0xf0, hash),
+    };
+    let network = match pool.reward_account.network.as_str() {
+        "Mainnet" => 1,
+        _ => 0,
+    };
+
+    let mut reward_account = vec![header | network];
+    reward_account.extend_from_slice(hash::<28>("reward account", credential)?.as_ref());
+
+    let pool_metadata = match &pool.metadata {
+        Some(metadata) => Some(PoolMetadata {
+            url: metadata.url.clone(),
+            hash: bytes("pool metadata hash", &metadata.hash)?,
+        }),
+        None => None,
+    };
+
+    Ok(PoolParam {
+        vrf_keyhash: hash("pool VRF key hash", &pool.vrf)?,
+        pledge: pool.pledge,
+        cost: pool.cost,
+        margin: pool.margin.clone(),
+        reward_account: reward_account.into(),
+        pool_owners: pool
+            .owners
+            .iter()
+            .map(|owner| hash("pool owner", owner))
+            .collect::<Result<_, _>>()?,
+        // the relay variant was already picked from its key when deserializing
+        relays: pool
+            .relays
+            .iter()
+            .flat_map(HashMap::values)
+            .map(relay)
+            .collect::<Result<_, _>>()?,
+        pool_metadata,
+    })
+}
+
+/// Certificate state at the start of the Shelley era: the genesis delegates,
+/// along with the pools and stake delegations of the `staking` section. Staked
+/// credentials are registered without any deposit.
+pub fn shelley_cert_state(genesis: &shelley::GenesisFile) -> Result<CertState, GenesisError> {
+    let mut cert_state = CertState::default();
+
+    for (genesis_hash, delegs) in genesis.gen_delegs.iter().flatten() {
+        let (Some(delegate), Some(vrf)) = (&delegs.delegate, &delegs.vrf) else {
+            return Err(GenesisError::InvalidValue(
+                "genesis delegation",
+                genesis_hash.clone(),
+            ));
+        };
+
+        cert_state.dstate.gen_delegs.insert(
+            bytes("genesis key hash", genesis_hash)?,
+            (
+                bytes("genesis delegate", delegate)?,
+                hash("genesis VRF", vrf)?,
+            ),
+        );
+    }
+
+    let Some(staking) = &genesis.staking else {
+        return Ok(cert_state);
+    };
+
+    for (pool_id, pool) in staking.pools.iter().flatten() {
+        cert_state
+            .pstate
+            .pool_params
+            .insert(hash("pool id", pool_id)?, pool_param(pool)?);
+    }
+
+    for (credential, pool_id) in staking.stake.iter().flatten() {
+        let credential = StakeCredential::AddrKeyhash(hash("stake key hash", credential)?);
+        let dstate = &mut cert_state.dstate;
+
+        dstate.rewards.insert(credential.clone(), 0);
+        dstate.deposits.insert(credential.clone(), 0);
+        dstate
+            .delegations
+            .insert(credential, hash("delegated pool id", pool_id)?);
+    }
+
+    Ok(cert_state)
+}
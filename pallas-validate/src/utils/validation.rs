@@ -27,6 +27,89 @@ pub enum ValidationError {
 
     #[error("{0}")]
     PostAlonzo(PostAlonzoError),
+
+    #[error("{0}")]
+    Block(BlockError),
+
+    #[error("{0}")]
+    Header(HeaderError),
+}
+
+#[derive(Debug, Clone, Error)]
+#[non_exhaustive]
+pub enum BlockError {
+    #[error("block and protocol parameters differ")]
+    BlockAndProtParamsDiffer,
+
+    #[error("block size exceeds the maximum allowed")]
+    MaxBlockSizeExceeded,
+
+    #[error("block header size exceeds the maximum allowed")]
+    MaxBlockHeaderSizeExceeded,
+
+    #[error("block body size exceeds the maximum allowed")]
+    MaxBlockBodySizeExceeded,
+
+    #[error("block body size does not match the size declared in the header")]
+    WrongBlockBodySize,
+
+    #[error("block body hash does not match the hash declared in the header")]
+    WrongBlockBodyHash,
+
+    #[error("header protocol version is too high")]
+    HeaderProtVerTooHigh,
+
+    #[error("block execution units exceed the maximum allowed")]
+    BlockExUnitsExceeded,
+
+    #[error("block body could not be serialised")]
+    UnencodableBlockBody,
+
+    #[error("block slot does not match the slot of the environment")]
+    WrongBlockSlot,
+}
+
+#[derive(Debug, Clone, Error)]
+#[non_exhaustive]
+pub enum HeaderError {
+    #[error("header is not a praos header")]
+    UnsupportedHeader,
+
+    #[error("header is malformed")]
+    MalformedHeader,
+
+    #[error("kes period of the header is before the start of the operational certificate")]
+    KesBeforeStart,
+
+    #[error("kes period of the header is after the end of the operational certificate")]
+    KesAfterEnd,
+
+    #[error("operational certificate signature does not verify")]
+    InvalidOpCertSignature,
+
+    #[error("kes signature of the header does not verify")]
+    InvalidKesSignature,
+
+    #[error("no operational certificate counter for the issuer")]
+    NoOpCertCounter,
+
+    #[error("operational certificate counter is lower than the last one seen")]
+    OpCertCounterTooSmall,
+
+    #[error("operational certificate counter was incremented by more than one")]
+    OpCertCounterOverIncremented,
+
+    #[error("issuer is not in the stake distribution")]
+    VrfKeyUnknown,
+
+    #[error("vrf key does not match the key registered by the pool")]
+    VrfKeyWrongHash,
+
+    #[error("vrf proof does not verify")]
+    VrfBadProof,
+
+    #[error("vrf leader value is too big for the stake of the pool")]
+    VrfLeaderValueTooBig,
 }
 
 #[derive(Debug, Clone, Error)]
@@ -409,6 +492,84 @@ pub enum PostAlonzoError {
 
     #[error("invalid script integrity hash")]
     ScriptIntegrityHash,
+
+    #[error("stake credential is already registered")]
+    StakeKeyAlreadyRegistered,
+
+    #[error("stake credential is not yet registered")]
+    StakeKeyNotRegistered,
+
+    #[error("stake registration deposit does not match the protocol parameters")]
+    IncorrectKeyDeposit,
+
+    #[error("stake deregistration refund does not match the deposit")]
+    IncorrectKeyRefund,
+
+    #[error("stake credential has a non-zero reward balance")]
+    RewardsNotNull,
+
+    #[error("withdrawal does not match the reward balance of a registered stake credential")]
+    WithdrawalsNotInRewards,
+
+    #[error("pool is not yet registered")]
+    PoolNotRegistered,
+
+    #[error("pool cost is below the minimum")]
+    PoolCostBelowMin,
+
+    #[error("pool retirement epoch is out of bounds")]
+    PoolRetirementEpoch,
+
+    #[error("DRep is already registered")]
+    DRepAlreadyRegistered,
+
+    #[error("DRep is not yet registered")]
+    DRepNotRegistered,
+
+    #[error("DRep registration deposit does not match the protocol parameters")]
+    IncorrectDRepDeposit,
+
+    #[error("DRep deregistration refund does not match the deposit")]
+    IncorrectDRepRefund,
+
+    #[error("committee member has previously resigned")]
+    CommitteeHasPreviouslyResigned,
+
+    #[error("committee member is neither current nor proposed")]
+    CommitteeIsUnknown,
+
+    #[error("vote refers to an unknown governance action")]
+    GovActionsDoNotExist,
+
+    #[error("vote refers to an expired governance action")]
+    VotingOnExpiredGovAction,
+
+    #[error("voter is not allowed to vote on the governance action")]
+    DisallowedVoter,
+
+    #[error("voter is not a registered DRep, pool or committee member")]
+    UnknownVoter,
+
+    #[error("proposal deposit does not match the protocol parameters")]
+    ProposalDepositIncorrect,
+
+    #[error("proposal return account has the wrong network ID")]
+    ProposalNetworkIdMismatch,
+
+    #[error("treasury withdrawal account has the wrong network ID")]
+    TreasuryWithdrawalsNetworkIdMismatch,
+
+    #[error("treasury withdrawals add up to zero")]
+    ZeroTreasuryWithdrawals,
+
+    #[error("proposal policy hash does not match the constitution guardrails script")]
+    InvalidPolicyHash,
+
+    #[error("committee update both adds and removes the same member")]
+    ConflictingCommitteeUpdate,
+
+    #[error("committee member expiration epoch is not in the future")]
+    ExpirationEpochTooSmall,
 }
 
 pub type ValidationResult = Result<(), ValidationError>;
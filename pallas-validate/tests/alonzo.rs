@@ -22,7 +22,7 @@ mod alonzo_tests {
     use pallas_validate::{
         phase1::validate_txs,
         utils::{
-            AccountState, AlonzoError, AlonzoProtParams, CertState, Environment,
+            AccountState, AlonzoError, AlonzoProtParams, CertState, Environment, GovState,
             MultiEraProtocolParameters, UTxOs, ValidationError::*,
         },
     };
@@ -57,7 +57,8 @@ mod alonzo_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => (),
             Err(err) => panic!("Unexpected error ({:?})", err),
         }
@@ -165,7 +166,8 @@ mod alonzo_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => (),
             Err(err) => panic!("Unexpected error ({:?})", err),
         }
@@ -200,7 +202,8 @@ mod alonzo_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => (),
             Err(err) => panic!("Unexpected error ({:?})", err),
         }
@@ -235,7 +238,8 @@ mod alonzo_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => (),
             Err(err) => panic!("Unexpected error ({:?})", err),
         }
@@ -275,7 +279,8 @@ mod alonzo_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("Inputs set should not be empty"),
             Err(err) => match err {
                 Alonzo(AlonzoError::TxInsEmpty) => (),
@@ -306,7 +311,8 @@ mod alonzo_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("All inputs should be within the UTxO set"),
             Err(err) => match err {
                 Alonzo(AlonzoError::InputNotInUTxO) => (),
@@ -350,7 +356,8 @@ mod alonzo_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("Validity interval lower bound should have been reached"),
             Err(err) => match err {
                 Alonzo(AlonzoError::BlockPrecedesValInt) => (),
@@ -394,7 +401,8 @@ mod alonzo_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("Validity interval upper bound should not have been surpassed"),
             Err(err) => match err {
                 Alonzo(AlonzoError::BlockExceedsValInt) => (),
@@ -434,7 +442,8 @@ mod alonzo_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("Fee should not be below minimum"),
             Err(err) => match err {
                 Alonzo(AlonzoError::FeeBelowMin) => (),
@@ -551,7 +560,8 @@ mod alonzo_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("No collateral inputs"),
             Err(err) => match err {
                 Alonzo(AlonzoError::CollateralMissing) => (),
@@ -664,7 +674,8 @@ mod alonzo_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("Number of collateral inputs should be within limits"),
             Err(err) => match err {
                 Alonzo(AlonzoError::TooManyCollaterals) => (),
@@ -796,7 +807,8 @@ mod alonzo_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("Collateral inputs should be verification-key locked"),
             Err(err) => match err {
                 Alonzo(AlonzoError::CollateralNotVKeyLocked) => (),
@@ -917,7 +929,8 @@ mod alonzo_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("Collateral inputs should contain only lovelace"),
             Err(err) => match err {
                 Alonzo(AlonzoError::NonLovelaceCollateral) => (),
@@ -1029,7 +1042,8 @@ mod alonzo_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("Collateral inputs should contain the minimum lovelace"),
             Err(err) => match err {
                 Alonzo(AlonzoError::CollateralMinLovelace) => (),
@@ -1072,7 +1086,8 @@ mod alonzo_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("Preservation of value does not hold"),
             Err(err) => match err {
                 Alonzo(AlonzoError::PreservationOfValue) => (),
@@ -1135,7 +1150,8 @@ mod alonzo_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("Output network ID should match environment network ID"),
             Err(err) => match err {
                 Alonzo(AlonzoError::OutputWrongNetworkID) => (),
@@ -1178,7 +1194,8 @@ mod alonzo_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("Transaction network ID should match environment network ID"),
             Err(err) => match err {
                 Alonzo(AlonzoError::TxWrongNetworkID) => (),
@@ -1291,7 +1308,8 @@ mod alonzo_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("Transaction ex units should be below maximum"),
             Err(err) => match err {
                 Alonzo(AlonzoError::TxExUnitsExceeded) => (),
@@ -1331,7 +1349,8 @@ mod alonzo_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!(
                 "Transaction size should not exceed the maximum allowed by the protocol parameter"
             ),
@@ -1455,7 +1474,8 @@ mod alonzo_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("All required signers should have signed the transaction"),
             Err(err) => match err {
                 Alonzo(AlonzoError::ReqSignerMissing) => (),
@@ -1498,7 +1518,8 @@ mod alonzo_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("Missing verification key witness"),
             Err(err) => match err {
                 Alonzo(AlonzoError::VKWitnessMissing) => (),
@@ -1548,7 +1569,8 @@ mod alonzo_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("Witness signature should verify the transaction"),
             Err(err) => match err {
                 Alonzo(AlonzoError::VKWrongSignature) => (),
@@ -1664,7 +1686,8 @@ mod alonzo_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("Missing Plutus script"),
             Err(err) => match err {
                 Alonzo(AlonzoError::ScriptWitnessMissing) => (),
@@ -1788,7 +1811,8 @@ mod alonzo_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("Unneeded Plutus script"),
             Err(err) => match err {
                 Alonzo(AlonzoError::UnneededNativeScript) => (),
@@ -1831,7 +1855,8 @@ mod alonzo_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("Minting policy is not supported by a matching native script"),
             Err(err) => match err {
                 Alonzo(AlonzoError::MintingLacksPolicy) => (),
@@ -1947,7 +1972,8 @@ mod alonzo_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("Missing datum"),
             Err(err) => match err {
                 Alonzo(AlonzoError::DatumMissing) => (),
@@ -2069,7 +2095,8 @@ mod alonzo_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("Unneeded datum"),
             Err(err) => match err {
                 Alonzo(AlonzoError::UnneededDatum) => (),
@@ -2192,7 +2219,8 @@ mod alonzo_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("Unneeded redeemer"),
             Err(err) => match err {
                 Alonzo(AlonzoError::UnneededRedeemer) => (),
@@ -2308,7 +2336,8 @@ mod alonzo_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("Redeemer missing"),
             Err(err) => match err {
                 Alonzo(AlonzoError::RedeemerMissing) => (),
@@ -2346,7 +2375,8 @@ mod alonzo_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("Transaction auxiliary data removed"),
             Err(err) => match err {
                 Alonzo(AlonzoError::MetadataHash) => (),
@@ -2385,7 +2415,8 @@ mod alonzo_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("Output minimum lovelace is unreached"),
             Err(err) => match err {
                 Alonzo(AlonzoError::MinLovelaceUnreached) => (),
@@ -2424,7 +2455,8 @@ mod alonzo_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("Max value size exceeded"),
             Err(err) => match err {
                 Alonzo(AlonzoError::MaxValSizeExceeded) => (),
@@ -2544,7 +2576,8 @@ mod alonzo_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("Wrong script integrity hash"),
             Err(err) => match err {
                 Alonzo(AlonzoError::ScriptIntegrityHash) => (),
@@ -21,8 +21,8 @@ mod babbage_tests {
     use pallas_validate::{
         phase1::validate_txs,
         utils::{
-            AccountState, BabbageProtParams, CertState, Environment, MultiEraProtocolParameters,
-            PostAlonzoError, UTxOs, ValidationError::*,
+            AccountState, BabbageProtParams, CertState, Environment, GovState,
+            MultiEraProtocolParameters, PostAlonzoError, UTxOs, ValidationError::*,
         },
     };
     use std::borrow::Cow;
@@ -61,7 +61,8 @@ mod babbage_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => (),
             Err(err) => assert!(false, "Unexpected error ({:?})", err),
         }
@@ -138,7 +139,8 @@ mod babbage_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => (),
             Err(err) => assert!(false, "Unexpected error ({:?})", err),
         }
@@ -224,7 +226,8 @@ mod babbage_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => (),
             Err(err) => assert!(false, "Unexpected error ({:?})", err),
         }
@@ -305,7 +308,8 @@ mod babbage_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => (),
             Err(err) => assert!(false, "Unexpected error ({:?})", err),
         }
@@ -389,7 +393,8 @@ mod babbage_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => (),
             Err(err) => assert!(false, "Unexpected error ({:?})", err),
         }
@@ -494,7 +499,8 @@ mod babbage_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => (),
             Err(err) => assert!(false, "Unexpected error ({:?})", err),
         }
@@ -568,7 +574,8 @@ mod babbage_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => (),
             Err(err) => assert!(false, "Unexpected error ({:?})", err),
         }
@@ -611,7 +618,8 @@ mod babbage_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => assert!(false, "Inputs set should not be empty"),
             Err(err) => match err {
                 PostAlonzo(PostAlonzoError::TxInsEmpty) => (),
@@ -641,7 +649,8 @@ mod babbage_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => assert!(false, "All inputs should be within the UTxO set"),
             Err(err) => match err {
                 PostAlonzo(PostAlonzoError::InputNotInUTxO) => (),
@@ -688,7 +697,8 @@ mod babbage_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => assert!(
                 false,
                 "Validity interval lower bound should have been reached"
@@ -738,7 +748,8 @@ mod babbage_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => assert!(
                 false,
                 "Validity interval upper bound should not have been surpassed"
@@ -784,7 +795,8 @@ mod babbage_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => assert!(false, "Fee should not be below minimum"),
             Err(err) => match err {
                 PostAlonzo(PostAlonzoError::FeeBelowMin) => (),
@@ -870,7 +882,8 @@ mod babbage_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => assert!(false, "No collateral inputs"),
             Err(err) => match err {
                 PostAlonzo(PostAlonzoError::CollateralMissing) => (),
@@ -953,7 +966,8 @@ mod babbage_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => assert!(false, "Number of collateral inputs should be within limits"),
             Err(err) => match err {
                 PostAlonzo(PostAlonzoError::TooManyCollaterals) => (),
@@ -1054,7 +1068,8 @@ mod babbage_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => assert!(false, "Collateral inputs should be verification-key locked"),
             Err(err) => match err {
                 PostAlonzo(PostAlonzoError::CollateralNotVKeyLocked) => (),
@@ -1168,7 +1183,8 @@ mod babbage_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => assert!(false, "Collateral balance should contained only lovelace"),
             Err(err) => match err {
                 PostAlonzo(PostAlonzoError::NonLovelaceCollateral) => (),
@@ -1251,7 +1267,8 @@ mod babbage_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => assert!(
                 false,
                 "Collateral balance should contained the minimum lovelace"
@@ -1340,7 +1357,8 @@ mod babbage_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => assert!(false, "Collateral annotation"),
             Err(err) => match err {
                 PostAlonzo(PostAlonzoError::CollateralAnnotation) => (),
@@ -1387,7 +1405,8 @@ mod babbage_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => assert!(false, "Preservation of value does not hold"),
             Err(err) => match err {
                 PostAlonzo(PostAlonzoError::PreservationOfValue) => (),
@@ -1430,7 +1449,8 @@ mod babbage_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => assert!(false, "Output minimum lovelace is unreached"),
             Err(err) => match err {
                 PostAlonzo(PostAlonzoError::MinLovelaceUnreached) => (),
@@ -1473,7 +1493,8 @@ mod babbage_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => assert!(false, "Max value size exceeded"),
             Err(err) => match err {
                 PostAlonzo(PostAlonzoError::MaxValSizeExceeded) => (),
@@ -1562,7 +1583,8 @@ mod babbage_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => assert!(
                 false,
                 "Output network ID should match environment network ID"
@@ -1612,7 +1634,8 @@ mod babbage_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => assert!(
                 false,
                 "Transaction network ID should match environment network ID"
@@ -1699,7 +1722,8 @@ mod babbage_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => assert!(false, "Transaction ex units should be below maximum"),
             Err(err) => match err {
                 PostAlonzo(PostAlonzoError::TxExUnitsExceeded) => (),
@@ -1743,7 +1767,8 @@ mod babbage_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => assert!(
                 false,
                 "Transaction size should not exceed the maximum allowed"
@@ -1860,7 +1885,8 @@ mod babbage_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => assert!(
                 false,
                 "Minting policy is not supported by the corresponding native script"
@@ -1941,7 +1967,8 @@ mod babbage_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => assert!(false, "Transaction auxiliary data removed"),
             Err(err) => match err {
                 PostAlonzo(PostAlonzoError::MetadataHash) => (),
@@ -2028,7 +2055,8 @@ mod babbage_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => assert!(
                 false,
                 "Script hash in input is not matched to a script in the witness set"
@@ -2117,7 +2145,8 @@ mod babbage_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => assert!(
                 false,
                 "Datum matching the script input datum hash is missing"
@@ -2212,7 +2241,8 @@ mod babbage_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => assert!(false, "Unneeded datum"),
             Err(err) => match err {
                 PostAlonzo(PostAlonzoError::UnneededDatum) => (),
@@ -2305,7 +2335,8 @@ mod babbage_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => assert!(false, "Unneeded datum"),
             Err(err) => match err {
                 PostAlonzo(PostAlonzoError::UnneededRedeemer) => (),
@@ -2395,7 +2426,8 @@ mod babbage_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => assert!(false, "Wrong script integrity hash"),
             Err(err) => match err {
                 PostAlonzo(PostAlonzoError::ScriptIntegrityHash) => (),
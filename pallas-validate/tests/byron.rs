@@ -6,8 +6,8 @@ mod byron_tests {
     use pallas_validate::{
         phase1::validate_txs,
         utils::{
-            ByronError, ByronProtParams, CertState, Environment, MultiEraProtocolParameters, UTxOs,
-            ValidationError::*,
+            ByronError, ByronProtParams, CertState, Environment, GovState,
+            MultiEraProtocolParameters, UTxOs, ValidationError::*,
         },
     };
 
@@ -77,7 +77,8 @@ mod byron_tests {
         );
         let env: Environment = hardcoded_environment_values!();
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => (),
             Err(err) => panic!("Unexpected error ({:?})", err),
         }
@@ -99,7 +100,8 @@ mod byron_tests {
         );
         let env: Environment = hardcoded_environment_values!();
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => (),
             Err(err) => panic!("Unexpected error ({:?})", err),
         }
@@ -129,7 +131,8 @@ mod byron_tests {
         let metx: MultiEraTx = MultiEraTx::from_byron(&mtxp);
         let env: Environment = hardcoded_environment_values!();
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("Inputs set should not be empty"),
             Err(err) => match err {
                 Byron(ByronError::TxInsEmpty) => (),
@@ -162,7 +165,8 @@ mod byron_tests {
         );
         let env: Environment = hardcoded_environment_values!();
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("Outputs set should not be empty"),
             Err(err) => match err {
                 Byron(ByronError::TxOutsEmpty) => (),
@@ -180,7 +184,8 @@ mod byron_tests {
         let utxos: UTxOs = UTxOs::new();
         let env: Environment = hardcoded_environment_values!();
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("All inputs must be within the UTxO set"),
             Err(err) => match err {
                 Byron(ByronError::InputNotInUTxO) => (),
@@ -219,7 +224,8 @@ mod byron_tests {
         );
         let env: Environment = hardcoded_environment_values!();
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("All outputs must contain lovelace"),
             Err(err) => match err {
                 Byron(ByronError::OutputWithoutLovelace) => (),
@@ -244,7 +250,8 @@ mod byron_tests {
 
         let env: Environment = hardcoded_environment_values!(summand = 1000, multiplier = 1000);
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("Fees should not be below minimum"),
             Err(err) => match err {
                 Byron(ByronError::FeesBelowMin) => (),
@@ -268,7 +275,8 @@ mod byron_tests {
         );
         let env: Environment = hardcoded_environment_values!(max_tx_size = 0);
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("Transaction size cannot exceed protocol limit"),
             Err(err) => match err {
                 Byron(ByronError::MaxTxSizeExceeded) => (),
@@ -300,7 +308,8 @@ mod byron_tests {
         );
         let env: Environment = hardcoded_environment_values!();
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("All inputs must have a witness signature"),
             Err(err) => match err {
                 Byron(ByronError::MissingWitness) => (),
@@ -341,7 +350,8 @@ mod byron_tests {
         );
         let env: Environment = hardcoded_environment_values!();
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("Witness signature should verify the transaction"),
             Err(err) => match err {
                 Byron(ByronError::WrongSignature) => (),
@@ -6,32 +6,53 @@ use pallas_codec::minicbor::{
     decode::{Decode, Decoder},
     encode,
 };
-use pallas_codec::utils::{Bytes, CborWrap, KeepRaw};
+use pallas_codec::utils::{Bytes, CborWrap, KeepRaw, MaybeIndefArray, Nullable};
 use pallas_primitives::conway::{
-    CostModels, DatumOption, ExUnits, NetworkId, PlutusScript, RationalNumber, ScriptRef,
-    TransactionBody, Tx, Value,
+    Anchor, Certificate, Constitution, CostModels, DRep, DatumOption, ExUnits, GovAction,
+    GovActionId, NetworkId, PlutusScript, ProposalProcedure, RationalNumber, ScriptRef,
+    TransactionBody, Tx, Value, Vote, Voter, VotingProcedure,
 };
 use pallas_primitives::{
     conway::{DRepVotingThresholds, PoolVotingThresholds, TransactionOutput},
-    Set,
+    Hash, NonEmptySet, Set, StakeCredential,
 };
-use pallas_traverse::MultiEraTx;
+use pallas_traverse::{Era, MultiEraTx};
 
 use pallas_validate::{
-    phase1::validate_txs,
+    phase1::{
+        block::validate_block_body,
+        header::{is_slot_leader, validate_header},
+        validate_block, validate_txs,
+    },
     utils::{
-        AccountState, CertState, ConwayProtParams, Environment, MultiEraProtocolParameters,
-        PostAlonzoError, UTxOs, ValidationError::*,
+        AccountState, BlockError, CertState, Committee, CommitteeAuthorization, ConwayProtParams,
+        DRepState, Environment, GovActionState, GovState, HeaderError, MultiEraProtocolParameters,
+        OcertCounters, PoolParam, PoolStake, PostAlonzoError, PraosEnvironment, TxoRef, UTxOs,
+        UtxoDelta, UtxoMap, ValidationError::*, ValidationResult,
     },
 };
 
 #[cfg(test)]
 mod conway_tests {
-    use std::{borrow::Cow, collections::BTreeMap};
+    use std::{
+        borrow::Cow,
+        collections::{BTreeMap, HashMap},
+    };
 
     use pallas_addresses::{Address, ShelleyAddress, ShelleyPaymentPart};
-    use pallas_primitives::{conway::PostAlonzoTransactionOutput, PositiveCoin};
-    use pallas_traverse::{MultiEraInput, MultiEraOutput};
+    use pallas_crypto::{
+        hash::Hasher,
+        key::{ed25519::SecretKey, kes, vrf},
+    };
+    use pallas_primitives::{
+        conway::{
+            AuxiliaryData, Header, HeaderBody, OperationalCert, PlutusData,
+            PostAlonzoTransactionOutput, Redeemer, RedeemerTag, Redeemers, TransactionInput,
+            VKeyWitness, WitnessSet,
+        },
+        PositiveCoin, VrfCert,
+    };
+    use pallas_traverse::{MultiEraBlock, MultiEraHeader, MultiEraInput, MultiEraOutput};
 
     use super::*;
 
@@ -67,7 +88,8 @@ mod conway_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => (),
             Err(err) => assert!(false, "Unexpected error ({:?})", err),
         }
@@ -156,8 +178,15 @@ mod conway_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-
-        match validate_txs(&[metx.clone()], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+
+        match validate_txs(
+            std::slice::from_ref(&metx),
+            &env,
+            &utxos,
+            &mut cert_state,
+            &mut gov_state,
+        ) {
             Ok(()) => (),
             Err(err) => assert!(false, "Unexpected error ({:?})", err),
         };
@@ -249,8 +278,15 @@ mod conway_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-
-        match validate_txs(&[metx.clone()], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+
+        match validate_txs(
+            std::slice::from_ref(&metx),
+            &env,
+            &utxos,
+            &mut cert_state,
+            &mut gov_state,
+        ) {
             Ok(()) => (),
             Err(err) => assert!(false, "Unexpected error ({:?})", err),
         };
@@ -304,7 +340,8 @@ mod conway_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => assert!(false, "Inputs set should not be empty"),
             Err(err) => match err {
                 PostAlonzo(PostAlonzoError::TxInsEmpty) => (),
@@ -334,7 +371,8 @@ mod conway_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => assert!(false, "All inputs should be within the UTxO set"),
             Err(err) => match err {
                 PostAlonzo(PostAlonzoError::InputNotInUTxO) => (),
@@ -381,7 +419,8 @@ mod conway_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => assert!(
                 false,
                 "Validity interval lower bound should have been reached"
@@ -431,7 +470,8 @@ mod conway_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => assert!(
                 false,
                 "Validity interval upper bound should not have been surpassed"
@@ -477,7 +517,8 @@ mod conway_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => assert!(false, "Fee should not be below minimum"),
             Err(err) => match err {
                 PostAlonzo(PostAlonzoError::FeeBelowMin) => (),
@@ -524,7 +565,8 @@ mod conway_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => assert!(false, "Preservation of value does not hold"),
             Err(err) => match err {
                 PostAlonzo(PostAlonzoError::PreservationOfValue) => (),
@@ -567,7 +609,8 @@ mod conway_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => assert!(false, "Output minimum lovelace is unreached"),
             Err(err) => match err {
                 PostAlonzo(PostAlonzoError::MinLovelaceUnreached) => (),
@@ -610,7 +653,8 @@ mod conway_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => assert!(false, "Max value size exceeded"),
             Err(err) => match err {
                 PostAlonzo(PostAlonzoError::MaxValSizeExceeded) => (),
@@ -657,7 +701,8 @@ mod conway_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => assert!(
                 false,
                 "Transaction network ID should match environment network ID"
@@ -727,7 +772,8 @@ mod conway_tests {
         let metx: MultiEraTx = MultiEraTx::from_conway(&mtx);
 
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => assert!(false, "No collateral inputs"),
             Err(err) => match err {
                 PostAlonzo(PostAlonzoError::CollateralMissing) => (),
@@ -792,7 +838,8 @@ mod conway_tests {
         };
 
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => assert!(false, "Number of collateral inputs should be within limits"),
             Err(err) => match err {
                 PostAlonzo(PostAlonzoError::TooManyCollaterals) => (),
@@ -874,7 +921,8 @@ mod conway_tests {
         let metx: MultiEraTx = MultiEraTx::from_conway(&mtx);
 
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => assert!(false, "Collateral inputs should be verification-key locked"),
             Err(err) => match err {
                 PostAlonzo(PostAlonzoError::CollateralNotVKeyLocked) => (),
@@ -949,7 +997,8 @@ mod conway_tests {
         add_collateral_conway(&mtx.transaction_body, &mut utxos, collateral_info);
 
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => assert!(false, "Collateral balance should contained only lovelace"),
             Err(err) => match err {
                 PostAlonzo(PostAlonzoError::NonLovelaceCollateral) => (),
@@ -1012,7 +1061,8 @@ mod conway_tests {
         conway_prot_params.collateral_percentage = 10;
 
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => assert!(
                 false,
                 "Collateral balance should contained the minimum lovelace"
@@ -1083,7 +1133,8 @@ mod conway_tests {
         let metx: MultiEraTx = MultiEraTx::from_conway(&mtx);
 
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => assert!(false, "Collateral annotation"),
             Err(err) => match err {
                 PostAlonzo(PostAlonzoError::CollateralAnnotation) => (),
@@ -1127,7 +1178,8 @@ mod conway_tests {
             acnt: Some(acnt),
         };
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => assert!(
                 false,
                 "Transaction size should not exceed the maximum allowed"
@@ -1139,6 +1191,1570 @@ mod conway_tests {
         }
     }
 
+    #[test]
+    // Same as successful_mainnet_tx, except that the transaction registers a
+    // stake credential which is already registered.
+    fn stake_key_already_registered() {
+        let stc = StakeCredential::AddrKeyhash(Hash::new([7; 28]));
+        let mut cert_state: CertState = CertState::default();
+        let mut gov_state: GovState = GovState::default();
+        cert_state.dstate.rewards.insert(stc.clone(), 0);
+        match validate_modified_conway3(
+            |tx_body| {
+                tx_body.certificates = NonEmptySet::from_vec(vec![Certificate::Reg(stc, 2000000)])
+            },
+            &mut cert_state,
+            &mut gov_state,
+        ) {
+            Ok(()) => assert!(false, "Stake credential should not be registered twice"),
+            Err(err) => match err {
+                PostAlonzo(PostAlonzoError::StakeKeyAlreadyRegistered) => (),
+                _ => assert!(false, "Unexpected error ({:?})", err),
+            },
+        }
+    }
+
+    #[test]
+    // Same as successful_mainnet_tx, except that the transaction registers a
+    // DRep with a deposit different from the one in the protocol parameters.
+    fn incorrect_drep_deposit() {
+        let drc = StakeCredential::AddrKeyhash(Hash::new([7; 28]));
+        let mut cert_state: CertState = CertState::default();
+        let mut gov_state: GovState = GovState::default();
+        match validate_modified_conway3(
+            |tx_body| {
+                tx_body.certificates =
+                    NonEmptySet::from_vec(vec![Certificate::RegDRepCert(drc, 1999, None)])
+            },
+            &mut cert_state,
+            &mut gov_state,
+        ) {
+            Ok(()) => assert!(false, "DRep deposit should match the protocol parameters"),
+            Err(err) => match err {
+                PostAlonzo(PostAlonzoError::IncorrectDRepDeposit) => (),
+                _ => assert!(false, "Unexpected error ({:?})", err),
+            },
+        }
+    }
+
+    #[test]
+    // Same as successful_mainnet_tx, except that the transaction delegates votes
+    // to a DRep which is not registered.
+    fn vote_delegation_to_unregistered_drep() {
+        let stc = StakeCredential::AddrKeyhash(Hash::new([7; 28]));
+        let mut cert_state: CertState = CertState::default();
+        let mut gov_state: GovState = GovState::default();
+        cert_state.dstate.rewards.insert(stc.clone(), 0);
+        match validate_modified_conway3(
+            |tx_body| {
+                tx_body.certificates = NonEmptySet::from_vec(vec![Certificate::VoteDeleg(
+                    stc,
+                    DRep::Key(Hash::new([8; 28])),
+                )])
+            },
+            &mut cert_state,
+            &mut gov_state,
+        ) {
+            Ok(()) => assert!(false, "Delegatee DRep should be registered"),
+            Err(err) => match err {
+                PostAlonzo(PostAlonzoError::DRepNotRegistered) => (),
+                _ => assert!(false, "Unexpected error ({:?})", err),
+            },
+        }
+    }
+
+    #[test]
+    // Same as successful_mainnet_tx, except that the transaction authorizes a
+    // hot key for a committee member which has already resigned.
+    fn committee_has_previously_resigned() {
+        let cold = StakeCredential::AddrKeyhash(Hash::new([7; 28]));
+        let hot = StakeCredential::AddrKeyhash(Hash::new([8; 28]));
+        let mut cert_state: CertState = CertState::default();
+        let mut gov_state: GovState = GovState {
+            committee: Some(mk_committee(std::slice::from_ref(&cold))),
+            ..GovState::default()
+        };
+        cert_state
+            .vstate
+            .committee_state
+            .insert(cold.clone(), CommitteeAuthorization::Resigned(None));
+        match validate_modified_conway3(
+            |tx_body| {
+                tx_body.certificates =
+                    NonEmptySet::from_vec(vec![Certificate::AuthCommitteeHot(cold, hot)])
+            },
+            &mut cert_state,
+            &mut gov_state,
+        ) {
+            Ok(()) => assert!(
+                false,
+                "Resigned committee member cannot authorize a hot key"
+            ),
+            Err(err) => match err {
+                PostAlonzo(PostAlonzoError::CommitteeHasPreviouslyResigned) => (),
+                _ => assert!(false, "Unexpected error ({:?})", err),
+            },
+        }
+    }
+
+    #[test]
+    // The registration deposit is paid from the input of the tx.
+    fn successful_stake_registration() {
+        let key: SecretKey = SecretKey::from([3; 32]);
+        let stc: StakeCredential = mk_stake_credential(&key);
+        let input: TransactionInput = mk_genesis_input();
+        let (_, tx) = mk_signed_tx_with(&key, input.clone(), 17_800_000, |tx_body| {
+            tx_body.certificates =
+                NonEmptySet::from_vec(vec![Certificate::Reg(stc.clone(), 2_000_000)]);
+        });
+        let utxos: UTxOs = mk_signed_tx_utxos(&key, input, 20_000_000);
+        let mut cert_state: CertState = CertState::default();
+        match validate_signed_tx(tx, &utxos, &mut cert_state) {
+            Ok(()) => (),
+            Err(err) => assert!(false, "Unexpected error ({:?})", err),
+        }
+        assert_eq!(cert_state.dstate.rewards.get(&stc), Some(&0));
+        assert_eq!(cert_state.dstate.deposits.get(&stc), Some(&2_000_000));
+    }
+
+    #[test]
+    // The credential was registered without a tracked deposit, so the current
+    // key deposit is refunded.
+    fn successful_stake_deregistration() {
+        let key: SecretKey = SecretKey::from([3; 32]);
+        let stc: StakeCredential = mk_stake_credential(&key);
+        let input: TransactionInput = mk_genesis_input();
+        let (_, tx) = mk_signed_tx_with(&key, input.clone(), 21_800_000, |tx_body| {
+            tx_body.certificates =
+                NonEmptySet::from_vec(vec![Certificate::StakeDeregistration(stc.clone())]);
+        });
+        let utxos: UTxOs = mk_signed_tx_utxos(&key, input, 20_000_000);
+        let mut cert_state: CertState = CertState::default();
+        cert_state.dstate.rewards.insert(stc.clone(), 0);
+        match validate_signed_tx(tx, &utxos, &mut cert_state) {
+            Ok(()) => (),
+            Err(err) => assert!(false, "Unexpected error ({:?})", err),
+        }
+        assert!(!cert_state.dstate.rewards.contains_key(&stc));
+    }
+
+    #[test]
+    // The whole reward balance is withdrawn, and the credential deregistered in
+    // the same tx.
+    fn successful_withdrawal_and_deregistration() {
+        let key: SecretKey = SecretKey::from([3; 32]);
+        let stc: StakeCredential = mk_stake_credential(&key);
+        let input: TransactionInput = mk_genesis_input();
+        let (_, tx) = mk_signed_tx_with(&key, input.clone(), 26_800_000, |tx_body| {
+            tx_body.withdrawals = Some(BTreeMap::from([(mk_reward_account(&key), 5_000_000)]));
+            tx_body.certificates =
+                NonEmptySet::from_vec(vec![Certificate::UnReg(stc.clone(), 2_000_000)]);
+        });
+        let utxos: UTxOs = mk_signed_tx_utxos(&key, input, 20_000_000);
+        let mut cert_state: CertState = CertState::default();
+        cert_state.dstate.rewards.insert(stc.clone(), 5_000_000);
+        cert_state.dstate.deposits.insert(stc.clone(), 2_000_000);
+        match validate_signed_tx(tx, &utxos, &mut cert_state) {
+            Ok(()) => (),
+            Err(err) => assert!(false, "Unexpected error ({:?})", err),
+        }
+        assert!(!cert_state.dstate.rewards.contains_key(&stc));
+    }
+
+    #[test]
+    // Withdrawals must drain the whole reward balance.
+    fn partial_withdrawal() {
+        let key: SecretKey = SecretKey::from([3; 32]);
+        let stc: StakeCredential = mk_stake_credential(&key);
+        let input: TransactionInput = mk_genesis_input();
+        let (_, tx) = mk_signed_tx_with(&key, input.clone(), 23_800_000, |tx_body| {
+            tx_body.withdrawals = Some(BTreeMap::from([(mk_reward_account(&key), 4_000_000)]));
+        });
+        let utxos: UTxOs = mk_signed_tx_utxos(&key, input, 20_000_000);
+        let mut cert_state: CertState = CertState::default();
+        cert_state.dstate.rewards.insert(stc, 5_000_000);
+        match validate_signed_tx(tx, &utxos, &mut cert_state) {
+            Ok(()) => assert!(false, "Withdrawal should match the reward balance"),
+            Err(err) => match err {
+                PostAlonzo(PostAlonzoError::WithdrawalsNotInRewards) => (),
+                _ => assert!(false, "Unexpected error ({:?})", err),
+            },
+        }
+    }
+
+    #[test]
+    // The pool deposit is paid from the input of the tx.
+    fn successful_pool_registration() {
+        let key: SecretKey = SecretKey::from([3; 32]);
+        let operator = Hasher::<224>::hash(key.public_key().as_ref());
+        let input: TransactionInput = mk_genesis_input();
+        let (_, tx) = mk_signed_tx_with(&key, input.clone(), 99_800_000, |tx_body| {
+            tx_body.certificates =
+                NonEmptySet::from_vec(vec![mk_pool_registration(&key, 340_000_000)]);
+        });
+        let utxos: UTxOs = mk_signed_tx_utxos(&key, input, 600_000_000);
+        let mut cert_state: CertState = CertState::default();
+        match validate_signed_tx(tx, &utxos, &mut cert_state) {
+            Ok(()) => (),
+            Err(err) => assert!(false, "Unexpected error ({:?})", err),
+        }
+        assert_eq!(cert_state.pstate.pool_params[&operator].cost, 340_000_000);
+    }
+
+    #[test]
+    // The pool cost is below the minimum of the protocol parameters.
+    fn pool_cost_below_min() {
+        let key: SecretKey = SecretKey::from([3; 32]);
+        let input: TransactionInput = mk_genesis_input();
+        let (_, tx) = mk_signed_tx_with(&key, input.clone(), 99_800_000, |tx_body| {
+            tx_body.certificates =
+                NonEmptySet::from_vec(vec![mk_pool_registration(&key, 339_999_999)]);
+        });
+        let utxos: UTxOs = mk_signed_tx_utxos(&key, input, 600_000_000);
+        let mut cert_state: CertState = CertState::default();
+        match validate_signed_tx(tx, &utxos, &mut cert_state) {
+            Ok(()) => assert!(false, "Pool cost should not be below the minimum"),
+            Err(err) => match err {
+                PostAlonzo(PostAlonzoError::PoolCostBelowMin) => (),
+                _ => assert!(false, "Unexpected error ({:?})", err),
+            },
+        }
+        assert!(cert_state.pstate.pool_params.is_empty());
+    }
+
+    #[test]
+    // The registered pool retires in the next epoch. The block is in epoch 365.
+    fn successful_pool_retirement() {
+        let key: SecretKey = SecretKey::from([3; 32]);
+        let operator = Hasher::<224>::hash(key.public_key().as_ref());
+        let mut cert_state: CertState = mk_pool_cert_state(&key);
+        let input: TransactionInput = mk_genesis_input();
+        let (_, tx) = mk_signed_tx_with(&key, input.clone(), 19_800_000, |tx_body| {
+            tx_body.certificates =
+                NonEmptySet::from_vec(vec![Certificate::PoolRetirement(operator, 366)]);
+        });
+        let utxos: UTxOs = mk_signed_tx_utxos(&key, input, 20_000_000);
+        match validate_signed_tx(tx, &utxos, &mut cert_state) {
+            Ok(()) => (),
+            Err(err) => assert!(false, "Unexpected error ({:?})", err),
+        }
+        assert_eq!(cert_state.pstate.retiring.get(&operator), Some(&366));
+    }
+
+    #[test]
+    // The retirement epoch is further than the maximum allowed by the protocol
+    // parameters (18 epochs).
+    fn pool_retirement_too_late() {
+        let key: SecretKey = SecretKey::from([3; 32]);
+        let operator = Hasher::<224>::hash(key.public_key().as_ref());
+        let mut cert_state: CertState = mk_pool_cert_state(&key);
+        let input: TransactionInput = mk_genesis_input();
+        let (_, tx) = mk_signed_tx_with(&key, input.clone(), 19_800_000, |tx_body| {
+            tx_body.certificates =
+                NonEmptySet::from_vec(vec![Certificate::PoolRetirement(operator, 365 + 19)]);
+        });
+        let utxos: UTxOs = mk_signed_tx_utxos(&key, input, 20_000_000);
+        match validate_signed_tx(tx, &utxos, &mut cert_state) {
+            Ok(()) => assert!(false, "Pool retirement epoch should be within bounds"),
+            Err(err) => match err {
+                PostAlonzo(PostAlonzoError::PoolRetirementEpoch) => (),
+                _ => assert!(false, "Unexpected error ({:?})", err),
+            },
+        }
+        assert!(cert_state.pstate.retiring.is_empty());
+    }
+
+    #[test]
+    // The anchor of the registered DRep is replaced, and its expiry renewed.
+    fn successful_drep_update() {
+        let key: SecretKey = SecretKey::from([3; 32]);
+        let drc: StakeCredential = mk_stake_credential(&key);
+        let mut cert_state: CertState = mk_drep_cert_state(&drc);
+        let anchor = Anchor {
+            url: String::from("https://example.com/drep.json"),
+            content_hash: Hash::new([0; 32]),
+        };
+        let input: TransactionInput = mk_genesis_input();
+        let (_, tx) = mk_signed_tx_with(&key, input.clone(), 19_800_000, |tx_body| {
+            tx_body.certificates = NonEmptySet::from_vec(vec![Certificate::UpdateDRepCert(
+                drc.clone(),
+                Some(anchor.clone()),
+            )]);
+        });
+        let utxos: UTxOs = mk_signed_tx_utxos(&key, input, 20_000_000);
+        match validate_signed_tx(tx, &utxos, &mut cert_state) {
+            Ok(()) => (),
+            Err(err) => assert!(false, "Unexpected error ({:?})", err),
+        }
+        assert_eq!(cert_state.vstate.dreps[&drc].anchor, Some(anchor));
+        assert_eq!(cert_state.vstate.dreps[&drc].expiry, 365 + 60);
+    }
+
+    #[test]
+    // The DRep to be updated is not registered.
+    fn update_unregistered_drep() {
+        let key: SecretKey = SecretKey::from([3; 32]);
+        let drc: StakeCredential = mk_stake_credential(&key);
+        let input: TransactionInput = mk_genesis_input();
+        let (_, tx) = mk_signed_tx_with(&key, input.clone(), 19_800_000, |tx_body| {
+            tx_body.certificates =
+                NonEmptySet::from_vec(vec![Certificate::UpdateDRepCert(drc.clone(), None)]);
+        });
+        let utxos: UTxOs = mk_signed_tx_utxos(&key, input, 20_000_000);
+        let mut cert_state: CertState = CertState::default();
+        match validate_signed_tx(tx, &utxos, &mut cert_state) {
+            Ok(()) => assert!(false, "Only registered DReps can be updated"),
+            Err(err) => match err {
+                PostAlonzo(PostAlonzoError::DRepNotRegistered) => (),
+                _ => assert!(false, "Unexpected error ({:?})", err),
+            },
+        }
+    }
+
+    #[test]
+    // The deposit of the DRep is refunded, and the votes delegated to it are
+    // dropped.
+    fn successful_drep_deregistration() {
+        let key: SecretKey = SecretKey::from([3; 32]);
+        let drc: StakeCredential = mk_stake_credential(&key);
+        let drep_hash = Hasher::<224>::hash(key.public_key().as_ref());
+        let stc = StakeCredential::AddrKeyhash(Hash::new([7; 28]));
+        let mut cert_state: CertState = mk_drep_cert_state(&drc);
+        cert_state
+            .dstate
+            .vote_delegations
+            .insert(stc, DRep::Key(drep_hash));
+        let input: TransactionInput = mk_genesis_input();
+        let (_, tx) = mk_signed_tx_with(&key, input.clone(), 19_802_000, |tx_body| {
+            tx_body.certificates =
+                NonEmptySet::from_vec(vec![Certificate::UnRegDRepCert(drc.clone(), 2000)]);
+        });
+        let utxos: UTxOs = mk_signed_tx_utxos(&key, input, 20_000_000);
+        match validate_signed_tx(tx, &utxos, &mut cert_state) {
+            Ok(()) => (),
+            Err(err) => assert!(false, "Unexpected error ({:?})", err),
+        }
+        assert!(!cert_state.vstate.dreps.contains_key(&drc));
+        assert!(cert_state.dstate.vote_delegations.is_empty());
+    }
+
+    #[test]
+    // The refund differs from the deposit paid when the DRep was registered.
+    fn incorrect_drep_refund() {
+        let key: SecretKey = SecretKey::from([3; 32]);
+        let drc: StakeCredential = mk_stake_credential(&key);
+        let mut cert_state: CertState = mk_drep_cert_state(&drc);
+        let input: TransactionInput = mk_genesis_input();
+        let (_, tx) = mk_signed_tx_with(&key, input.clone(), 19_801_999, |tx_body| {
+            tx_body.certificates =
+                NonEmptySet::from_vec(vec![Certificate::UnRegDRepCert(drc.clone(), 1999)]);
+        });
+        let utxos: UTxOs = mk_signed_tx_utxos(&key, input, 20_000_000);
+        match validate_signed_tx(tx, &utxos, &mut cert_state) {
+            Ok(()) => assert!(false, "DRep refund should match its deposit"),
+            Err(err) => match err {
+                PostAlonzo(PostAlonzoError::IncorrectDRepRefund) => (),
+                _ => assert!(false, "Unexpected error ({:?})", err),
+            },
+        }
+        assert!(cert_state.vstate.dreps.contains_key(&drc));
+    }
+
+    #[test]
+    // A registered DRep votes on a known governance action, which records the
+    // vote and renews the expiry of the DRep. The block is in epoch 365.
+    fn successful_drep_vote() {
+        let key: SecretKey = SecretKey::from([3; 32]);
+        let drc: StakeCredential = mk_stake_credential(&key);
+        let drep_hash = Hasher::<224>::hash(key.public_key().as_ref());
+        let input: TransactionInput = mk_genesis_input();
+        let action_id = GovActionId {
+            transaction_id: Hash::new([1; 32]),
+            action_index: 0,
+        };
+        let (_, tx) = mk_signed_tx_with(&key, input.clone(), 19_800_000, |tx_body| {
+            tx_body.voting_procedures = Some(BTreeMap::from([(
+                Voter::DRepKey(drep_hash),
+                BTreeMap::from([(
+                    action_id.clone(),
+                    VotingProcedure {
+                        vote: Vote::Yes,
+                        anchor: None,
+                    },
+                )]),
+            )]));
+        });
+        let utxos: UTxOs = mk_signed_tx_utxos(&key, input, 20_000_000);
+        let mut cert_state: CertState = CertState::default();
+        let mut gov_state: GovState = GovState::default();
+        cert_state.vstate.dreps.insert(
+            drc.clone(),
+            DRepState {
+                deposit: 500000000,
+                anchor: None,
+                expiry: 370,
+            },
+        );
+        gov_state.proposals.insert(
+            action_id.clone(),
+            mk_gov_action_state(GovAction::Information),
+        );
+        match validate_signed_tx_with(tx, &utxos, &mut cert_state, &mut gov_state) {
+            Ok(()) => (),
+            Err(err) => assert!(false, "Unexpected error ({:?})", err),
+        }
+        assert_eq!(
+            gov_state.proposals[&action_id].votes,
+            BTreeMap::from([(Voter::DRepKey(drep_hash), Vote::Yes)])
+        );
+        assert_eq!(cert_state.vstate.dreps[&drc].expiry, 365 + 60);
+    }
+
+    #[test]
+    // The proposal is added to the governance state under the id of the tx,
+    // and expires after the validity period of the protocol parameters.
+    fn successful_proposal() {
+        let key: SecretKey = SecretKey::from([3; 32]);
+        let input: TransactionInput = mk_genesis_input();
+        let proposal: ProposalProcedure = mk_proposal(1000, GovAction::Information);
+        let (tx_hash, tx) = mk_signed_tx_with(&key, input.clone(), 19_799_000, |tx_body| {
+            tx_body.proposal_procedures = NonEmptySet::from_vec(vec![proposal.clone()]);
+        });
+        let utxos: UTxOs = mk_signed_tx_utxos(&key, input, 20_000_000);
+        let mut cert_state: CertState = CertState::default();
+        let mut gov_state: GovState = GovState::default();
+        match validate_signed_tx_with(tx, &utxos, &mut cert_state, &mut gov_state) {
+            Ok(()) => (),
+            Err(err) => assert!(false, "Unexpected error ({:?})", err),
+        }
+        let action_id = GovActionId {
+            transaction_id: tx_hash,
+            action_index: 0,
+        };
+        let state: &GovActionState = &gov_state.proposals[&action_id];
+        assert_eq!(state.proposal, proposal);
+        assert_eq!(state.proposed_in, 365);
+        assert_eq!(state.expires_after, 365 + 3600);
+        assert!(state.votes.is_empty());
+    }
+
+    #[test]
+    // Same as successful_mainnet_tx, except that a registered DRep votes on a
+    // governance action which is not in the governance state.
+    fn vote_on_unknown_gov_action() {
+        let drep_hash = Hash::new([7; 28]);
+        let mut cert_state: CertState = CertState::default();
+        let mut gov_state: GovState = GovState::default();
+        cert_state.vstate.dreps.insert(
+            StakeCredential::AddrKeyhash(drep_hash),
+            DRepState {
+                deposit: 500000000,
+                anchor: None,
+                expiry: 600,
+            },
+        );
+        let action_id = GovActionId {
+            transaction_id: Hash::new([1; 32]),
+            action_index: 0,
+        };
+        match validate_modified_conway3(
+            |tx_body| {
+                tx_body.voting_procedures = Some(BTreeMap::from([(
+                    Voter::DRepKey(drep_hash),
+                    BTreeMap::from([(
+                        action_id,
+                        VotingProcedure {
+                            vote: Vote::Yes,
+                            anchor: None,
+                        },
+                    )]),
+                )]))
+            },
+            &mut cert_state,
+            &mut gov_state,
+        ) {
+            Ok(()) => assert!(false, "Votes should refer to known governance actions"),
+            Err(err) => match err {
+                PostAlonzo(PostAlonzoError::GovActionsDoNotExist) => (),
+                _ => assert!(false, "Unexpected error ({:?})", err),
+            },
+        }
+    }
+
+    #[test]
+    // Same as successful_mainnet_tx, except that a constitutional committee
+    // member votes on a no-confidence motion.
+    fn committee_votes_on_no_confidence() {
+        let cold = StakeCredential::AddrKeyhash(Hash::new([7; 28]));
+        let hot_hash = Hash::new([8; 28]);
+        let mut cert_state: CertState = CertState::default();
+        let mut gov_state: GovState = GovState {
+            committee: Some(mk_committee(std::slice::from_ref(&cold))),
+            ..GovState::default()
+        };
+        cert_state.vstate.committee_state.insert(
+            cold,
+            CommitteeAuthorization::HotCredential(StakeCredential::AddrKeyhash(hot_hash)),
+        );
+        let action_id = GovActionId {
+            transaction_id: Hash::new([1; 32]),
+            action_index: 0,
+        };
+        gov_state.proposals.insert(
+            action_id.clone(),
+            mk_gov_action_state(GovAction::NoConfidence(None)),
+        );
+        match validate_modified_conway3(
+            |tx_body| {
+                tx_body.voting_procedures = Some(BTreeMap::from([(
+                    Voter::ConstitutionalCommitteeKey(hot_hash),
+                    BTreeMap::from([(
+                        action_id,
+                        VotingProcedure {
+                            vote: Vote::No,
+                            anchor: None,
+                        },
+                    )]),
+                )]))
+            },
+            &mut cert_state,
+            &mut gov_state,
+        ) {
+            Ok(()) => assert!(false, "Committee cannot vote on no-confidence motions"),
+            Err(err) => match err {
+                PostAlonzo(PostAlonzoError::DisallowedVoter) => (),
+                _ => assert!(false, "Unexpected error ({:?})", err),
+            },
+        }
+    }
+
+    #[test]
+    // Same as successful_mainnet_tx, except that the transaction submits a
+    // proposal whose deposit differs from the one in the protocol parameters.
+    fn proposal_deposit_incorrect() {
+        let mut cert_state: CertState = CertState::default();
+        let mut gov_state: GovState = GovState::default();
+        match validate_modified_conway3(
+            |tx_body| {
+                tx_body.proposal_procedures =
+                    NonEmptySet::from_vec(vec![mk_proposal(1, GovAction::Information)])
+            },
+            &mut cert_state,
+            &mut gov_state,
+        ) {
+            Ok(()) => assert!(
+                false,
+                "Proposal deposit should match the protocol parameters"
+            ),
+            Err(err) => match err {
+                PostAlonzo(PostAlonzoError::ProposalDepositIncorrect) => (),
+                _ => assert!(false, "Unexpected error ({:?})", err),
+            },
+        }
+    }
+
+    #[test]
+    // Same as successful_mainnet_tx, except that the transaction proposes a
+    // treasury withdrawal without the guardrails script of the constitution.
+    fn invalid_policy_hash() {
+        let mut cert_state: CertState = CertState::default();
+        let mut gov_state: GovState = GovState {
+            constitution: Some(Constitution {
+                anchor: Anchor {
+                    url: String::from("https://example.com/constitution.txt"),
+                    content_hash: Hash::new([0; 32]),
+                },
+                guardrail_script: Some(Hash::new([9; 28])),
+            }),
+            ..GovState::default()
+        };
+        let mut reward_account: Vec<u8> = vec![0xe1];
+        reward_account.extend([8; 28]);
+        let withdrawals = BTreeMap::from([(reward_account.into(), 1000000)]);
+        match validate_modified_conway3(
+            |tx_body| {
+                tx_body.proposal_procedures = NonEmptySet::from_vec(vec![mk_proposal(
+                    1000,
+                    GovAction::TreasuryWithdrawals(withdrawals, None),
+                )])
+            },
+            &mut cert_state,
+            &mut gov_state,
+        ) {
+            Ok(()) => assert!(false, "Proposal should carry the guardrails script hash"),
+            Err(err) => match err {
+                PostAlonzo(PostAlonzoError::InvalidPolicyHash) => (),
+                _ => assert!(false, "Unexpected error ({:?})", err),
+            },
+        }
+    }
+
+    #[test]
+    fn successful_block_body() {
+        let cbor_bytes: Vec<u8> = cbor_to_bytes(include_str!("../../test_data/conway1.block"));
+        let block: MultiEraBlock = MultiEraBlock::decode(&cbor_bytes).unwrap();
+        // The parameters predate the Conway hard fork, so the protocol version is
+        // bumped to allow for the version announced by the header.
+        let prot_pps = MultiEraProtocolParameters::Conway(ConwayProtParams {
+            protocol_version: (9, 0),
+            ..mk_mainnet_params_epoch_365()
+        });
+        match validate_block_body(&block, &prot_pps) {
+            Ok(()) => (),
+            Err(err) => assert!(false, "Unexpected error ({:?})", err),
+        }
+    }
+
+    #[test]
+    // The second tx of the block spends the output created by the first one.
+    fn successful_block_with_chained_txs() {
+        let key: SecretKey = SecretKey::from([3; 32]);
+        let genesis_input = TransactionInput {
+            transaction_id: Hash::new([1; 32]),
+            index: 0,
+        };
+        let (first_hash, first) = mk_signed_tx(&key, genesis_input.clone(), 19_800_000);
+        let first_input = TransactionInput {
+            transaction_id: first_hash,
+            index: 0,
+        };
+        let (second_hash, second) = mk_signed_tx(&key, first_input, 19_600_000);
+        let cbor_bytes: Vec<u8> = mk_conway_block(vec![first, second], vec![], None);
+        let block: MultiEraBlock = MultiEraBlock::decode_conway(&cbor_bytes).unwrap();
+        let utxos: UTxOs = mk_signed_tx_utxos(&key, genesis_input, 20_000_000);
+        let mut cert_state: CertState = CertState::default();
+        let mut gov_state: GovState = GovState::default();
+        match validate_block(
+            &block,
+            &mk_block_env(),
+            &utxos,
+            &mut cert_state,
+            &mut gov_state,
+        ) {
+            Ok(delta) => {
+                let consumed: Vec<&TxoRef> = delta.consumed.keys().collect();
+                assert_eq!(consumed, vec![&TxoRef(Hash::new([1; 32]), 0)]);
+                let produced: Vec<&TxoRef> = delta.produced.keys().collect();
+                assert_eq!(produced, vec![&TxoRef(second_hash, 0)]);
+            }
+            Err(err) => assert!(false, "Unexpected error ({:?})", err),
+        }
+    }
+
+    #[test]
+    // Both txs of the block spend the same output.
+    fn block_with_double_spend() {
+        let key: SecretKey = SecretKey::from([3; 32]);
+        let genesis_input = TransactionInput {
+            transaction_id: Hash::new([1; 32]),
+            index: 0,
+        };
+        let (_, first) = mk_signed_tx(&key, genesis_input.clone(), 19_800_000);
+        let (_, second) = mk_signed_tx(&key, genesis_input.clone(), 19_700_000);
+        let cbor_bytes: Vec<u8> = mk_conway_block(vec![first, second], vec![], None);
+        let block: MultiEraBlock = MultiEraBlock::decode_conway(&cbor_bytes).unwrap();
+        let utxos: UTxOs = mk_signed_tx_utxos(&key, genesis_input, 20_000_000);
+        let mut cert_state: CertState = CertState::default();
+        let mut gov_state: GovState = GovState::default();
+        match validate_block(
+            &block,
+            &mk_block_env(),
+            &utxos,
+            &mut cert_state,
+            &mut gov_state,
+        ) {
+            Ok(_) => assert!(false, "Outputs cannot be spent twice within a block"),
+            Err(err) => match err {
+                PostAlonzo(PostAlonzoError::InputNotInUTxO) => (),
+                _ => assert!(false, "Unexpected error ({:?})", err),
+            },
+        }
+    }
+
+    #[test]
+    // The header of the block declares a body hash that does not match its
+    // contents.
+    fn wrong_block_body_hash() {
+        let key: SecretKey = SecretKey::from([3; 32]);
+        let genesis_input = TransactionInput {
+            transaction_id: Hash::new([1; 32]),
+            index: 0,
+        };
+        let (_, tx) = mk_signed_tx(&key, genesis_input.clone(), 19_800_000);
+        let cbor_bytes: Vec<u8> = mk_conway_block(vec![tx], vec![], Some(Hash::new([0; 32])));
+        let block: MultiEraBlock = MultiEraBlock::decode_conway(&cbor_bytes).unwrap();
+        let utxos: UTxOs = mk_signed_tx_utxos(&key, genesis_input, 20_000_000);
+        let mut cert_state: CertState = CertState::default();
+        let mut gov_state: GovState = GovState::default();
+        match validate_block(
+            &block,
+            &mk_block_env(),
+            &utxos,
+            &mut cert_state,
+            &mut gov_state,
+        ) {
+            Ok(_) => assert!(false, "Block body hash should match the header"),
+            Err(err) => match err {
+                Block(BlockError::WrongBlockBodyHash) => (),
+                _ => assert!(false, "Unexpected error ({:?})", err),
+            },
+        }
+    }
+
+    #[test]
+    // The only tx of the block is flagged as invalid, so it consumes its
+    // collateral and produces its collateral return instead of spending its
+    // regular input.
+    fn block_with_invalid_tx() {
+        let key: SecretKey = SecretKey::from([3; 32]);
+        let genesis_input = TransactionInput {
+            transaction_id: Hash::new([1; 32]),
+            index: 0,
+        };
+        let collateral_input = TransactionInput {
+            transaction_id: Hash::new([2; 32]),
+            index: 0,
+        };
+        let (tx_hash, tx) = mk_signed_tx_with(&key, genesis_input.clone(), 19_800_000, |tx_body| {
+            tx_body.collateral = NonEmptySet::from_vec(vec![collateral_input.clone()]);
+            tx_body.collateral_return = Some(TransactionOutput::PostAlonzo(
+                PostAlonzoTransactionOutput {
+                    address: mk_enterprise_address(&key),
+                    value: Value::Coin(4_700_000),
+                    datum_option: None,
+                    script_ref: None,
+                }
+                .into(),
+            ));
+            tx_body.total_collateral = Some(300_000);
+        });
+        let cbor_bytes: Vec<u8> = mk_conway_block(vec![tx], vec![0], None);
+        let block: MultiEraBlock = MultiEraBlock::decode_conway(&cbor_bytes).unwrap();
+        let mut utxos: UTxOs = mk_signed_tx_utxos(&key, genesis_input, 20_000_000);
+        utxos.extend(mk_signed_tx_utxos(&key, collateral_input, 5_000_000));
+        let mut cert_state: CertState = CertState::default();
+        let mut gov_state: GovState = GovState::default();
+        match validate_block(
+            &block,
+            &mk_block_env(),
+            &utxos,
+            &mut cert_state,
+            &mut gov_state,
+        ) {
+            Ok(delta) => {
+                let consumed: Vec<&TxoRef> = delta.consumed.keys().collect();
+                assert_eq!(consumed, vec![&TxoRef(Hash::new([2; 32]), 0)]);
+                let produced: Vec<&TxoRef> = delta.produced.keys().collect();
+                assert_eq!(produced, vec![&TxoRef(tx_hash, 1)]);
+            }
+            Err(err) => assert!(false, "Unexpected error ({:?})", err),
+        }
+    }
+
+    #[test]
+    // The redeemers of the block ask for more memory than a block may use.
+    fn block_ex_units_exceeded() {
+        let key: SecretKey = SecretKey::from([3; 32]);
+        let (_, (tx_body, mut wits)) = mk_signed_tx(&key, mk_genesis_input(), 19_800_000);
+        let redeemer = Redeemer {
+            tag: RedeemerTag::Spend,
+            index: 0,
+            data: PlutusData::Array(MaybeIndefArray::Def(vec![])),
+            ex_units: ExUnits {
+                mem: 62_000_001,
+                steps: 1,
+            },
+        };
+        wits.redeemer = Some(Redeemers::List(vec![redeemer]).into());
+        let cbor_bytes: Vec<u8> = mk_conway_block(vec![(tx_body, wits)], vec![], None);
+        let block: MultiEraBlock = MultiEraBlock::decode_conway(&cbor_bytes).unwrap();
+        match validate_block_body(&block, mk_block_env().prot_params()) {
+            Ok(()) => assert!(false, "Block ExUnits should not exceed the maximum"),
+            Err(err) => match err {
+                Block(BlockError::BlockExUnitsExceeded) => (),
+                _ => assert!(false, "Unexpected error ({:?})", err),
+            },
+        }
+    }
+
+    #[test]
+    // The environment describes a slot other than that of the block.
+    fn wrong_block_slot() {
+        let key: SecretKey = SecretKey::from([3; 32]);
+        let genesis_input: TransactionInput = mk_genesis_input();
+        let (_, tx) = mk_signed_tx(&key, genesis_input.clone(), 19_800_000);
+        let cbor_bytes: Vec<u8> = mk_conway_block(vec![tx], vec![], None);
+        let block: MultiEraBlock = MultiEraBlock::decode_conway(&cbor_bytes).unwrap();
+        let utxos: UTxOs = mk_signed_tx_utxos(&key, genesis_input, 20_000_000);
+        let env: Environment = Environment {
+            block_slot: 72316897,
+            ..mk_block_env()
+        };
+        match validate_block(
+            &block,
+            &env,
+            &utxos,
+            &mut CertState::default(),
+            &mut GovState::default(),
+        ) {
+            Ok(_) => assert!(false, "Block slot should match the environment"),
+            Err(err) => match err {
+                Block(BlockError::WrongBlockSlot) => (),
+                _ => assert!(false, "Unexpected error ({:?})", err),
+            },
+        }
+    }
+
+    #[test]
+    // Transaction hash:
+    // 90bd64b133e327daecfa0cc60c26f3b96fc6f0285a6d96cc122819908b3aaf93
+    fn utxo_delta_apply_and_undo() {
+        let cbor_bytes: Vec<u8> = cbor_to_bytes(include_str!("../../test_data/conway3.tx"));
+        let mtx: Tx = conway_minted_tx_from_cbor(&cbor_bytes);
+        let metx: MultiEraTx = MultiEraTx::from_conway(&mtx);
+        let tx_outs_info: &[(
+            String,
+            Value,
+            Option<DatumOption>,
+            Option<CborWrap<ScriptRef>>,
+        )] = &[(
+            String::from("015c5c318d01f729e205c95eb1b02d623dd10e78ea58f72d0c13f892b2e8904edc699e2f0ce7b72be7cec991df651a222e2ae9244eb5975cba"),
+            Value::Coin(20000000),
+            None,
+            None,
+        )];
+        let original: UtxoMap =
+            mk_utxo_for_eval(mk_utxo_for_conway_tx(&mtx.transaction_body, tx_outs_info));
+        let delta: UtxoDelta = UtxoDelta::from_tx(&metx, &original);
+        assert_eq!(delta.consumed, original);
+        assert_eq!(delta.produced.len(), mtx.transaction_body.outputs.len());
+
+        let mut utxos: UtxoMap = original.clone();
+        delta.apply(&mut utxos);
+        assert_eq!(utxos, delta.produced);
+        delta.undo(&mut utxos);
+        assert_eq!(utxos, original);
+    }
+
+    #[test]
+    // Same as utxo_delta_apply_and_undo, except that the transaction is flagged
+    // as invalid and carries a collateral input and a collateral return.
+    fn utxo_delta_of_invalid_tx() {
+        let cbor_bytes: Vec<u8> = cbor_to_bytes(include_str!("../../test_data/conway3.tx"));
+        let mut mtx: Tx = conway_minted_tx_from_cbor(&cbor_bytes);
+        let tx_outs_info: &[(
+            String,
+            Value,
+            Option<DatumOption>,
+            Option<CborWrap<ScriptRef>>,
+        )] = &[(
+            String::from("015c5c318d01f729e205c95eb1b02d623dd10e78ea58f72d0c13f892b2e8904edc699e2f0ce7b72be7cec991df651a222e2ae9244eb5975cba"),
+            Value::Coin(20000000),
+            None,
+            None,
+        )];
+        let mut utxos: UTxOs = mk_utxo_for_conway_tx(&mtx.transaction_body, tx_outs_info);
+        let collateral_info: &[(
+            String,
+            Value,
+            Option<DatumOption>,
+            Option<CborWrap<ScriptRef>>,
+        )] = &[(
+            String::from("015c5c318d01f729e205c95eb1b02d623dd10e78ea58f72d0c13f892b2e8904edc699e2f0ce7b72be7cec991df651a222e2ae9244eb5975cba"),
+            Value::Coin(5000000),
+            None,
+            None,
+        )];
+        let mut tx_body: TransactionBody = (*mtx.transaction_body).clone();
+        tx_body.collateral = NonEmptySet::from_vec(vec![TransactionInput {
+            transaction_id: Hash::new([7; 32]),
+            index: 0,
+        }]);
+        tx_body.collateral_return = Some(tx_body.outputs[0].clone());
+        add_collateral_conway(&tx_body, &mut utxos, collateral_info);
+        let mut tx_buf: Vec<u8> = Vec::new();
+        let _ = encode(tx_body, &mut tx_buf);
+        mtx.transaction_body =
+            Decode::decode(&mut Decoder::new(tx_buf.as_slice()), &mut ()).unwrap();
+        mtx.success = false;
+        let metx: MultiEraTx = MultiEraTx::from_conway(&mtx);
+        let utxos: UtxoMap = mk_utxo_for_eval(utxos);
+
+        let delta: UtxoDelta = UtxoDelta::from_tx(&metx, &utxos);
+        let consumed: Vec<&TxoRef> = delta.consumed.keys().collect();
+        assert_eq!(consumed, vec![&TxoRef(Hash::new([7; 32]), 0)]);
+        let produced: Vec<&TxoRef> = delta.produced.keys().collect();
+        let return_index: u32 = mtx.transaction_body.outputs.len() as u32;
+        assert_eq!(produced, vec![&TxoRef(metx.hash(), return_index)]);
+    }
+
+    #[test]
+    // The epoch nonce of the block is not at hand, so the header is expected to
+    // go through the operational certificate and KES checks and to stop at the
+    // VRF proof.
+    fn header_ocert_and_kes_signature() {
+        let cbor_bytes: Vec<u8> = cbor_to_bytes(include_str!("../../test_data/conway1.block"));
+        let block: MultiEraBlock = MultiEraBlock::decode(&cbor_bytes).unwrap();
+        let header: MultiEraHeader = block.header();
+        let env: PraosEnvironment = mk_praos_env(&header);
+        let mut ocert_counters: OcertCounters = OcertCounters::new();
+        match validate_header(&header, &env, &mut ocert_counters) {
+            Ok(()) => assert!(false, "Wrong epoch nonce"),
+            Err(err) => match err {
+                Header(HeaderError::VrfBadProof) => (),
+                _ => assert!(false, "Unexpected error ({:?})", err),
+            },
+        }
+        assert!(ocert_counters.is_empty());
+    }
+
+    #[test]
+    // The headers of the test data are issued in epochs whose nonce is not at
+    // hand, so the valid headers are issued here with known cold, KES and VRF
+    // keys.
+    fn header_valid_updates_opcert_counter() {
+        let env: PraosEnvironment = mk_issuer_env();
+        let pool_id: Hash<28> = Hasher::<224>::hash(mk_issuer_cold_key().public_key().as_ref());
+        let mut ocert_counters: OcertCounters = OcertCounters::new();
+        let header_cbor: Vec<u8> = mk_issued_header(1, &env.epoch_nonce);
+        let header: MultiEraHeader = MultiEraHeader::decode(6, None, &header_cbor).unwrap();
+        match validate_header(&header, &env, &mut ocert_counters) {
+            Ok(()) => (),
+            Err(err) => assert!(false, "Unexpected error ({:?})", err),
+        }
+        assert_eq!(ocert_counters.get(&pool_id), Some(&1));
+        // the same certificate can be used again
+        match validate_header(&header, &env, &mut ocert_counters) {
+            Ok(()) => (),
+            Err(err) => assert!(false, "Unexpected error ({:?})", err),
+        }
+        assert_eq!(ocert_counters.get(&pool_id), Some(&1));
+        let header_cbor: Vec<u8> = mk_issued_header(3, &env.epoch_nonce);
+        let header: MultiEraHeader = MultiEraHeader::decode(6, None, &header_cbor).unwrap();
+        match validate_header(&header, &env, &mut ocert_counters) {
+            Ok(()) => assert!(false, "Operational certificate counter is over-incremented"),
+            Err(err) => match err {
+                Header(HeaderError::OpCertCounterOverIncremented) => (),
+                _ => assert!(false, "Unexpected error ({:?})", err),
+            },
+        }
+        assert_eq!(ocert_counters.get(&pool_id), Some(&1));
+        let header_cbor: Vec<u8> = mk_issued_header(2, &env.epoch_nonce);
+        let header: MultiEraHeader = MultiEraHeader::decode(6, None, &header_cbor).unwrap();
+        match validate_header(&header, &env, &mut ocert_counters) {
+            Ok(()) => (),
+            Err(err) => assert!(false, "Unexpected error ({:?})", err),
+        }
+        assert_eq!(ocert_counters.get(&pool_id), Some(&2));
+        let header_cbor: Vec<u8> = mk_issued_header(1, &env.epoch_nonce);
+        let header: MultiEraHeader = MultiEraHeader::decode(6, None, &header_cbor).unwrap();
+        match validate_header(&header, &env, &mut ocert_counters) {
+            Ok(()) => assert!(false, "Operational certificate counter is too small"),
+            Err(err) => match err {
+                Header(HeaderError::OpCertCounterTooSmall) => (),
+                _ => assert!(false, "Unexpected error ({:?})", err),
+            },
+        }
+        assert_eq!(ocert_counters.get(&pool_id), Some(&2));
+    }
+
+    #[test]
+    fn header_issued_for_another_nonce() {
+        let env: PraosEnvironment = mk_issuer_env();
+        let header_cbor: Vec<u8> = mk_issued_header(0, &Hash::new([5; 32]));
+        let header: MultiEraHeader = MultiEraHeader::decode(6, None, &header_cbor).unwrap();
+        let mut ocert_counters: OcertCounters = OcertCounters::new();
+        match validate_header(&header, &env, &mut ocert_counters) {
+            Ok(()) => assert!(false, "Wrong epoch nonce"),
+            Err(err) => match err {
+                Header(HeaderError::VrfBadProof) => (),
+                _ => assert!(false, "Unexpected error ({:?})", err),
+            },
+        }
+        assert!(ocert_counters.is_empty());
+    }
+
+    #[test]
+    // The KES period of the header is computed from the slot, so a different
+    // number of slots per KES period selects another key of the KES tree.
+    fn header_wrong_kes_period() {
+        let cbor_bytes: Vec<u8> = cbor_to_bytes(include_str!("../../test_data/conway1.block"));
+        let block: MultiEraBlock = MultiEraBlock::decode(&cbor_bytes).unwrap();
+        let header: MultiEraHeader = block.header();
+        let mut env: PraosEnvironment = mk_praos_env(&header);
+        env.slots_per_kes_period = header.slot() / (header_kes_period(&header) + 1);
+        match validate_header(&header, &env, &mut OcertCounters::new()) {
+            Ok(()) => assert!(false, "Wrong KES period"),
+            Err(err) => match err {
+                Header(HeaderError::InvalidKesSignature) => (),
+                _ => assert!(false, "Unexpected error ({:?})", err),
+            },
+        }
+        env.slots_per_kes_period = header.slot() / header_kes_period(&header) * 2;
+        match validate_header(&header, &env, &mut OcertCounters::new()) {
+            Ok(()) => assert!(false, "KES period before the operational certificate"),
+            Err(err) => match err {
+                Header(HeaderError::KesBeforeStart) => (),
+                _ => assert!(false, "Unexpected error ({:?})", err),
+            },
+        }
+        env.slots_per_kes_period = 129600;
+        env.max_kes_evolutions = 0;
+        match validate_header(&header, &env, &mut OcertCounters::new()) {
+            Ok(()) => assert!(false, "KES period after the operational certificate"),
+            Err(err) => match err {
+                Header(HeaderError::KesAfterEnd) => (),
+                _ => assert!(false, "Unexpected error ({:?})", err),
+            },
+        }
+    }
+
+    #[test]
+    // The operational certificate of babbage4.block has a counter of 4.
+    fn header_opcert_counter() {
+        let cbor_bytes: Vec<u8> = cbor_to_bytes(include_str!("../../test_data/babbage4.block"));
+        let block: MultiEraBlock = MultiEraBlock::decode(&cbor_bytes).unwrap();
+        let header: MultiEraHeader = block.header();
+        let env: PraosEnvironment = mk_praos_env(&header);
+        let pool_id: Hash<28> = header_pool_id(&header);
+        let counter: u64 = header
+            .as_babbage()
+            .unwrap()
+            .header_body
+            .operational_cert
+            .operational_cert_sequence_number;
+        let mut ocert_counters: OcertCounters = OcertCounters::from([(pool_id, counter + 1)]);
+        match validate_header(&header, &env, &mut ocert_counters) {
+            Ok(()) => assert!(false, "Operational certificate counter is too small"),
+            Err(err) => match err {
+                Header(HeaderError::OpCertCounterTooSmall) => (),
+                _ => assert!(false, "Unexpected error ({:?})", err),
+            },
+        }
+        let mut ocert_counters: OcertCounters = OcertCounters::from([(pool_id, counter - 2)]);
+        match validate_header(&header, &env, &mut ocert_counters) {
+            Ok(()) => assert!(false, "Operational certificate counter is over-incremented"),
+            Err(err) => match err {
+                Header(HeaderError::OpCertCounterOverIncremented) => (),
+                _ => assert!(false, "Unexpected error ({:?})", err),
+            },
+        }
+        let env: PraosEnvironment = PraosEnvironment {
+            pool_distr: HashMap::new(),
+            ..mk_praos_env(&header)
+        };
+        match validate_header(&header, &env, &mut OcertCounters::new()) {
+            Ok(()) => assert!(false, "Issuer is not in the stake distribution"),
+            Err(err) => match err {
+                Header(HeaderError::NoOpCertCounter) => (),
+                _ => assert!(false, "Unexpected error ({:?})", err),
+            },
+        }
+    }
+
+    #[test]
+    fn header_wrong_vrf_key() {
+        let cbor_bytes: Vec<u8> = cbor_to_bytes(include_str!("../../test_data/conway1.block"));
+        let block: MultiEraBlock = MultiEraBlock::decode(&cbor_bytes).unwrap();
+        let header: MultiEraHeader = block.header();
+        let mut env: PraosEnvironment = mk_praos_env(&header);
+        for pool_stake in env.pool_distr.values_mut() {
+            pool_stake.vrf_keyhash = Hash::new([0; 32]);
+        }
+        match validate_header(&header, &env, &mut OcertCounters::new()) {
+            Ok(()) => assert!(false, "VRF key is not the one registered by the pool"),
+            Err(err) => match err {
+                Header(HeaderError::VrfKeyWrongHash) => (),
+                _ => assert!(false, "Unexpected error ({:?})", err),
+            },
+        }
+    }
+
+    #[test]
+    fn slot_leader_threshold() {
+        let f: RationalNumber = RationalNumber {
+            numerator: 1,
+            denominator: 20,
+        };
+        let half_stake: RationalNumber = RationalNumber {
+            numerator: 1,
+            denominator: 2,
+        };
+        let no_stake: RationalNumber = RationalNumber {
+            numerator: 0,
+            denominator: 1,
+        };
+        // 1 - (1 - 1/20)^(1/2) is about 0.0253
+        let mut leader_value: [u8; 32] = [0; 32];
+        leader_value[0] = 0x06;
+        assert!(is_slot_leader(&leader_value, &half_stake, &f));
+        leader_value[0] = 0x07;
+        assert!(!is_slot_leader(&leader_value, &half_stake, &f));
+        assert!(!is_slot_leader(&[0; 32], &no_stake, &f));
+        assert!(is_slot_leader(
+            &[0xff; 32],
+            &no_stake,
+            &RationalNumber {
+                numerator: 1,
+                denominator: 1
+            }
+        ));
+    }
+
+    // Validates conway3.tx after applying `modify` to its body. Checks occurring
+    // after value preservation (such as the witness checks) are not reached
+    // unless the modification is value-neutral.
+    fn validate_modified_conway3(
+        modify: impl FnOnce(&mut TransactionBody),
+        cert_state: &mut CertState,
+        gov_state: &mut GovState,
+    ) -> ValidationResult {
+        let cbor_bytes: Vec<u8> = cbor_to_bytes(include_str!("../../test_data/conway3.tx"));
+        let mut mtx: Tx = conway_minted_tx_from_cbor(&cbor_bytes);
+        let tx_outs_info: &[(
+            String,
+            Value,
+            Option<DatumOption>,
+            Option<CborWrap<ScriptRef>>,
+        )] = &[(
+            String::from("015c5c318d01f729e205c95eb1b02d623dd10e78ea58f72d0c13f892b2e8904edc699e2f0ce7b72be7cec991df651a222e2ae9244eb5975cba"),
+            Value::Coin(20000000),
+            None,
+            None,
+        )];
+        let utxos: UTxOs = mk_utxo_for_conway_tx(&mtx.transaction_body, tx_outs_info);
+        let mut tx_body: TransactionBody = (*mtx.transaction_body).clone();
+        modify(&mut tx_body);
+        let mut tx_buf: Vec<u8> = Vec::new();
+        let _ = encode(tx_body, &mut tx_buf);
+        mtx.transaction_body =
+            Decode::decode(&mut Decoder::new(tx_buf.as_slice()), &mut ()).unwrap();
+        let metx: MultiEraTx = MultiEraTx::from_conway(&mtx);
+        let acnt = AccountState {
+            treasury: 261_254_564_000_000,
+            reserves: 0,
+        };
+
+        let env: Environment = Environment {
+            prot_params: MultiEraProtocolParameters::Conway(mk_mainnet_params_epoch_365()),
+            prot_magic: 764824073,
+            block_slot: 72316896,
+            network_id: 1,
+            acnt: Some(acnt),
+        };
+        validate_txs(&[metx], &env, &utxos, cert_state, gov_state)
+    }
+
+    fn mk_committee(members: &[StakeCredential]) -> Committee {
+        Committee {
+            members: members.iter().map(|cold| (cold.clone(), 1000)).collect(),
+            threshold: RationalNumber {
+                numerator: 2,
+                denominator: 3,
+            },
+        }
+    }
+
+    fn mk_gov_action_state(gov_action: GovAction) -> GovActionState {
+        GovActionState {
+            proposal: mk_proposal(1000, gov_action),
+            proposed_in: 500,
+            expires_after: 506,
+            votes: BTreeMap::new(),
+        }
+    }
+
+    fn mk_proposal(deposit: u64, gov_action: GovAction) -> ProposalProcedure {
+        let mut reward_account: Vec<u8> = vec![0xe1];
+        reward_account.extend([7; 28]);
+        ProposalProcedure {
+            deposit,
+            reward_account: reward_account.into(),
+            gov_action,
+            anchor: Anchor {
+                url: String::from("https://example.com/proposal.json"),
+                content_hash: Hash::new([0; 32]),
+            },
+        }
+    }
+
+    fn mk_genesis_input() -> TransactionInput {
+        TransactionInput {
+            transaction_id: Hash::new([1; 32]),
+            index: 0,
+        }
+    }
+
+    fn mk_stake_credential(key: &SecretKey) -> StakeCredential {
+        StakeCredential::AddrKeyhash(Hasher::<224>::hash(key.public_key().as_ref()))
+    }
+
+    fn mk_reward_account(key: &SecretKey) -> Bytes {
+        let mut reward_account: Vec<u8> = vec![0xe1];
+        reward_account.extend(Hasher::<224>::hash(key.public_key().as_ref()).as_ref());
+        reward_account.into()
+    }
+
+    // Validates a tx built by mk_signed_tx_with in the environment of
+    // mk_block_env.
+    fn validate_signed_tx(
+        tx: (TransactionBody<'static>, WitnessSet<'static>),
+        utxos: &UTxOs,
+        cert_state: &mut CertState,
+    ) -> ValidationResult {
+        validate_signed_tx_with(tx, utxos, cert_state, &mut GovState::default())
+    }
+
+    // Same as validate_signed_tx, against the given governance state.
+    fn validate_signed_tx_with(
+        tx: (TransactionBody<'static>, WitnessSet<'static>),
+        utxos: &UTxOs,
+        cert_state: &mut CertState,
+        gov_state: &mut GovState,
+    ) -> ValidationResult {
+        let (tx_body, wits) = tx;
+        let tx = Tx {
+            transaction_body: tx_body.into(),
+            transaction_witness_set: wits.into(),
+            success: true,
+            auxiliary_data: Nullable::Null,
+        };
+        let cbor_bytes: Vec<u8> = minicbor::to_vec(&tx).unwrap();
+        let metx: MultiEraTx = MultiEraTx::decode_for_era(Era::Conway, &cbor_bytes).unwrap();
+        validate_txs(&[metx], &mk_block_env(), utxos, cert_state, gov_state)
+    }
+
+    // Registration of a pool operated and owned by `key`.
+    fn mk_pool_registration(key: &SecretKey, cost: u64) -> Certificate {
+        let operator = Hasher::<224>::hash(key.public_key().as_ref());
+        Certificate::PoolRegistration {
+            operator,
+            vrf_keyhash: Hash::new([9; 32]),
+            pledge: 0,
+            cost,
+            margin: RationalNumber {
+                numerator: 1,
+                denominator: 100,
+            },
+            reward_account: mk_reward_account(key),
+            pool_owners: Set::from(vec![operator]),
+            relays: vec![],
+            pool_metadata: None,
+        }
+    }
+
+    // Certificate state where the pool operated by `key` is registered.
+    fn mk_pool_cert_state(key: &SecretKey) -> CertState {
+        let operator = Hasher::<224>::hash(key.public_key().as_ref());
+        let mut cert_state: CertState = CertState::default();
+        cert_state.pstate.pool_params.insert(
+            operator,
+            PoolParam {
+                vrf_keyhash: Hash::new([9; 32]),
+                pledge: 0,
+                cost: 340_000_000,
+                margin: RationalNumber {
+                    numerator: 1,
+                    denominator: 100,
+                },
+                reward_account: mk_reward_account(key),
+                pool_owners: vec![operator],
+                relays: vec![],
+                pool_metadata: None,
+            },
+        );
+        cert_state
+    }
+
+    // Certificate state where `drc` is registered as a DRep with the deposit
+    // of the protocol parameters.
+    fn mk_drep_cert_state(drc: &StakeCredential) -> CertState {
+        let mut cert_state: CertState = CertState::default();
+        cert_state.vstate.dreps.insert(
+            drc.clone(),
+            DRepState {
+                deposit: 2000,
+                anchor: None,
+                expiry: 370,
+            },
+        );
+        cert_state
+    }
+
+    fn mk_enterprise_address(key: &SecretKey) -> Bytes {
+        let mut address: Vec<u8> = vec![0x61];
+        address.extend(Hasher::<224>::hash(key.public_key().as_ref()).as_ref());
+        address.into()
+    }
+
+    // Builds a tx signed by `key` that moves `input` to the enterprise address
+    // of `key`, keeping `amount` and paying the rest as fee.
+    fn mk_signed_tx(
+        key: &SecretKey,
+        input: TransactionInput,
+        amount: u64,
+    ) -> (Hash<32>, (TransactionBody<'static>, WitnessSet<'static>)) {
+        mk_signed_tx_with(key, input, amount, |_| ())
+    }
+
+    // Same as mk_signed_tx, with the body modified by `modify` before signing.
+    fn mk_signed_tx_with(
+        key: &SecretKey,
+        input: TransactionInput,
+        amount: u64,
+        modify: impl FnOnce(&mut TransactionBody<'static>),
+    ) -> (Hash<32>, (TransactionBody<'static>, WitnessSet<'static>)) {
+        let output = TransactionOutput::PostAlonzo(
+            PostAlonzoTransactionOutput {
+                address: mk_enterprise_address(key),
+                value: Value::Coin(amount),
+                datum_option: None,
+                script_ref: None,
+            }
+            .into(),
+        );
+        let mut tx_body = TransactionBody {
+            inputs: Set::from(vec![input]),
+            outputs: vec![output],
+            fee: 200_000,
+            ttl: None,
+            certificates: None,
+            withdrawals: None,
+            auxiliary_data_hash: None,
+            validity_interval_start: None,
+            mint: None,
+            script_data_hash: None,
+            collateral: None,
+            required_signers: None,
+            network_id: None,
+            collateral_return: None,
+            total_collateral: None,
+            reference_inputs: None,
+            voting_procedures: None,
+            proposal_procedures: None,
+            treasury_value: None,
+            donation: None,
+        };
+        modify(&mut tx_body);
+        let tx_hash: Hash<32> = Hasher::<256>::hash(&minicbor::to_vec(&tx_body).unwrap());
+        let witness = VKeyWitness {
+            vkey: Bytes::from(key.public_key().as_ref().to_vec()),
+            signature: Bytes::from(key.sign(tx_hash).as_ref().to_vec()),
+        };
+        let wits = WitnessSet {
+            vkeywitness: NonEmptySet::from_vec(vec![witness]),
+            native_script: None,
+            bootstrap_witness: None,
+            plutus_v1_script: None,
+            plutus_data: None,
+            redeemer: None,
+            plutus_v2_script: None,
+            plutus_v3_script: None,
+        };
+        (tx_hash, (tx_body, wits))
+    }
+
+    // UTxO set holding `input`, locked by the enterprise address of `key`.
+    fn mk_signed_tx_utxos<'a>(key: &SecretKey, input: TransactionInput, amount: u64) -> UTxOs<'a> {
+        let output = TransactionOutput::PostAlonzo(
+            PostAlonzoTransactionOutput {
+                address: mk_enterprise_address(key),
+                value: Value::Coin(amount),
+                datum_option: None,
+                script_ref: None,
+            }
+            .into(),
+        );
+        UTxOs::from([(
+            MultiEraInput::AlonzoCompatible(Box::new(Cow::Owned(input))),
+            MultiEraOutput::Conway(Box::new(Cow::Owned(output))),
+        )])
+    }
+
+    // Encodes a block holding `txs`, the ones at the `invalid` positions being
+    // flagged as failing phase-2 validation, with a header declaring the actual
+    // size and hash of the body unless `body_hash` overrides the latter. The tx
+    // bodies are framed as an indefinite-length array, which the body hash must
+    // cover as it appears on the wire.
+    fn mk_conway_block(
+        txs: Vec<(TransactionBody<'static>, WitnessSet<'static>)>,
+        invalid: Vec<u32>,
+        body_hash: Option<Hash<32>>,
+    ) -> Vec<u8> {
+        let (tx_bodies, tx_wits): (Vec<_>, Vec<_>) = txs.into_iter().unzip();
+        let auxiliary_data_set: BTreeMap<u32, AuxiliaryData> = BTreeMap::new();
+        let segments: [Vec<u8>; 4] = [
+            minicbor::to_vec(MaybeIndefArray::Indef(tx_bodies)).unwrap(),
+            minicbor::to_vec(&tx_wits).unwrap(),
+            minicbor::to_vec(&auxiliary_data_set).unwrap(),
+            minicbor::to_vec(&invalid).unwrap(),
+        ];
+        let mut hasher = Hasher::<256>::new();
+        for segment in segments.iter() {
+            hasher.input(Hasher::<256>::hash(segment).as_ref());
+        }
+        let header = Header {
+            header_body: HeaderBody {
+                block_number: 1,
+                slot: 72316896,
+                prev_hash: None,
+                issuer_vkey: vec![0; 32].into(),
+                vrf_vkey: vec![0; 32].into(),
+                vrf_result: VrfCert(vec![0; 64].into(), vec![0; 80].into()),
+                block_body_size: segments.iter().map(|x| x.len() as u64).sum(),
+                block_body_hash: body_hash.unwrap_or(hasher.finalize()),
+                operational_cert: OperationalCert {
+                    operational_cert_hot_vkey: vec![0; 32].into(),
+                    operational_cert_sequence_number: 0,
+                    operational_cert_kes_period: 0,
+                    operational_cert_sigma: vec![0; 64].into(),
+                },
+                protocol_version: (9, 0),
+            },
+            body_signature: vec![0; 448].into(),
+        };
+        let mut encoder = minicbor::Encoder::new(Vec::new());
+        encoder.array(2).unwrap().u16(7).unwrap();
+        encoder.array(5).unwrap().encode(&header).unwrap();
+        let mut cbor_bytes: Vec<u8> = encoder.into_writer();
+        for segment in segments {
+            cbor_bytes.extend(segment);
+        }
+        cbor_bytes
+    }
+
+    fn mk_block_env() -> Environment {
+        Environment {
+            prot_params: MultiEraProtocolParameters::Conway(ConwayProtParams {
+                protocol_version: (9, 0),
+                ..mk_mainnet_params_epoch_365()
+            }),
+            prot_magic: 764824073,
+            block_slot: 72316896,
+            network_id: 1,
+            acnt: Some(AccountState {
+                treasury: 261_254_564_000_000,
+                reserves: 0,
+            }),
+        }
+    }
+
+    fn mk_issuer_cold_key() -> SecretKey {
+        SecretKey::from([1; 32])
+    }
+
+    fn mk_issuer_kes_key() -> kes::SecretKey {
+        kes::SecretKey::from_seed(&[2; 32])
+    }
+
+    fn mk_issuer_vrf_key() -> vrf::SecretKey {
+        vrf::SecretKey::from([3; 32])
+    }
+
+    // Environment in which the issuer of `mk_issued_header` holds all the stake,
+    // and every slot has a leader so that any VRF output is elected.
+    fn mk_issuer_env() -> PraosEnvironment {
+        let vrf_vkey: vrf::PublicKey = mk_issuer_vrf_key().public_key();
+        PraosEnvironment {
+            epoch_nonce: Hash::new([4; 32]),
+            active_slot_coeff: RationalNumber {
+                numerator: 1,
+                denominator: 1,
+            },
+            slots_per_kes_period: 129600,
+            max_kes_evolutions: 62,
+            pool_distr: HashMap::from([(
+                Hasher::<224>::hash(mk_issuer_cold_key().public_key().as_ref()),
+                PoolStake {
+                    relative_stake: RationalNumber {
+                        numerator: 1,
+                        denominator: 1,
+                    },
+                    vrf_keyhash: Hasher::<256>::hash(vrf_vkey.as_ref()),
+                },
+            )]),
+        }
+    }
+
+    // A Conway header issued at the start of KES period 558 under an operational
+    // certificate with the given counter, proving leadership for `epoch_nonce`.
+    fn mk_issued_header(counter: u64, epoch_nonce: &Hash<32>) -> Vec<u8> {
+        let slot: u64 = 558 * 129600;
+        let cold_key: SecretKey = mk_issuer_cold_key();
+        let kes_key: kes::SecretKey = mk_issuer_kes_key();
+        let vrf_key: vrf::SecretKey = mk_issuer_vrf_key();
+        let mut ocert_signable: Vec<u8> = kes_key.public_key().as_ref().to_vec();
+        ocert_signable.extend(counter.to_be_bytes());
+        ocert_signable.extend(558u64.to_be_bytes());
+        let mut vrf_input = Hasher::<256>::new();
+        vrf_input.input(&slot.to_be_bytes());
+        vrf_input.input(epoch_nonce.as_ref());
+        let vrf_proof: vrf::Proof = vrf_key.prove(vrf_input.finalize()).unwrap();
+        let header_body = HeaderBody {
+            block_number: 1,
+            slot,
+            prev_hash: None,
+            issuer_vkey: cold_key.public_key().as_ref().to_vec().into(),
+            vrf_vkey: vrf_key.public_key().as_ref().to_vec().into(),
+            vrf_result: VrfCert(
+                vrf_proof.to_output().unwrap().as_ref().to_vec().into(),
+                vrf_proof.as_ref().to_vec().into(),
+            ),
+            block_body_size: 0,
+            block_body_hash: Hash::new([0; 32]),
+            operational_cert: OperationalCert {
+                operational_cert_hot_vkey: kes_key.public_key().as_ref().to_vec().into(),
+                operational_cert_sequence_number: counter,
+                operational_cert_kes_period: 558,
+                operational_cert_sigma: cold_key.sign(ocert_signable).as_ref().to_vec().into(),
+            },
+            protocol_version: (9, 0),
+        };
+        let body_signature: kes::Signature = kes_key.sign(minicbor::to_vec(&header_body).unwrap());
+        minicbor::to_vec(Header {
+            header_body,
+            body_signature: body_signature.as_ref().to_vec().into(),
+        })
+        .unwrap()
+    }
+
+    fn header_pool_id(header: &MultiEraHeader) -> Hash<28> {
+        Hasher::<224>::hash(header.issuer_vkey().unwrap())
+    }
+
+    fn header_kes_period(header: &MultiEraHeader) -> u64 {
+        header
+            .as_babbage()
+            .unwrap()
+            .header_body
+            .operational_cert
+            .operational_cert_kes_period
+    }
+
+    // Consensus parameters shared by mainnet and the public testnets, with the
+    // issuer of `header` registered with the VRF key of the header.
+    fn mk_praos_env(header: &MultiEraHeader) -> PraosEnvironment {
+        PraosEnvironment {
+            epoch_nonce: Hash::new([0; 32]),
+            active_slot_coeff: RationalNumber {
+                numerator: 1,
+                denominator: 20,
+            },
+            slots_per_kes_period: 129600,
+            max_kes_evolutions: 62,
+            pool_distr: HashMap::from([(
+                header_pool_id(header),
+                PoolStake {
+                    relative_stake: RationalNumber {
+                        numerator: 1,
+                        denominator: 1000,
+                    },
+                    vrf_keyhash: Hasher::<256>::hash(header.vrf_vkey().unwrap()),
+                },
+            )]),
+        }
+    }
+
     fn mk_mainnet_params_epoch_365() -> ConwayProtParams {
         ConwayProtParams {
             system_start: chrono::DateTime::parse_from_rfc3339("2017-09-23T21:44:51Z").unwrap(),
@@ -0,0 +1,111 @@
+mod genesis_tests {
+    use std::path::PathBuf;
+
+    use pallas_configs::{byron, shelley};
+    use pallas_crypto::hash::Hash;
+    use pallas_primitives::{Relay, StakeCredential};
+    use pallas_traverse::{Era, MultiEraOutput};
+    use pallas_validate::utils::{
+        genesis::{byron_utxos, shelley_cert_state, shelley_utxos},
+        TxoRef,
+    };
+
+    fn test_data(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../test_data")
+            .join(name)
+    }
+
+    fn golden_shelley_genesis() -> shelley::GenesisFile {
+        shelley::from_file(&test_data("golden-shelley-genesis.json")).unwrap()
+    }
+
+    #[test]
+    fn mainnet_byron_utxos() {
+        let genesis = byron::from_file(&test_data("mainnet-byron-genesis.json")).unwrap();
+        let utxos = byron_utxos(&genesis);
+
+        assert_eq!(
+            utxos.len(),
+            genesis.avvm_distr.len() + genesis.non_avvm_balances.len()
+        );
+
+        let supply: u64 = utxos
+            .values()
+            .map(|cbor| {
+                assert_eq!(cbor.0, Era::Byron);
+                MultiEraOutput::try_from(cbor).unwrap().value().coin()
+            })
+            .sum();
+
+        assert_eq!(supply, 31_112_484_745_000_000);
+    }
+
+    #[test]
+    fn golden_shelley_utxos() {
+        let utxos = shelley_utxos(&golden_shelley_genesis()).unwrap();
+
+        // as given by `cardano-cli genesis initial-txin`
+        let txo = TxoRef(
+            "04387d049935c19e1161f69ec57fbb8366cd221798bed24347ed602815fae05d"
+                .parse()
+                .unwrap(),
+            0,
+        );
+        let output = MultiEraOutput::try_from(&utxos[&txo]).unwrap();
+
+        assert_eq!(utxos.len(), 1);
+        assert_eq!(output.era(), Era::Shelley);
+        assert_eq!(output.value().coin(), 12157196);
+        assert_eq!(
+            output.address().unwrap().to_bech32().unwrap(),
+            "addr_test1qrsm4h32h9r95f8at64ykuugxqu3wvu0s5ay3vg6tlyevjh4e2flkegka00r69gt8c4vkxgf2vnnph3nsvhlkg5ukgxslee3tf"
+        );
+    }
+
+    #[test]
+    fn golden_shelley_cert_state() {
+        let cert_state = shelley_cert_state(&golden_shelley_genesis()).unwrap();
+
+        let pool_id: Hash<28> = "f583a45e4947c102091b96170ef50ef0cf8edb62666193a2163247bb"
+            .parse()
+            .unwrap();
+        let pool = &cert_state.pstate.pool_params[&pool_id];
+
+        assert_eq!(pool.pledge, 1);
+        assert_eq!(pool.cost, 5);
+        assert_eq!((pool.margin.numerator, pool.margin.denominator), (1, 4));
+        assert_eq!(
+            hex::encode(pool.reward_account.as_slice()),
+            "e04e88cc2d27c364aaf90648a87dfb95f8ee103ba67fa1f12f5e86c42a"
+        );
+        assert_eq!(pool.pool_owners.len(), 1);
+        assert_eq!(pool.pool_metadata.as_ref().unwrap().url, "best.pool.com");
+        assert_eq!(pool.relays.len(), 3);
+        assert!(pool.relays.contains(&Relay::SingleHostAddr(
+            Some(1234),
+            Some(vec![0, 0, 0, 0].into()),
+            Some(
+                hex::decode("b80d012000000a000000000023010000")
+                    .unwrap()
+                    .into()
+            ),
+        )));
+        assert!(pool
+            .relays
+            .contains(&Relay::MultiHostName("cool.domain.com".into())));
+
+        let staker = StakeCredential::AddrKeyhash(
+            "83a192dec0e8da2188e520d0c536a69a747cf173a3df16a6daa94d86"
+                .parse()
+                .unwrap(),
+        );
+        assert_eq!(
+            cert_state.dstate.delegations[&staker].to_string(),
+            "649eda82bf644d34a6925f24ea4c4c36d27e51de1b44ef47e3560be7"
+        );
+        assert_eq!(cert_state.dstate.rewards[&staker], 0);
+
+        assert_eq!(cert_state.dstate.gen_delegs.len(), 1);
+    }
+}
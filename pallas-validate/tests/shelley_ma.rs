@@ -21,8 +21,8 @@ mod shelley_ma_tests {
     use pallas_validate::{
         phase1::validate_txs,
         utils::{
-            AccountState, CertState, Environment, MultiEraProtocolParameters, ShelleyMAError,
-            ShelleyProtParams, UTxOs, ValidationError::*,
+            AccountState, CertState, Environment, GovState, MultiEraProtocolParameters,
+            ShelleyMAError, ShelleyProtParams, UTxOs, ValidationError::*,
         },
     };
     use std::str::FromStr;
@@ -108,7 +108,8 @@ mod shelley_ma_tests {
 
         let env: Environment = hardcoded_environment_values!();
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => (),
             Err(err) => panic!("Unexpected error ({:?})", err),
         }
@@ -132,7 +133,8 @@ mod shelley_ma_tests {
 
         let env: Environment = hardcoded_environment_values!();
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => (),
             Err(err) => panic!("Unexpected error ({:?})", err),
         }
@@ -167,7 +169,8 @@ mod shelley_ma_tests {
 
         let env: Environment = hardcoded_environment_values!();
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => (),
             Err(err) => panic!("Unexpected error ({:?})", err),
         }
@@ -191,7 +194,8 @@ mod shelley_ma_tests {
 
         let env: Environment = hardcoded_environment_values!();
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => (),
             Err(err) => panic!("Unexpected error ({:?})", err),
         }
@@ -215,7 +219,8 @@ mod shelley_ma_tests {
 
         let env: Environment = hardcoded_environment_values!();
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => (),
             Err(err) => panic!("Unexpected error ({:?})", err),
         }
@@ -239,6 +244,7 @@ mod shelley_ma_tests {
 
         let env: Environment = hardcoded_environment_values!();
         let mut cert_state: CertState = CertState::default();
+        let mut gov_state: GovState = GovState::default();
         let hash =
             Hash::from_str("FB2B631DB76384F64DD94B47F97FC8C2A206764C17A1DE7DA2F70E83").unwrap();
         cert_state
@@ -246,7 +252,7 @@ mod shelley_ma_tests {
             .rewards
             .insert(StakeCredential::AddrKeyhash(hash), 0);
 
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => (),
             Err(err) => panic!("Unexpected error ({:?})", err),
         };
@@ -275,11 +281,18 @@ mod shelley_ma_tests {
         );
 
         let mut cert_state: CertState = CertState::default();
+        let mut gov_state: GovState = GovState::default();
         cert_state
             .pstate
             .pool_params
             .insert(mary2_pool_operator(), mary2_pool_param());
-        match validate_txs(&[metx], &mary3_env(), &utxos, &mut cert_state) {
+        match validate_txs(
+            &[metx],
+            &mary3_env(),
+            &utxos,
+            &mut cert_state,
+            &mut gov_state,
+        ) {
             Ok(()) => (),
             Err(err) => panic!("Unexpected error ({:?})", err),
         }
@@ -399,7 +412,8 @@ mod shelley_ma_tests {
         env.block_slot = 19282133;
 
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => (),
             Err(err) => panic!("Unexpected error ({:?})", err),
         }
@@ -432,7 +446,8 @@ mod shelley_ma_tests {
 
         let env = hardcoded_environment_values!();
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("Inputs set should not be empty"),
             Err(err) => match err {
                 ShelleyMA(ShelleyMAError::TxInsEmpty) => (),
@@ -451,7 +466,8 @@ mod shelley_ma_tests {
 
         let env = hardcoded_environment_values!();
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("All inputs must be within the UTxO set"),
             Err(err) => match err {
                 ShelleyMA(ShelleyMAError::InputNotInUTxO) => (),
@@ -486,7 +502,8 @@ mod shelley_ma_tests {
 
         let env: Environment = hardcoded_environment_values!();
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("TTL must always be present in Shelley transactions"),
             Err(err) => match err {
                 ShelleyMA(ShelleyMAError::AlonzoCompNotShelley) => (),
@@ -514,7 +531,8 @@ mod shelley_ma_tests {
         env.block_slot = 9999999;
 
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("TTL cannot be exceeded"),
             Err(err) => match err {
                 ShelleyMA(ShelleyMAError::TTLExceeded) => (),
@@ -541,7 +559,8 @@ mod shelley_ma_tests {
         let env: Environment = hardcoded_environment_values!(max_transaction_size = 0);
 
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("Tx size exceeds max limit"),
             Err(err) => match err {
                 ShelleyMA(ShelleyMAError::MaxTxSizeExceeded) => (),
@@ -568,7 +587,8 @@ mod shelley_ma_tests {
 
         let env = hardcoded_environment_values!(min_utxo_value = 10000000000000);
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("Output amount must be above min lovelace value"),
             Err(err) => match err {
                 ShelleyMA(ShelleyMAError::MinLovelaceUnreached) => (),
@@ -604,7 +624,8 @@ mod shelley_ma_tests {
 
         let env: Environment = hardcoded_environment_values!();
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("Preservation of value property doesn't hold"),
             Err(err) => match err {
                 ShelleyMA(ShelleyMAError::PreservationOfValue) => (),
@@ -630,7 +651,8 @@ mod shelley_ma_tests {
 
         let env: Environment = hardcoded_environment_values!(minfee_b = 155381, minfee_a = 70);
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("Fee should not be below minimum"),
             Err(err) => match err {
                 ShelleyMA(ShelleyMAError::FeesBelowMin) => (),
@@ -687,7 +709,8 @@ mod shelley_ma_tests {
             )],
         );
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("Output with wrong network ID should be rejected"),
             Err(err) => match err {
                 ShelleyMA(ShelleyMAError::WrongNetworkID) => (),
@@ -716,7 +739,8 @@ mod shelley_ma_tests {
 
         let env: Environment = hardcoded_environment_values!();
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("Output with wrong network ID should be rejected"),
             Err(err) => match err {
                 ShelleyMA(ShelleyMAError::MetadataHash) => (),
@@ -754,7 +778,8 @@ mod shelley_ma_tests {
             )],
         );
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("Missing verification key witness"),
             Err(err) => match err {
                 ShelleyMA(ShelleyMAError::MissingVKWitness) => (),
@@ -797,7 +822,8 @@ mod shelley_ma_tests {
             )],
         );
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("Missing verification key witness"),
             Err(err) => match err {
                 ShelleyMA(ShelleyMAError::WrongSignature) => (),
@@ -835,7 +861,8 @@ mod shelley_ma_tests {
             )],
         );
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("Missing native script witness"),
             Err(err) => match err {
                 ShelleyMA(ShelleyMAError::MissingScriptWitness) => (),
@@ -875,7 +902,8 @@ mod shelley_ma_tests {
             )],
         );
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("The script is not satisfied"),
             Err(err) => match err {
                 ShelleyMA(ShelleyMAError::ScriptDenial) => (),
@@ -897,7 +925,14 @@ mod shelley_ma_tests {
         );
 
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &mary3_env(), &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(
+            &[metx],
+            &mary3_env(),
+            &utxos,
+            &mut cert_state,
+            &mut gov_state,
+        ) {
             Ok(()) => panic!("Pool is not registered"),
             Err(err) => match err {
                 ShelleyMA(ShelleyMAError::PoolNotRegistered) => (),
@@ -935,11 +970,18 @@ mod shelley_ma_tests {
         );
 
         let mut cert_state: CertState = CertState::default();
+        let mut gov_state: GovState = GovState::default();
         cert_state
             .pstate
             .pool_params
             .insert(mary2_pool_operator(), mary2_pool_param());
-        match validate_txs(&[metx], &mary3_env(), &utxos, &mut cert_state) {
+        match validate_txs(
+            &[metx],
+            &mary3_env(),
+            &utxos,
+            &mut cert_state,
+            &mut gov_state,
+        ) {
             Ok(()) => panic!("Staking key is not registered"),
             Err(err) => match err {
                 ShelleyMA(ShelleyMAError::KeyNotRegistered) => (),
@@ -966,7 +1008,8 @@ mod shelley_ma_tests {
 
         let env: Environment = hardcoded_environment_values!(max_transaction_size = 16384);
         let mut cert_state: CertState = CertState::default();
-        match validate_txs(&[metx], &env, &utxos, &mut cert_state) {
+        let mut gov_state: GovState = GovState::default();
+        match validate_txs(&[metx], &env, &utxos, &mut cert_state, &mut gov_state) {
             Ok(()) => panic!("MIR after the stability window"),
             Err(err) => match err {
                 ShelleyMA(ShelleyMAError::MIRCertificateTooLateinEpoch) => (),